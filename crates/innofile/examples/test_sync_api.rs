@@ -24,8 +24,8 @@ fn main() -> InnoFileResult<()> {
     arrow_writer.close()?;
 
     let file = Arc::from(file_system.open(path)?);
-    let mut arrow_reader = ArrowReaderBuilder::new().build_sync(file)?;
-    while let Some(record_batch) = arrow_reader.next() {
+    let arrow_reader = ArrowReaderBuilder::new().build_sync(file)?;
+    for record_batch in arrow_reader {
         println!("record_batch num_rows = {}", record_batch?.num_rows());
     }
 
@@ -1,23 +1,39 @@
-use arrow::datatypes::SchemaRef;
+#[cfg(any(feature = "orc", feature = "parquet"))]
+use std::sync::Arc;
+
+#[cfg(feature = "parquet")]
+use ::parquet::{
+    basic::{BrotliLevel, Compression, GzipLevel, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use arrow::datatypes::{Schema, SchemaRef};
 
 #[cfg(feature = "sync")]
 use crate::sync::{
-    arrow::{ArrowReader as SyncArrowReader, ArrowWriter as SyncArrowWriter},
+    arrow::{
+        ArrowReader as SyncArrowReader, ArrowWriter as SyncArrowWriter,
+        ProjectedReader as SyncProjectedReader,
+    },
     fs::FileRef as SyncFileRef,
 };
 #[cfg(feature = "tokio")]
 use crate::tokio::{
-    arrow::{ArrowReader as AsyncArrowReader, ArrowWriter as AsyncArrowWriter},
+    arrow::{
+        ArrowReader as AsyncArrowReader, ArrowWriter as AsyncArrowWriter,
+        ProjectedReader as AsyncProjectedReader,
+    },
     fs::FileRef as AsyncFileRef,
 };
 use crate::{
-    error::{InnoFileError, InnoFileResult},
+    error::{ErrorContext, InnoFileError, InnoFileResult, Operation},
     utils::path_extension,
     with_field,
 };
 
 #[cfg(feature = "csv")]
 use self::csv::*;
+#[cfg(all(feature = "ipc", feature = "sync"))]
+use self::ipc::*;
 #[cfg(feature = "json")]
 use self::json::*;
 #[cfg(feature = "orc")]
@@ -37,6 +53,14 @@ mod csv {
     };
 }
 
+// The Arrow IPC streaming format is only read and written synchronously.
+#[cfg(all(feature = "ipc", feature = "sync"))]
+mod ipc {
+    pub use crate::sync::arrow::ipc::{
+        ArrowIpcReader as SyncArrowIpcReader, ArrowIpcWriter as SyncArrowIpcWriter,
+    };
+}
+
 #[cfg(feature = "json")]
 mod json {
     #[cfg(feature = "sync")]
@@ -74,10 +98,130 @@ mod parquet {
     };
 }
 
+/// Number of records the schema of CSV and JSON files is inferred from by default.
+pub(crate) const DEFAULT_INFER_RECORDS: usize = 100;
+
+/// Uses `file_format` if given, or else the extension of `path`.
+fn resolve_file_format(file_format: Option<String>, path: &str) -> InnoFileResult<String> {
+    match file_format {
+        Some(file_format) => Ok(file_format),
+        None => path_extension(path)?.ok_or(InnoFileError::FileFormatNotFound),
+    }
+}
+
+/// Indices of the columns named `columns` in `schema`, in the order given.
+fn column_indices(schema: &Schema, columns: &[String]) -> InnoFileResult<Vec<usize>> {
+    Ok(columns
+        .iter()
+        .map(|column| schema.index_of(column))
+        .collect::<Result<_, _>>()?)
+}
+
+/// Whether `indices` select all columns of `schema` in order.
+fn selects_all(schema: &Schema, indices: &[usize]) -> bool {
+    indices.iter().copied().eq(0..schema.fields().len())
+}
+
+/// Parses a Parquet compression codec such as `snappy` or `zstd(3)`, using the default level of
+/// codecs with levels when none is given.
+#[cfg(feature = "parquet")]
+fn parquet_compression(compression: &str) -> InnoFileResult<Compression> {
+    Ok(match compression.to_lowercase().as_str() {
+        "gzip" => Compression::GZIP(GzipLevel::default()),
+        "brotli" => Compression::BROTLI(BrotliLevel::default()),
+        "zstd" => Compression::ZSTD(ZstdLevel::default()),
+        compression => compression.parse()?,
+    })
+}
+
+/// Fails for CSV options other than the default ones, which the asynchronous CSV format does not
+/// support yet.
+#[cfg(feature = "tokio")]
+fn check_async_csv_options(
+    csv_options: Option<&CsvOptions>,
+    file_format: &str,
+) -> InnoFileResult<()> {
+    match csv_options {
+        Some(csv_options) if *csv_options != CsvOptions::default() => {
+            Err(InnoFileError::OptionNotSupported {
+                option: "asynchronous CSV dialect".to_string(),
+                file_format: file_format.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Dialect of CSV files, by default with a header and the delimiter of the file format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub(crate) header: bool,
+    pub(crate) delimiter: Option<u8>,
+    pub(crate) quote: Option<u8>,
+    pub(crate) escape: Option<u8>,
+    pub(crate) comment: Option<u8>,
+    pub(crate) null_value: Option<String>,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            header: true,
+            delimiter: None,
+            quote: None,
+            escape: None,
+            comment: None,
+            null_value: None,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    /// Sets the field delimiter, instead of the one of the file format.
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = Some(delimiter);
+        self
+    }
+
+    pub fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = Some(quote);
+        self
+    }
+
+    pub fn with_escape(mut self, escape: u8) -> Self {
+        self.escape = Some(escape);
+        self
+    }
+
+    /// Sets the first character of lines skipped as comments when reading.
+    pub fn with_comment(mut self, comment: u8) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    /// Sets the value read as and written for nulls, instead of an empty field.
+    pub fn with_null_value(mut self, null_value: impl ToString) -> Self {
+        self.null_value = Some(null_value.to_string());
+        self
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ArrowReaderBuilder {
     file_format: Option<String>,
     schema: Option<SchemaRef>,
+    infer_records: Option<usize>,
+    csv_options: Option<CsvOptions>,
+    columns: Option<Vec<String>>,
 }
 
 impl ArrowReaderBuilder {
@@ -89,66 +233,195 @@ impl ArrowReaderBuilder {
 
     with_field!(with_schema, schema, SchemaRef);
 
+    with_field!(
+        /// Number of records to infer the schema of CSV and JSON files from, when no schema is
+        /// given.
+        with_infer_records,
+        infer_records,
+        usize
+    );
+
+    with_field!(
+        /// Dialect of CSV files. The asynchronous CSV format only supports the default one yet.
+        with_csv_options,
+        csv_options,
+        CsvOptions
+    );
+
+    with_field!(
+        /// Names of the columns to read, in the order given, instead of all columns. Parquet and
+        /// ORC files skip reading the other columns.
+        with_columns,
+        columns,
+        Vec<String>
+    );
+
     #[cfg(feature = "tokio")]
     pub async fn build_async(
         self,
         file: AsyncFileRef,
     ) -> InnoFileResult<Box<dyn AsyncArrowReader>> {
-        let file_format = if self.file_format.is_some() {
-            self.file_format
-        } else {
-            path_extension(file.path())?
-        };
-        Ok(match file_format {
-            None => Err(InnoFileError::FileFormatNotFound)?,
+        let file_format = resolve_file_format(self.file_format, file.path())?;
+        let context = ErrorContext::new(Operation::Open)
+            .with_path(file.path())
+            .with_format(&file_format);
+        let infer_records = self.infer_records.unwrap_or(DEFAULT_INFER_RECORDS);
+        let csv_options = self.csv_options.as_ref();
+        let columns = self.columns.as_deref();
+        Self::async_reader(
+            file,
+            file_format,
+            self.schema,
+            infer_records,
+            csv_options,
+            columns,
+        )
+        .await
+        .map_err(|error| error.with_context(context))
+    }
 
-            Some(file_format) => match file_format.to_lowercase().as_str() {
-                #[cfg(feature = "csv")]
-                "csv" | "dsv" | "psv" | "tsv" => {
-                    Box::new(AsyncArrowCsvReader::new(file, file_format, self.schema).await?)
+    #[cfg(feature = "tokio")]
+    async fn async_reader(
+        file: AsyncFileRef,
+        file_format: String,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
+        csv_options: Option<&CsvOptions>,
+        columns: Option<&[String]>,
+    ) -> InnoFileResult<Box<dyn AsyncArrowReader>> {
+        check_async_csv_options(csv_options, &file_format)?;
+        #[cfg(not(any(feature = "csv", feature = "json")))]
+        let _ = (schema, infer_records);
+        let arrow_reader: Box<dyn AsyncArrowReader> = match file_format.to_lowercase().as_str() {
+            #[cfg(feature = "csv")]
+            "csv" | "dsv" | "psv" | "tsv" => Box::new(
+                AsyncArrowCsvReader::with_infer_records(file, file_format, schema, infer_records)
+                    .await?,
+            ),
+
+            #[cfg(feature = "json")]
+            "json" => Box::new(
+                AsyncArrowJsonReader::with_infer_records(file, schema, infer_records).await?,
+            ),
+
+            // the schema is read first to only read the columns at their indices
+            #[cfg(feature = "orc")]
+            "orc" => Box::new(match columns {
+                Some(columns) => {
+                    let schema = AsyncArrowOrcReader::new(Arc::clone(&file)).await?.schema();
+                    let projection = column_indices(&schema, columns)?;
+                    AsyncArrowOrcReader::with_projection(file, &projection).await?
                 }
+                None => AsyncArrowOrcReader::new(file).await?,
+            }),
+
+            #[cfg(feature = "parquet")]
+            "parquet" => Box::new(match columns {
+                Some(columns) => {
+                    let schema = AsyncArrowParquetReader::new(Arc::clone(&file))
+                        .await?
+                        .schema();
+                    let projection = column_indices(&schema, columns)?;
+                    AsyncArrowParquetReader::with_projection(file, &projection).await?
+                }
+                None => AsyncArrowParquetReader::new(file).await?,
+            }),
 
-                #[cfg(feature = "json")]
-                "json" => Box::new(AsyncArrowJsonReader::new(file, self.schema).await?),
-
-                #[cfg(feature = "orc")]
-                "orc" => Box::new(AsyncArrowOrcReader::new(file).await?),
-
-                #[cfg(feature = "parquet")]
-                "parquet" => Box::new(AsyncArrowParquetReader::new(file).await?),
-
-                _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
-            },
+            _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
+        };
+        let Some(columns) = columns else {
+            return Ok(arrow_reader);
+        };
+        let indices = column_indices(&arrow_reader.schema(), columns)?;
+        Ok(match selects_all(&arrow_reader.schema(), &indices) {
+            true => arrow_reader,
+            false => Box::new(AsyncProjectedReader::new(arrow_reader, indices)?),
         })
     }
 
     #[cfg(feature = "sync")]
     pub fn build_sync(self, file: SyncFileRef) -> InnoFileResult<Box<dyn SyncArrowReader>> {
-        let file_format = if self.file_format.is_some() {
-            self.file_format
-        } else {
-            path_extension(file.path())?
-        };
-        Ok(match file_format {
-            None => Err(InnoFileError::FileFormatNotFound)?,
+        let file_format = resolve_file_format(self.file_format, file.path())?;
+        let context = ErrorContext::new(Operation::Open)
+            .with_path(file.path())
+            .with_format(&file_format);
+        let infer_records = self.infer_records.unwrap_or(DEFAULT_INFER_RECORDS);
+        let csv_options = self.csv_options.unwrap_or_default();
+        let columns = self.columns.as_deref();
+        Self::sync_reader(
+            file,
+            file_format,
+            self.schema,
+            infer_records,
+            csv_options,
+            columns,
+        )
+        .map_err(|error| error.with_context(context))
+    }
 
-            Some(file_format) => match file_format.to_lowercase().as_str() {
-                #[cfg(feature = "csv")]
-                "csv" | "dsv" | "psv" | "tsv" => {
-                    Box::new(SyncArrowCsvReader::new(file, file_format, self.schema)?)
+    #[cfg(feature = "sync")]
+    fn sync_reader(
+        file: SyncFileRef,
+        file_format: String,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
+        csv_options: CsvOptions,
+        columns: Option<&[String]>,
+    ) -> InnoFileResult<Box<dyn SyncArrowReader>> {
+        #[cfg(not(any(feature = "csv", feature = "json")))]
+        let _ = (schema, infer_records);
+        #[cfg(not(feature = "csv"))]
+        let _ = csv_options;
+        let arrow_reader: Box<dyn SyncArrowReader> = match file_format.to_lowercase().as_str() {
+            #[cfg(feature = "csv")]
+            "csv" | "dsv" | "psv" | "tsv" => Box::new(SyncArrowCsvReader::with_options(
+                file,
+                file_format,
+                schema,
+                infer_records,
+                &csv_options,
+            )?),
+
+            #[cfg(feature = "ipc")]
+            "arrows" => Box::new(SyncArrowIpcReader::new(file)?),
+
+            #[cfg(feature = "json")]
+            "json" => Box::new(SyncArrowJsonReader::with_infer_records(
+                file,
+                schema,
+                infer_records,
+            )?),
+
+            // the schema is read first to only read the columns at their indices
+            #[cfg(feature = "orc")]
+            "orc" => Box::new(match columns {
+                Some(columns) => {
+                    let schema = SyncArrowOrcReader::new(Arc::clone(&file))?.schema();
+                    let projection = column_indices(&schema, columns)?;
+                    SyncArrowOrcReader::with_projection(file, &projection)?
                 }
+                None => SyncArrowOrcReader::new(file)?,
+            }),
+
+            #[cfg(feature = "parquet")]
+            "parquet" => Box::new(match columns {
+                Some(columns) => {
+                    let schema = SyncArrowParquetReader::new(Arc::clone(&file))?.schema();
+                    let projection = column_indices(&schema, columns)?;
+                    SyncArrowParquetReader::with_projection(file, &projection)?
+                }
+                None => SyncArrowParquetReader::new(file)?,
+            }),
 
-                #[cfg(feature = "json")]
-                "json" => Box::new(SyncArrowJsonReader::new(file, self.schema)?),
-
-                #[cfg(feature = "orc")]
-                "orc" => Box::new(SyncArrowOrcReader::new(file)?),
-
-                #[cfg(feature = "parquet")]
-                "parquet" => Box::new(SyncArrowParquetReader::new(file)?),
-
-                _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
-            },
+            _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
+        };
+        let Some(columns) = columns else {
+            return Ok(arrow_reader);
+        };
+        let indices = column_indices(&arrow_reader.schema(), columns)?;
+        Ok(match selects_all(&arrow_reader.schema(), &indices) {
+            true => arrow_reader,
+            false => Box::new(SyncProjectedReader::new(arrow_reader, indices)?),
         })
     }
 }
@@ -157,6 +430,10 @@ impl ArrowReaderBuilder {
 pub struct ArrowWriterBuilder {
     file_format: Option<String>,
     schema: SchemaRef,
+    append: Option<bool>,
+    compression: Option<String>,
+    row_group_size: Option<usize>,
+    csv_options: Option<CsvOptions>,
 }
 
 impl ArrowWriterBuilder {
@@ -164,71 +441,194 @@ impl ArrowWriterBuilder {
         Self {
             file_format: None,
             schema,
+            append: None,
+            compression: None,
+            row_group_size: None,
+            csv_options: None,
         }
     }
 
     with_field!(with_file_format, file_format, String);
 
+    with_field!(
+        /// Appends to the file, which must be opened for appending, without repeating headers.
+        /// Only supported by the CSV and line delimited JSON formats.
+        with_append,
+        append,
+        bool
+    );
+
+    with_field!(
+        /// Compression codec such as `snappy` or `zstd(3)`, only supported by the Parquet format.
+        with_compression,
+        compression,
+        String
+    );
+
+    with_field!(
+        /// Maximum number of rows of each row group, only supported by the Parquet format.
+        with_row_group_size,
+        row_group_size,
+        usize
+    );
+
+    with_field!(
+        /// Dialect of CSV files. The asynchronous CSV format only supports the default one yet.
+        with_csv_options,
+        csv_options,
+        CsvOptions
+    );
+
     #[cfg(feature = "tokio")]
     pub async fn build_async(
         self,
         file: AsyncFileRef,
     ) -> InnoFileResult<Box<dyn AsyncArrowWriter>> {
-        let file_format = if self.file_format.is_some() {
-            self.file_format
+        let file_format = resolve_file_format(self.file_format.clone(), file.path())?;
+        let append = self.append.unwrap_or_default();
+        let operation = if append {
+            Operation::Append
         } else {
-            path_extension(file.path())?
+            Operation::Create
         };
-        Ok(match file_format {
-            None => Err(InnoFileError::FileFormatNotFound)?,
-
-            Some(file_format) => match file_format.to_lowercase().as_str() {
-                #[cfg(feature = "csv")]
-                "csv" | "dsv" | "psv" | "tsv" => {
-                    Box::new(AsyncArrowCsvWriter::new(file, file_format).await?)
-                }
-
-                #[cfg(feature = "json")]
-                "json" => Box::new(AsyncArrowJsonWriter::new_line_delimited(file).await?),
-
-                #[cfg(feature = "orc")]
-                "orc" => Box::new(AsyncArrowOrcWriter::new(file, self.schema).await?),
-
-                #[cfg(feature = "parquet")]
-                "parquet" => Box::new(AsyncArrowParquetWriter::new(file, self.schema).await?),
+        let context = ErrorContext::new(operation)
+            .with_path(file.path())
+            .with_format(&file_format);
+        self.async_writer(file, file_format, append)
+            .await
+            .map_err(|error| error.with_context(context))
+    }
 
-                _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
-            },
+    #[cfg(feature = "tokio")]
+    async fn async_writer(
+        self,
+        file: AsyncFileRef,
+        file_format: String,
+        append: bool,
+    ) -> InnoFileResult<Box<dyn AsyncArrowWriter>> {
+        self.check_options(&file_format)?;
+        check_async_csv_options(self.csv_options.as_ref(), &file_format)?;
+        Ok(match file_format.to_lowercase().as_str() {
+            #[cfg(feature = "csv")]
+            "csv" | "dsv" | "psv" | "tsv" if append => {
+                Box::new(AsyncArrowCsvWriter::new_append(file, file_format).await?)
+            }
+
+            #[cfg(feature = "csv")]
+            "csv" | "dsv" | "psv" | "tsv" => {
+                Box::new(AsyncArrowCsvWriter::new(file, file_format).await?)
+            }
+
+            #[cfg(feature = "json")]
+            "json" => Box::new(AsyncArrowJsonWriter::new_line_delimited(file).await?),
+
+            _ if append => Err(InnoFileError::AppendNotSupported(file_format))?,
+
+            #[cfg(feature = "orc")]
+            "orc" => Box::new(AsyncArrowOrcWriter::new(file, self.schema).await?),
+
+            #[cfg(feature = "parquet")]
+            "parquet" => {
+                let properties = self.parquet_properties()?;
+                Box::new(
+                    AsyncArrowParquetWriter::with_properties(file, self.schema, properties).await?,
+                )
+            }
+
+            _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
         })
     }
 
     #[cfg(feature = "sync")]
     pub fn build_sync(self, file: SyncFileRef) -> InnoFileResult<Box<dyn SyncArrowWriter>> {
-        let file_format = if self.file_format.is_some() {
-            self.file_format
+        let file_format = resolve_file_format(self.file_format.clone(), file.path())?;
+        let append = self.append.unwrap_or_default();
+        let operation = if append {
+            Operation::Append
         } else {
-            path_extension(file.path())?
+            Operation::Create
         };
-        Ok(match file_format {
-            None => Err(InnoFileError::FileFormatNotFound)?,
-
-            Some(file_format) => match file_format.to_lowercase().as_str() {
-                #[cfg(feature = "csv")]
-                "csv" | "dsv" | "psv" | "tsv" => {
-                    Box::new(SyncArrowCsvWriter::new(file, file_format)?)
-                }
-
-                #[cfg(feature = "json")]
-                "json" => Box::new(SyncArrowJsonWriter::new_line_delimited(file)?),
+        let context = ErrorContext::new(operation)
+            .with_path(file.path())
+            .with_format(&file_format);
+        self.sync_writer(file, file_format, append)
+            .map_err(|error| error.with_context(context))
+    }
 
-                #[cfg(feature = "orc")]
-                "orc" => Box::new(SyncArrowOrcWriter::new(file, self.schema)?),
+    #[cfg(feature = "sync")]
+    fn sync_writer(
+        self,
+        file: SyncFileRef,
+        file_format: String,
+        append: bool,
+    ) -> InnoFileResult<Box<dyn SyncArrowWriter>> {
+        self.check_options(&file_format)?;
+        #[cfg(feature = "csv")]
+        let csv_options = self.csv_options.clone().unwrap_or_default();
+        Ok(match file_format.to_lowercase().as_str() {
+            #[cfg(feature = "csv")]
+            "csv" | "dsv" | "psv" | "tsv" if append => Box::new(
+                SyncArrowCsvWriter::new_append_with_options(file, file_format, &csv_options)?,
+            ),
+
+            #[cfg(feature = "csv")]
+            "csv" | "dsv" | "psv" | "tsv" => Box::new(SyncArrowCsvWriter::with_options(
+                file,
+                file_format,
+                &csv_options,
+            )?),
+
+            #[cfg(feature = "json")]
+            "json" => Box::new(SyncArrowJsonWriter::new_line_delimited(file)?),
+
+            _ if append => Err(InnoFileError::AppendNotSupported(file_format))?,
+
+            #[cfg(feature = "ipc")]
+            "arrows" => Box::new(SyncArrowIpcWriter::new(file, self.schema)?),
+
+            #[cfg(feature = "orc")]
+            "orc" => Box::new(SyncArrowOrcWriter::new(file, self.schema)?),
+
+            #[cfg(feature = "parquet")]
+            "parquet" => {
+                let properties = self.parquet_properties()?;
+                Box::new(SyncArrowParquetWriter::with_properties(
+                    file,
+                    self.schema,
+                    properties,
+                )?)
+            }
+
+            _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
+        })
+    }
 
-                #[cfg(feature = "parquet")]
-                "parquet" => Box::new(SyncArrowParquetWriter::new(file, self.schema)?),
+    /// Fails for options set but not supported by `file_format`.
+    fn check_options(&self, file_format: &str) -> InnoFileResult<()> {
+        let options = [
+            ("compression", self.compression.is_some()),
+            ("row group size", self.row_group_size.is_some()),
+        ];
+        match options.into_iter().find(|(_, set)| *set) {
+            Some((option, _)) if !file_format.eq_ignore_ascii_case("parquet") => {
+                Err(InnoFileError::OptionNotSupported {
+                    option: option.to_string(),
+                    file_format: file_format.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
 
-                _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
-            },
-        })
+    #[cfg(feature = "parquet")]
+    fn parquet_properties(&self) -> InnoFileResult<WriterProperties> {
+        let mut builder = WriterProperties::builder();
+        if let Some(compression) = &self.compression {
+            builder = builder.set_compression(parquet_compression(compression)?);
+        }
+        if let Some(row_group_size) = self.row_group_size {
+            builder = builder.set_max_row_group_size(row_group_size);
+        }
+        Ok(builder.build())
     }
 }
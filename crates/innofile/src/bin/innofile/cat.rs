@@ -0,0 +1,288 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use arrow::{array::RecordBatch, compute::concat_batches, datatypes::SchemaRef};
+use clap::{Args, ValueEnum};
+use innofile::{
+    arrow::ArrowReaderBuilder,
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+};
+
+use crate::open_file;
+
+/// Number of rows printed by `head` and `tail` without `--limit`.
+const DEFAULT_LIMIT: usize = 10;
+
+/// Maximum number of rows printed as a table, which are kept in memory until all are printed.
+const MAX_TABLE_ROWS: usize = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rows {
+    /// All rows from the start
+    All,
+    /// First rows, [`DEFAULT_LIMIT`] by default
+    Head,
+    /// Last rows, [`DEFAULT_LIMIT`] by default
+    Tail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Pretty printed table
+    Table,
+    /// CSV with header
+    #[cfg(feature = "csv")]
+    Csv,
+    /// Newline delimited JSON
+    #[cfg(feature = "json")]
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct CatArgs {
+    /// File path to print rows of, or `-` for standard input
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// File format to read, required to read from standard input with `-`
+    #[arg(long)]
+    pub from_format: Option<String>,
+
+    /// Maximum number of rows to print
+    #[arg(long, short = 'n')]
+    pub limit: Option<usize>,
+
+    /// Number of rows to skip, from the start or from the end for `tail`
+    #[arg(long, default_value_t = 0)]
+    pub offset: usize,
+
+    /// Comma separated columns to print, all columns by default
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Output format of the rows
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    pub format: OutputFormat,
+}
+
+pub fn do_cat(args: &CatArgs, rows: Rows, profile: Option<&str>) -> InnoFileResult<()> {
+    let file = open_file(&args.path, args.from_format.as_deref(), profile)?;
+    let columns = (!args.columns.is_empty()).then(|| args.columns.clone());
+    let arrow_reader = ArrowReaderBuilder::new()
+        .with_file_format(args.from_format.as_deref())
+        .with_columns(columns)
+        .build_sync(file)?;
+
+    let schema = arrow_reader.schema();
+    let read_context = || ErrorContext::new(Operation::Read).with_path(&args.path);
+    let batches = arrow_reader.map(|batch| batch.with_context(read_context));
+
+    let mut printer = BatchPrinter::new(schema, args.format, io::stdout().lock());
+    match rows {
+        Rows::All => head(batches, args.offset, args.limit, &mut printer)?,
+        Rows::Head => head(
+            batches,
+            args.offset,
+            Some(args.limit.unwrap_or(DEFAULT_LIMIT)),
+            &mut printer,
+        )?,
+        Rows::Tail => {
+            let limit = args.limit.unwrap_or(DEFAULT_LIMIT);
+            if let Some(batch) = tail(batches, args.offset, limit)? {
+                printer.print(&batch)?;
+            }
+        }
+    }
+    printer.finish()
+}
+
+/// Prints rows after skipping `offset` rows, stopping to read once `limit` rows are printed.
+fn head<W: Write>(
+    batches: impl Iterator<Item = InnoFileResult<RecordBatch>>,
+    mut offset: usize,
+    limit: Option<usize>,
+    printer: &mut BatchPrinter<W>,
+) -> InnoFileResult<()> {
+    let mut remaining = limit.unwrap_or(usize::MAX);
+    for batch in batches {
+        if remaining == 0 {
+            break;
+        }
+        let batch = batch?;
+        let skipped = offset.min(batch.num_rows());
+        offset -= skipped;
+        let length = (batch.num_rows() - skipped).min(remaining);
+        if length > 0 {
+            printer.print(&batch.slice(skipped, length))?;
+            remaining -= length;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the last `limit` rows before the last `offset` rows, keeping only the batches needed
+/// in memory.
+fn tail(
+    batches: impl Iterator<Item = InnoFileResult<RecordBatch>>,
+    offset: usize,
+    limit: usize,
+) -> InnoFileResult<Option<RecordBatch>> {
+    let needed = offset.saturating_add(limit);
+    let mut kept = VecDeque::<RecordBatch>::new();
+    let mut kept_rows = 0;
+    for batch in batches {
+        let batch = batch?;
+        kept_rows += batch.num_rows();
+        kept.push_back(batch);
+        while let Some(front) = kept.front() {
+            if kept_rows - front.num_rows() < needed {
+                break;
+            }
+            kept_rows -= front.num_rows();
+            kept.pop_front();
+        }
+    }
+
+    let Some(first) = kept.front() else {
+        return Ok(None);
+    };
+    let batch = concat_batches(&first.schema(), &kept)?;
+    let end = batch.num_rows().saturating_sub(offset);
+    let start = end.saturating_sub(limit);
+    Ok(Some(batch.slice(start, end - start)))
+}
+
+/// Prints record batches to a writer in an [`OutputFormat`].
+///
+/// Tables are only printed on [`BatchPrinter::finish`] since column widths depend on all rows, so
+/// they are limited to [`MAX_TABLE_ROWS`] rows.
+struct BatchPrinter<W: Write> {
+    schema: SchemaRef,
+    format: OutputFormat,
+    writer: W,
+    batches: Vec<RecordBatch>,
+    #[cfg(feature = "csv")]
+    header_written: bool,
+}
+
+impl<W: Write> BatchPrinter<W> {
+    fn new(schema: SchemaRef, format: OutputFormat, writer: W) -> Self {
+        Self {
+            schema,
+            format,
+            writer,
+            batches: vec![],
+            #[cfg(feature = "csv")]
+            header_written: false,
+        }
+    }
+
+    fn print(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
+        match self.format {
+            OutputFormat::Table => {
+                let rows = self
+                    .batches
+                    .iter()
+                    .map(RecordBatch::num_rows)
+                    .sum::<usize>();
+                if rows + batch.num_rows() > MAX_TABLE_ROWS {
+                    Err(anyhow!(
+                        "Printing more than {MAX_TABLE_ROWS} rows as a table not supported, use \
+                         --limit or --format csv or json instead"
+                    ))?;
+                }
+                self.batches.push(batch.clone());
+            }
+            #[cfg(feature = "csv")]
+            OutputFormat::Csv => {
+                // header is written along with the first batch only
+                let mut csv_writer = arrow_csv::WriterBuilder::new()
+                    .with_header(!self.header_written)
+                    .build(&mut self.writer);
+                csv_writer.write(batch)?;
+                self.header_written = true;
+            }
+            #[cfg(feature = "json")]
+            OutputFormat::Json => {
+                let mut json_writer = arrow_json::LineDelimitedWriter::new(&mut self.writer);
+                json_writer.write(batch)?;
+                json_writer.finish()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(mut self) -> InnoFileResult<()> {
+        match self.format {
+            OutputFormat::Table => {
+                if self.batches.is_empty() {
+                    self.batches
+                        .push(RecordBatch::new_empty(Arc::clone(&self.schema)));
+                }
+                let table = arrow::util::pretty::pretty_format_batches(&self.batches)?;
+                writeln!(self.writer, "{}", table)?;
+            }
+            #[cfg(feature = "csv")]
+            OutputFormat::Csv if !self.header_written => {
+                self.print(&RecordBatch::new_empty(Arc::clone(&self.schema)))?;
+            }
+            #[allow(unreachable_patterns)]
+            _ => {}
+        }
+        Ok(self.writer.flush()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::Int64Array,
+        datatypes::{DataType, Field, Schema},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_head_and_tail() -> InnoFileResult<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let batches = || {
+            [0..3, 3..5, 5..9].into_iter().map(|ids| {
+                let ids = Int64Array::from_iter_values(ids);
+                Ok(RecordBatch::try_new(
+                    Arc::clone(&schema),
+                    vec![Arc::new(ids)],
+                )?)
+            })
+        };
+        let ids = |batch: &RecordBatch| {
+            let ids = batch.column(0).as_any().downcast_ref::<Int64Array>();
+            ids.unwrap().values().to_vec()
+        };
+
+        let mut printer = BatchPrinter::new(Arc::clone(&schema), OutputFormat::Table, vec![]);
+        head(batches(), 2, Some(4), &mut printer)?;
+        let printed = printer.batches.iter().flat_map(ids).collect::<Vec<_>>();
+        assert_eq!(printed, vec![2, 3, 4, 5]);
+        printer.finish()?;
+
+        let batch = tail(batches(), 1, 4)?.unwrap();
+        assert_eq!(ids(&batch), vec![4, 5, 6, 7]);
+        let batch = tail(batches(), 0, 20)?.unwrap();
+        assert_eq!(ids(&batch), (0..9).collect::<Vec<_>>());
+        assert!(tail(std::iter::empty(), 0, 1)?.is_none());
+
+        #[cfg(feature = "csv")]
+        {
+            let mut printer = BatchPrinter::new(Arc::clone(&schema), OutputFormat::Csv, vec![]);
+            head(batches(), 7, None, &mut printer)?;
+            let output = String::from_utf8(printer.writer.clone()).unwrap();
+            assert_eq!(output, "id\n7\n8\n");
+        }
+
+        Ok(())
+    }
+}
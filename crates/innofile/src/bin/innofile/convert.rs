@@ -0,0 +1,663 @@
+use std::{
+    num::NonZeroUsize,
+    path::Path,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use anyhow::anyhow;
+use arrow::{
+    array::RecordBatch,
+    compute::{cast_with_options, CastOptions},
+    datatypes::{Schema, SchemaRef},
+};
+use clap::Args;
+use innofile::{
+    arrow::{ArrowReaderBuilder, ArrowWriterBuilder, CsvOptions},
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+    io::SyncCloseable as _,
+    sync::fs::{stdio::STDIO_PATH, FileRef, FileSystem},
+};
+
+use crate::{
+    create_file, file_system,
+    filter::Filter,
+    fs::{expand, glob_base, join, name},
+    open_file,
+};
+
+/// Extensions of the file formats read and written by converting.
+const FILE_FORMATS: [&str; 8] = [
+    "csv", "dsv", "psv", "tsv", "json", "orc", "parquet", "arrows",
+];
+
+#[derive(Debug, Args)]
+pub struct ConvertArgs {
+    /// File path or glob pattern to convert from, or `-` for standard input
+    #[arg(long = "from")]
+    pub from_path: String,
+
+    /// File path to convert to, directory for a glob pattern or a path ending with `/`, or `-`
+    /// for standard output
+    #[arg(long = "to")]
+    pub to_path: String,
+
+    /// File format to convert from, required to read from standard input
+    #[arg(long)]
+    pub from_format: Option<String>,
+
+    /// File format to convert to, required to convert to a directory or standard output
+    #[arg(long, visible_alias = "format")]
+    pub to_format: Option<String>,
+
+    /// Number of files converted in parallel
+    #[arg(long, short, default_value_t = NonZeroUsize::MIN)]
+    pub jobs: NonZeroUsize,
+
+    /// Convert to a directory even if outputs are newer than their inputs
+    #[arg(long)]
+    pub force: bool,
+
+    /// Comma separated columns to convert, all columns by default
+    #[arg(long, value_delimiter = ',')]
+    pub columns: Vec<String>,
+
+    /// Filter expression of the rows to convert, such as `age >= 18 AND name IS NOT NULL`
+    #[arg(long = "where", value_name = "EXPRESSION")]
+    pub filter: Option<Filter>,
+
+    /// Arrow JSON schema file, as printed by `schema --format json`, to read and cast columns as
+    #[arg(long = "schema", value_name = "PATH")]
+    pub schema_path: Option<String>,
+
+    /// Comma separated columns to rename, as `old=new`
+    #[arg(long, value_delimiter = ',', value_parser = parse_rename)]
+    pub rename: Vec<(String, String)>,
+
+    /// Compression codec of Parquet files, such as `snappy`, `zstd` or `zstd(9)`
+    #[arg(long)]
+    pub compression: Option<String>,
+
+    /// Maximum number of rows of each row group of Parquet files
+    #[arg(long)]
+    pub row_group_size: Option<usize>,
+
+    #[command(flatten)]
+    pub csv: CsvArgs,
+}
+
+/// Dialect of the CSV files read and written.
+#[derive(Debug, Args)]
+#[command(next_help_heading = "CSV options")]
+pub struct CsvArgs {
+    /// Read and write CSV files without a header
+    #[arg(long)]
+    pub no_header: bool,
+
+    /// Field delimiter, instead of the one of the file extension
+    #[arg(long, value_parser = parse_char)]
+    pub delimiter: Option<u8>,
+
+    /// Quote character
+    #[arg(long, value_parser = parse_char)]
+    pub quote: Option<u8>,
+
+    /// Escape character
+    #[arg(long, value_parser = parse_char)]
+    pub escape: Option<u8>,
+
+    /// First character of lines skipped as comments
+    #[arg(long, value_parser = parse_char)]
+    pub comment: Option<u8>,
+
+    /// Value read as and written for nulls, instead of an empty field
+    #[arg(long)]
+    pub null_value: Option<String>,
+}
+
+impl CsvArgs {
+    pub fn options(&self) -> CsvOptions {
+        let mut options = CsvOptions::new().with_header(!self.no_header);
+        if let Some(delimiter) = self.delimiter {
+            options = options.with_delimiter(delimiter);
+        }
+        if let Some(quote) = self.quote {
+            options = options.with_quote(quote);
+        }
+        if let Some(escape) = self.escape {
+            options = options.with_escape(escape);
+        }
+        if let Some(comment) = self.comment {
+            options = options.with_comment(comment);
+        }
+        if let Some(null_value) = &self.null_value {
+            options = options.with_null_value(null_value);
+        }
+        options
+    }
+}
+
+fn parse_rename(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((old, new)) if !old.is_empty() && !new.is_empty() => {
+            Ok((old.to_string(), new.to_string()))
+        }
+        _ => Err(format!("expected `old=new`, found `{s}`")),
+    }
+}
+
+/// Parses a single ASCII character, or `\t` for a tab.
+fn parse_char(s: &str) -> Result<u8, String> {
+    match s.as_bytes() {
+        b"\\t" => Ok(b'\t'),
+        [c] if c.is_ascii() => Ok(*c),
+        _ => Err(format!("expected a single ASCII character, found `{s}`")),
+    }
+}
+
+/// Options of converting each file, shared by all files of a batch.
+struct Conversion<'a> {
+    args: &'a ConvertArgs,
+    schema: Option<SchemaRef>,
+    csv_options: CsvOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    Converted,
+    Skipped,
+    Failed,
+}
+
+pub fn do_convert(args: &ConvertArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let conversion = Conversion {
+        args,
+        schema: match &args.schema_path {
+            Some(path) => Some(read_schema(path, profile)?),
+            None => None,
+        },
+        csv_options: args.csv.options(),
+    };
+    let base = glob_base(&args.from_path);
+    if base.is_none() && !args.to_path.ends_with('/') {
+        let from_file = open_file(&args.from_path, args.from_format.as_deref(), profile)?;
+        let format = args.to_format.as_deref();
+        let to_file = create_file(&args.to_path, format, profile)?;
+        return conversion.convert(from_file, to_file, format);
+    }
+
+    if args.to_path == STDIO_PATH {
+        Err(anyhow!(
+            "Converting multiple files to standard output not supported"
+        ))?
+    }
+    let format = args
+        .to_format
+        .as_deref()
+        .ok_or_else(|| anyhow!("--to-format is required when converting to a directory"))?;
+    let from_file_system = file_system(&args.from_path, profile)?;
+    let to_file_system = file_system(&args.to_path, profile)?;
+    // directories matched by the pattern are neither converted nor counted
+    let mut tasks = vec![];
+    for from_path in expand(&from_file_system, &args.from_path)? {
+        let from_status = from_file_system.status(&from_path)?;
+        if from_status.is_dir {
+            continue;
+        }
+        let relative = match &base {
+            Some(base) => from_path[base.len()..].trim_start_matches('/'),
+            None => name(&from_path),
+        };
+        let to_path = join(&args.to_path, &replace_extension(relative, format));
+        tasks.push((from_status, to_path));
+    }
+
+    let next = AtomicUsize::new(0);
+    let workers = args.jobs.get().min(tasks.len());
+    let outcomes = thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut outcomes = vec![];
+                    while let Some((from_status, to_path)) =
+                        tasks.get(next.fetch_add(1, Ordering::Relaxed))
+                    {
+                        let result = conversion.convert_if_outdated(
+                            &from_file_system,
+                            from_status,
+                            &to_file_system,
+                            to_path,
+                            format,
+                        );
+                        outcomes.push(match result {
+                            Ok(true) => Outcome::Converted,
+                            Ok(false) => Outcome::Skipped,
+                            Err(error) => {
+                                eprintln!("Failed to convert {}: {error}", from_status.path);
+                                Outcome::Failed
+                            }
+                        });
+                    }
+                    outcomes
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("conversion thread panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let count = |outcome| outcomes.iter().filter(|o| **o == outcome).count();
+    let failed = count(Outcome::Failed);
+    eprintln!(
+        "Converted {}, skipped {} up-to-date, failed {} of {} files",
+        count(Outcome::Converted),
+        count(Outcome::Skipped),
+        failed,
+        tasks.len()
+    );
+    match failed {
+        0 => Ok(()),
+        _ => Err(anyhow!("{failed} of {} conversions failed", tasks.len()))?,
+    }
+}
+
+/// Reads an Arrow JSON schema file, as printed by `schema --format json`.
+pub fn read_schema(path: &str, profile: Option<&str>) -> InnoFileResult<SchemaRef> {
+    let reader = file_system(path, profile)?.open(path)?.reader()?;
+    let schema: Schema = serde_json::from_reader(reader)
+        .map_err(anyhow::Error::from)
+        .with_context(|| ErrorContext::new(Operation::Read).with_path(path))?;
+    Ok(Arc::new(schema))
+}
+
+impl Conversion<'_> {
+    /// Converts the file of `from_status` unless `to_path` was modified after it, returning
+    /// whether it converted.
+    fn convert_if_outdated(
+        &self,
+        from_file_system: &dyn FileSystem,
+        from_status: &FileStatus,
+        to_file_system: &dyn FileSystem,
+        to_path: &str,
+        format: &str,
+    ) -> InnoFileResult<bool> {
+        if !self.args.force {
+            let to_modified = match to_file_system.status(to_path) {
+                Ok(status) => status.last_modified,
+                Err(error) if error.is_not_found() => None,
+                Err(error) => Err(error)?,
+            };
+            if let (Some(from), Some(to)) = (from_status.last_modified, to_modified) {
+                if to >= from {
+                    return Ok(false);
+                }
+            }
+        }
+        // create atomically, so a failed conversion leaves no output behind
+        let from_file = Arc::from(from_file_system.open(&from_status.path)?);
+        let to_file = Arc::from(to_file_system.create_atomic(to_path)?);
+        self.convert(from_file, to_file, Some(format))?;
+        Ok(true)
+    }
+
+    fn convert(
+        &self,
+        from_file: FileRef,
+        to_file: FileRef,
+        format: Option<&str>,
+    ) -> InnoFileResult<()> {
+        let (from_path, to_path) = (from_file.path().to_string(), to_file.path().to_string());
+
+        // create reader
+        let arrow_reader = ArrowReaderBuilder::new()
+            .with_file_format(self.args.from_format.as_deref())
+            .with_schema(self.schema.clone())
+            .with_csv_options(Some(self.csv_options.clone()))
+            .build_sync(from_file)?;
+        let (indices, schema) = self.output_schema(&arrow_reader.schema())?;
+
+        // create writer
+        let mut arrow_writer = ArrowWriterBuilder::new(Arc::clone(&schema))
+            .with_file_format(format)
+            .with_compression(self.args.compression.as_deref())
+            .with_row_group_size(self.args.row_group_size)
+            .with_csv_options(Some(self.csv_options.clone()))
+            .build_sync(to_file)?;
+
+        // iteratively read, transform and write record batches
+        let read_context = || ErrorContext::new(Operation::Read).with_path(&from_path);
+        let write_context = || ErrorContext::new(Operation::Write).with_path(&to_path);
+        for record_batch in arrow_reader {
+            let mut record_batch = record_batch.with_context(read_context)?;
+            if let Some(schema) = &self.schema {
+                record_batch = cast_batch(&record_batch, schema).with_context(read_context)?;
+            }
+            if let Some(filter) = &self.args.filter {
+                record_batch = filter.apply(&record_batch)?;
+                if record_batch.num_rows() == 0 {
+                    continue;
+                }
+            }
+            let columns = record_batch.project(&indices)?.columns().to_vec();
+            let record_batch = RecordBatch::try_new(Arc::clone(&schema), columns)?;
+            arrow_writer
+                .write(&record_batch)
+                .with_context(write_context)?;
+        }
+        arrow_writer
+            .close()
+            .with_context(|| ErrorContext::new(Operation::Close).with_path(&to_path))
+    }
+
+    /// Returns the indices of the columns to convert and their schema after renaming, given the
+    /// schema read.
+    fn output_schema(&self, schema: &SchemaRef) -> InnoFileResult<(Vec<usize>, SchemaRef)> {
+        let schema = self.schema.as_ref().unwrap_or(schema);
+        let indices = match self.args.columns.is_empty() {
+            true => (0..schema.fields().len()).collect(),
+            false => self
+                .args
+                .columns
+                .iter()
+                .map(|column| schema.index_of(column))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let projected = schema.project(&indices)?;
+        for (old, _) in &self.args.rename {
+            projected.index_of(old)?;
+        }
+        let fields = projected.fields().iter().map(|field| {
+            match self.args.rename.iter().find(|(old, _)| old == field.name()) {
+                Some((_, new)) => Arc::new(field.as_ref().clone().with_name(new)),
+                None => Arc::clone(field),
+            }
+        });
+        let schema = Schema::new_with_metadata(fields.collect::<Vec<_>>(), projected.metadata);
+        Ok((indices, Arc::new(schema)))
+    }
+}
+
+/// Casts the columns of `batch` to the types of the columns of `schema` with the same names.
+fn cast_batch(batch: &RecordBatch, schema: &SchemaRef) -> InnoFileResult<RecordBatch> {
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = batch
+                .column_by_name(field.name())
+                .ok_or_else(|| anyhow!("Column not found: {}", field.name()))?;
+            Ok(cast_with_options(column, field.data_type(), &options)?)
+        })
+        .collect::<InnoFileResult<Vec<_>>>()?;
+    Ok(RecordBatch::try_new(Arc::clone(schema), columns)?)
+}
+
+/// Replaces the extension of `path` by `format`, along with the file format extension before a
+/// compression extension such as `.csv.gz`.
+fn replace_extension(path: &str, format: &str) -> String {
+    fn strip(path: &str) -> Option<(&str, bool)> {
+        let extension = Path::new(path).extension()?.to_str()?;
+        let known = FILE_FORMATS.contains(&extension.to_lowercase().as_str());
+        Some((&path[..path.len() - extension.len() - 1], known))
+    }
+
+    let stem = match strip(path) {
+        None => path,
+        Some((stem, true)) => stem,
+        Some((stem, false)) => match strip(stem) {
+            Some((inner, true)) => inner,
+            _ => stem,
+        },
+    };
+    format!("{stem}.{format}")
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(all(feature = "csv", feature = "parquet"))]
+    use std::io::Write as _;
+
+    #[cfg(all(feature = "csv", any(feature = "parquet", feature = "json")))]
+    use clap::Parser as _;
+    #[cfg(all(feature = "csv", feature = "parquet"))]
+    use innofile::sync::fs::local::LocalFS;
+    #[cfg(all(feature = "csv", feature = "parquet"))]
+    use tempfile::tempdir;
+
+    #[cfg(all(feature = "csv", any(feature = "parquet", feature = "json")))]
+    use crate::{Cli, Commands};
+
+    use super::*;
+
+    #[cfg(all(feature = "csv", any(feature = "parquet", feature = "json")))]
+    fn convert_args(args: &[&str]) -> ConvertArgs {
+        let args = ["innofile", "convert"].iter().chain(args);
+        match Cli::parse_from(args).command {
+            Commands::Convert(args) => *args,
+            command => panic!("Convert command not parsed correctly: {command:?}"),
+        }
+    }
+
+    #[cfg(all(feature = "csv", feature = "parquet"))]
+    fn write_file(path: &str, content: &str) -> InnoFileResult<()> {
+        let mut writer = LocalFS.create(path)?.writer()?;
+        writer.write_all(content.as_bytes())?;
+        writer.close()
+    }
+
+    #[test]
+    fn test_replace_extension() {
+        assert_eq!(replace_extension("a/b.csv", "parquet"), "a/b.parquet");
+        assert_eq!(replace_extension("a/b.csv.gz", "parquet"), "a/b.parquet");
+        assert_eq!(
+            replace_extension("a/b.2024.01.orc", "csv"),
+            "a/b.2024.01.csv"
+        );
+        assert_eq!(replace_extension("a/b.txt", "json"), "a/b.json");
+        assert_eq!(replace_extension("a/b", "json"), "a/b.json");
+    }
+
+    #[cfg(all(feature = "csv", feature = "parquet"))]
+    #[test]
+    fn test_batch_convert() -> InnoFileResult<()> {
+        use std::time::{Duration, SystemTime};
+
+        let dir = tempdir()?;
+        let dir = dir.path().to_str().unwrap();
+        let file_system = LocalFS;
+        for (path, content) in [
+            ("raw/a.csv", "x,y\n1,2\n"),
+            ("raw/nested/b.csv", "x,y\n3,4\n"),
+            ("raw/c.txt", "not converted\n"),
+        ] {
+            write_file(&format!("{dir}/{path}"), content)?;
+        }
+        file_system.create_dir(&format!("{dir}/raw/archive.csv"))?;
+
+        let (from_path, to_path) = (format!("{dir}/raw/**/*.csv"), format!("{dir}/clean/"));
+        let args = convert_args(&[
+            "--from",
+            &from_path,
+            "--to",
+            &to_path,
+            "--to-format",
+            "parquet",
+            "--jobs",
+            "2",
+        ]);
+        do_convert(&args, None)?;
+        let outputs = ["clean/a.parquet", "clean/nested/b.parquet"];
+        for output in outputs {
+            assert!(file_system.exists(&format!("{dir}/{output}"))?);
+        }
+        assert!(!file_system.exists(&format!("{dir}/clean/archive.parquet"))?);
+
+        // up-to-date outputs are skipped, so only the touched input is converted again
+        let set_modified = |path: &str, time| {
+            let file = std::fs::File::options()
+                .write(true)
+                .open(format!("{dir}/{path}"))?;
+            file.set_modified(time)
+        };
+        let modified = |path| {
+            file_system
+                .status(&format!("{dir}/{path}"))
+                .map(|s| s.last_modified)
+        };
+        let converted = SystemTime::now() - Duration::from_secs(3600);
+        set_modified("raw/nested/b.csv", converted - Duration::from_secs(60))?;
+        for output in outputs {
+            set_modified(output, converted)?;
+        }
+        set_modified("raw/a.csv", SystemTime::now())?;
+        do_convert(&args, None)?;
+        assert!(modified(outputs[0])? > Some(converted));
+        assert_eq!(modified(outputs[1])?, Some(converted));
+
+        let args = ConvertArgs {
+            from_path: format!("{dir}/raw/*.txt"),
+            ..args
+        };
+        assert!(do_convert(&args, None).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "csv", feature = "parquet"))]
+    #[test]
+    fn test_convert_options() -> InnoFileResult<()> {
+        use arrow::{
+            array::{AsArray as _, Float64Array, Int64Array, StringArray},
+            datatypes::{DataType, Field},
+        };
+        use innofile::sync::arrow::parquet::ArrowParquetReader;
+
+        let dir = tempdir()?;
+        let dir = dir.path().to_str().unwrap();
+        let (from_path, to_path) = (format!("{dir}/from.csv"), format!("{dir}/to.parquet"));
+        let schema_path = format!("{dir}/schema.json");
+        write_file(
+            &from_path,
+            "# exported\nid;name;score;country\n1;Alex;9.5;DE\n2;Bob;NA;FR\n3;Carl;7;US\n",
+        )?;
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("score", DataType::Float64, true),
+            Field::new("country", DataType::Utf8, true),
+        ]);
+        write_file(&schema_path, &serde_json::to_string(&schema).unwrap())?;
+
+        let args = convert_args(&[
+            "--from",
+            &from_path,
+            "--to",
+            &to_path,
+            "--schema",
+            &schema_path,
+            "--columns",
+            "id,name,score",
+            "--where",
+            "country IN ('DE', 'FR')",
+            "--rename",
+            "name=full_name",
+            "--compression",
+            "zstd",
+            "--row-group-size",
+            "1",
+            "--delimiter",
+            ";",
+            "--null-value",
+            "NA",
+            "--comment",
+            "#",
+        ]);
+        do_convert(&args, None)?;
+
+        let file = Arc::from(LocalFS.open(&to_path)?);
+        let arrow_reader = ArrowParquetReader::new(file)?;
+        assert_eq!(arrow_reader.metadata().num_row_groups(), 2);
+        let batches = arrow_reader.collect::<InnoFileResult<Vec<_>>>()?;
+        let batch = arrow::compute::concat_batches(&batches[0].schema(), &batches)?;
+        let names = batch
+            .schema()
+            .fields()
+            .iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["id", "full_name", "score"]);
+        assert_eq!(
+            batch.column(0).as_primitive(),
+            &Int64Array::from(vec![1, 2])
+        );
+        assert_eq!(
+            batch.column(1).as_string(),
+            &StringArray::from(vec!["Alex", "Bob"])
+        );
+        assert_eq!(
+            batch.column(2).as_primitive(),
+            &Float64Array::from(vec![Some(9.5), None])
+        );
+
+        let args = ConvertArgs {
+            rename: vec![("missing".to_string(), "name".to_string())],
+            ..args
+        };
+        assert!(do_convert(&args, None).is_err());
+
+        Ok(())
+    }
+
+    #[cfg(all(feature = "csv", feature = "json"))]
+    #[test]
+    fn test_convert_stdio() -> InnoFileResult<()> {
+        use std::io::{Cursor, Read as _, Seek as _};
+
+        use innofile::sync::fs::stdio::{StdinFile, StdoutFile};
+
+        let args = convert_args(&[
+            "--from",
+            "-",
+            "--from-format",
+            "csv",
+            "--to",
+            "-",
+            "--to-format",
+            "json",
+            "--where",
+            "x > 1",
+        ]);
+        let conversion = Conversion {
+            args: &args,
+            schema: None,
+            csv_options: args.csv.options(),
+        };
+        let mut output_file = tempfile::tempfile()?;
+        conversion.convert(
+            Arc::new(StdinFile::from_reader(Cursor::new("x,y\n1,a\n2,b\n3,c\n"))),
+            Arc::new(StdoutFile::from_writer(output_file.try_clone()?)),
+            args.to_format.as_deref(),
+        )?;
+        let mut output = String::new();
+        output_file.rewind()?;
+        output_file.read_to_string(&mut output)?;
+        assert_eq!(output, "{\"x\":2,\"y\":\"b\"}\n{\"x\":3,\"y\":\"c\"}\n");
+
+        // standard streams have no extension to infer their file format from
+        let args = convert_args(&["--from", "-", "--to", "-"]);
+        let error = do_convert(&args, None).unwrap_err();
+        assert!(error.to_string().contains("--from-format"));
+
+        Ok(())
+    }
+}
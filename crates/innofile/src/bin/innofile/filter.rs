@@ -0,0 +1,467 @@
+use std::{str::FromStr, sync::Arc};
+
+use anyhow::{anyhow, bail};
+use arrow::{
+    array::{
+        new_null_array, Array, ArrayRef, AsArray as _, BooleanArray, Datum, RecordBatch, Scalar,
+        StringArray,
+    },
+    compute::{
+        cast_with_options, filter_record_batch,
+        kernels::{
+            boolean::{and_kleene, is_not_null, is_null, not, or_kleene},
+            cmp,
+            comparison::{like, nlike},
+        },
+        CastOptions,
+    },
+    datatypes::DataType,
+};
+use innofile::error::InnoFileResult;
+
+/// Filter expression of rows, such as `age >= 18 AND country IN ('DE', 'FR')`.
+///
+/// Supports comparisons, `AND`, `OR`, `NOT`, `IS [NOT] NULL`, `[NOT] IN` and `[NOT] LIKE`, with
+/// literals cast to the type of the column they are compared to.
+#[derive(Debug, Clone)]
+pub struct Filter(Expr);
+
+#[derive(Debug, Clone)]
+enum Expr {
+    Column(String),
+    Literal(Option<String>),
+    Compare(CompareOp, Box<Expr>, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    IsNull(Box<Expr>, bool),
+    InList(Box<Expr>, Vec<Expr>, bool),
+    Like(Box<Expr>, String, bool),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CompareOp {
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+}
+
+/// Keywords after which operands are expected.
+const KEYWORDS: [&str; 6] = ["and", "or", "not", "is", "in", "like"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Ident(String),
+    Number(String),
+    String(String),
+    Op(&'static str),
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            position: 0,
+        };
+        let expr = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(Self(expr)),
+            Some(token) => bail!("Unexpected {token:?} in filter: {s}"),
+        }
+    }
+}
+
+impl Filter {
+    /// Returns the rows of `batch` for which the expression is true.
+    pub fn apply(&self, batch: &RecordBatch) -> InnoFileResult<RecordBatch> {
+        let mask = predicate(&self.0, batch)?;
+        Ok(filter_record_batch(batch, &mask)?)
+    }
+}
+
+fn predicate(expr: &Expr, batch: &RecordBatch) -> InnoFileResult<BooleanArray> {
+    Ok(match expr {
+        Expr::Compare(op, left, right) => {
+            let (left, right) = operands(left, right, batch)?;
+            let (left, right) = (left.datum(), right.datum());
+            match op {
+                CompareOp::Eq => cmp::eq(left, right)?,
+                CompareOp::NotEq => cmp::neq(left, right)?,
+                CompareOp::Lt => cmp::lt(left, right)?,
+                CompareOp::LtEq => cmp::lt_eq(left, right)?,
+                CompareOp::Gt => cmp::gt(left, right)?,
+                CompareOp::GtEq => cmp::gt_eq(left, right)?,
+            }
+        }
+        Expr::And(left, right) => and_kleene(&predicate(left, batch)?, &predicate(right, batch)?)?,
+        Expr::Or(left, right) => or_kleene(&predicate(left, batch)?, &predicate(right, batch)?)?,
+        Expr::Not(expr) => not(&predicate(expr, batch)?)?,
+        Expr::IsNull(expr, negated) => {
+            let array = column(expr, batch)?;
+            match negated {
+                false => is_null(&array)?,
+                true => is_not_null(&array)?,
+            }
+        }
+        Expr::InList(expr, list, negated) => {
+            let mut mask = BooleanArray::from(vec![false; batch.num_rows()]);
+            for item in list {
+                let compare = Expr::Compare(CompareOp::Eq, expr.clone(), Box::new(item.clone()));
+                mask = or_kleene(&mask, &predicate(&compare, batch)?)?;
+            }
+            match negated {
+                false => mask,
+                true => not(&mask)?,
+            }
+        }
+        Expr::Like(expr, pattern, negated) => {
+            let array = cast(&column(expr, batch)?, &DataType::Utf8)?;
+            let pattern = Scalar::new(StringArray::from(vec![pattern.as_str()]));
+            match negated {
+                false => like(&array, &pattern)?,
+                true => nlike(&array, &pattern)?,
+            }
+        }
+        Expr::Column(_) => {
+            let array = column(expr, batch)?;
+            match array.data_type() {
+                DataType::Boolean => array.as_boolean().clone(),
+                data_type => Err(anyhow!("Filter on non-boolean column of type {data_type}"))?,
+            }
+        }
+        Expr::Literal(_) => Err(anyhow!("Filter on literal without column"))?,
+    })
+}
+
+/// Operand of a comparison, which is a scalar for literals.
+enum Operand {
+    Array(ArrayRef),
+    Scalar(Scalar<ArrayRef>),
+}
+
+impl Operand {
+    fn datum(&self) -> &dyn Datum {
+        match self {
+            Self::Array(array) => array,
+            Self::Scalar(scalar) => scalar,
+        }
+    }
+}
+
+/// Evaluates both sides of a comparison, casting literals and differently typed columns on the
+/// right to the type of the column on the left, or vice versa.
+fn operands(left: &Expr, right: &Expr, batch: &RecordBatch) -> InnoFileResult<(Operand, Operand)> {
+    Ok(match (left, right) {
+        (Expr::Literal(_), Expr::Literal(_)) => {
+            Err(anyhow!("Comparison of literals without column"))?
+        }
+        (Expr::Literal(value), _) => {
+            let array = column(right, batch)?;
+            let scalar = literal(value, array.data_type())?;
+            (Operand::Scalar(scalar), Operand::Array(array))
+        }
+        (_, Expr::Literal(value)) => {
+            let array = column(left, batch)?;
+            let scalar = literal(value, array.data_type())?;
+            (Operand::Array(array), Operand::Scalar(scalar))
+        }
+        _ => {
+            let left = column(left, batch)?;
+            let right = cast(&column(right, batch)?, left.data_type())?;
+            (Operand::Array(left), Operand::Array(right))
+        }
+    })
+}
+
+fn column(expr: &Expr, batch: &RecordBatch) -> InnoFileResult<ArrayRef> {
+    match expr {
+        Expr::Column(name) => Ok(Arc::clone(
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| anyhow!("Column not found: {name}"))?,
+        )),
+        expr => Ok(Arc::new(predicate(expr, batch)?)),
+    }
+}
+
+fn literal(value: &Option<String>, data_type: &DataType) -> InnoFileResult<Scalar<ArrayRef>> {
+    let array = match value {
+        Some(value) => cast(
+            &(Arc::new(StringArray::from(vec![value.as_str()])) as _),
+            data_type,
+        )?,
+        None => new_null_array(data_type, 1),
+    };
+    Ok(Scalar::new(array))
+}
+
+/// Casts `array`, failing on values not representable as `data_type`.
+fn cast(array: &ArrayRef, data_type: &DataType) -> InnoFileResult<ArrayRef> {
+    if array.data_type() == data_type {
+        return Ok(Arc::clone(array));
+    }
+    let options = CastOptions {
+        safe: false,
+        ..Default::default()
+    };
+    Ok(cast_with_options(array, data_type, &options)?)
+}
+
+fn tokenize(s: &str) -> anyhow::Result<Vec<Token>> {
+    let mut tokens = vec![];
+    let mut rest = s.trim_start();
+    while let Some(c) = rest.chars().next() {
+        // a minus after an operand is a binary operator, which isn't supported
+        let follows_operand = match tokens.last() {
+            Some(Token::Word(word)) => !KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word)),
+            Some(Token::Ident(_) | Token::Number(_) | Token::String(_)) => true,
+            _ => false,
+        };
+        let (token, len) = if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+                .unwrap_or(rest.len());
+            (Token::Word(rest[..len].to_string()), len)
+        } else if c.is_ascii_digit() || (c == '-' && !follows_operand) {
+            let len = rest[1..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+                .map_or(rest.len(), |len| len + 1);
+            (Token::Number(rest[..len].to_string()), len)
+        } else if matches!(c, '\'' | '"' | '`') {
+            let (quoted, len) =
+                unquote(rest, c).ok_or_else(|| anyhow!("Unterminated {c} in filter: {s}"))?;
+            match c {
+                '\'' => (Token::String(quoted), len),
+                _ => (Token::Ident(quoted), len),
+            }
+        } else {
+            let op = ["<=", ">=", "<>", "!=", "==", "=", "<", ">", "(", ")", ","]
+                .into_iter()
+                .find(|op| rest.starts_with(op))
+                .ok_or_else(|| anyhow!("Unexpected {c:?} in filter: {s}"))?;
+            (Token::Op(op), op.len())
+        };
+        tokens.push(token);
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Returns the content of the string starting with `quote`, in which quotes are escaped by
+/// doubling them, and its length including quotes.
+fn unquote(s: &str, quote: char) -> Option<(String, usize)> {
+    let mut unquoted = String::new();
+    let mut chars = s.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c != quote {
+            unquoted.push(c);
+        } else if chars.next_if(|(_, c)| *c == quote).is_some() {
+            unquoted.push(quote);
+        } else {
+            return Some((unquoted, i + 1));
+        }
+    }
+    None
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> anyhow::Result<Token> {
+        let token = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| anyhow!("Unexpected end of filter"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    /// Consumes the next token if it's the keyword `word`, ignoring case.
+    fn keyword(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(w)) if w.eq_ignore_ascii_case(word) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn op(&mut self, op: &str) -> bool {
+        match self.peek() {
+            Some(Token::Op(o)) if *o == op => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_op(&mut self, op: &str) -> anyhow::Result<()> {
+        match self.op(op) {
+            true => Ok(()),
+            false => bail!("Expected {op:?} in filter, found {:?}", self.peek()),
+        }
+    }
+
+    fn parse_or(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.parse_and()?;
+        while self.keyword("or") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.parse_not()?;
+        while self.keyword("and") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> anyhow::Result<Expr> {
+        match self.keyword("not") {
+            true => Ok(Expr::Not(Box::new(self.parse_not()?))),
+            false => self.parse_predicate(),
+        }
+    }
+
+    fn parse_predicate(&mut self) -> anyhow::Result<Expr> {
+        if self.op("(") {
+            let expr = self.parse_or()?;
+            self.expect_op(")")?;
+            return Ok(expr);
+        }
+
+        let expr = Box::new(self.parse_operand()?);
+        if self.keyword("is") {
+            let negated = self.keyword("not");
+            match self.keyword("null") {
+                true => return Ok(Expr::IsNull(expr, negated)),
+                false => bail!("Expected NULL in filter, found {:?}", self.peek()),
+            }
+        }
+        let negated = self.keyword("not");
+        if self.keyword("in") {
+            self.expect_op("(")?;
+            let mut list = vec![self.parse_operand()?];
+            while self.op(",") {
+                list.push(self.parse_operand()?);
+            }
+            self.expect_op(")")?;
+            return Ok(Expr::InList(expr, list, negated));
+        }
+        if self.keyword("like") {
+            return match self.next()? {
+                Token::String(pattern) => Ok(Expr::Like(expr, pattern, negated)),
+                token => bail!("Expected pattern after LIKE in filter, found {token:?}"),
+            };
+        }
+        if negated {
+            bail!(
+                "Expected IN or LIKE after NOT in filter, found {:?}",
+                self.peek()
+            );
+        }
+
+        let op = match self.peek() {
+            Some(Token::Op("=" | "==")) => CompareOp::Eq,
+            Some(Token::Op("!=" | "<>")) => CompareOp::NotEq,
+            Some(Token::Op("<")) => CompareOp::Lt,
+            Some(Token::Op("<=")) => CompareOp::LtEq,
+            Some(Token::Op(">")) => CompareOp::Gt,
+            Some(Token::Op(">=")) => CompareOp::GtEq,
+            _ => return Ok(*expr),
+        };
+        self.position += 1;
+        Ok(Expr::Compare(op, expr, Box::new(self.parse_operand()?)))
+    }
+
+    fn parse_operand(&mut self) -> anyhow::Result<Expr> {
+        Ok(match self.next()? {
+            Token::Word(word) => match word.to_lowercase().as_str() {
+                "null" => Expr::Literal(None),
+                "true" | "false" => Expr::Literal(Some(word.to_lowercase())),
+                _ => Expr::Column(word),
+            },
+            Token::Ident(name) => Expr::Column(name),
+            Token::Number(number) | Token::String(number) => Expr::Literal(Some(number)),
+            Token::Op(op) => bail!("Expected column or literal in filter, found {op:?}"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::Int64Array,
+        datatypes::{Field, Schema},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_filter() -> InnoFileResult<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+            Field::new("active", DataType::Boolean, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3, 4])),
+                Arc::new(StringArray::from(vec![
+                    Some("Alex"),
+                    Some("Bob"),
+                    None,
+                    Some("it's"),
+                ])),
+                Arc::new(BooleanArray::from(vec![
+                    Some(true),
+                    Some(false),
+                    Some(true),
+                    None,
+                ])),
+            ],
+        )?;
+
+        let ids = |filter: &str| -> InnoFileResult<Vec<i64>> {
+            let batch = filter.parse::<Filter>()?.apply(&batch)?;
+            Ok(batch
+                .column(0)
+                .as_primitive::<arrow::datatypes::Int64Type>()
+                .values()
+                .to_vec())
+        };
+        assert_eq!(ids("id >= 2 AND id < 4")?, [2, 3]);
+        assert_eq!(ids("id = 1 or NOT (id <> 4)")?, [1, 4]);
+        assert_eq!(ids("name IS NULL")?, [3]);
+        assert_eq!(ids("name is not null and active")?, [1]);
+        assert_eq!(ids("id NOT IN (1, 3)")?, [2, 4]);
+        assert_eq!(ids("name LIKE 'A%' OR name = 'it''s'")?, [1, 4]);
+        assert_eq!(ids("-1 < \"id\" AND `name` != 'Bob'")?, [1, 4]);
+        assert_eq!(ids("active = true")?, [1, 3]);
+        assert_eq!(ids("id > 3 OR -1 >= id")?, [4]);
+        assert!(ids("id = 'x'").is_err());
+        assert!(ids("missing = 1").is_err());
+        assert!("id = (1".parse::<Filter>().is_err());
+        assert!("id = 1 id".parse::<Filter>().is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,566 @@
+use std::{
+    io::{self, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::anyhow;
+use arrow::temporal_conversions::timestamp_s_to_datetime;
+use clap::Args;
+use innofile::{
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+    sync::{fs::FileSystem, io::Closeable as _},
+};
+
+use crate::file_system;
+
+#[derive(Debug, Args)]
+pub struct LsArgs {
+    /// Paths or glob patterns to list
+    #[arg(value_name = "PATH", required = true)]
+    pub paths: Vec<String>,
+
+    /// List directories recursively
+    #[arg(long, short)]
+    pub recursive: bool,
+
+    /// Print type, size and modification time of each entry
+    #[arg(long, short)]
+    pub long: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct CpArgs {
+    /// Paths or glob patterns to copy
+    #[arg(value_name = "SOURCE", required = true)]
+    pub sources: Vec<String>,
+
+    /// Path to copy to, a directory when copying multiple sources
+    #[arg(value_name = "DESTINATION")]
+    pub destination: String,
+
+    /// Copy directories recursively
+    #[arg(long, short)]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct MvArgs {
+    /// Paths or glob patterns to move
+    #[arg(value_name = "SOURCE", required = true)]
+    pub sources: Vec<String>,
+
+    /// Path to move to, a directory when moving multiple sources
+    #[arg(value_name = "DESTINATION")]
+    pub destination: String,
+}
+
+#[derive(Debug, Args)]
+pub struct RmArgs {
+    /// Paths or glob patterns to remove
+    #[arg(value_name = "PATH", required = true)]
+    pub paths: Vec<String>,
+
+    /// Remove directories and their contents recursively
+    #[arg(long, short)]
+    pub recursive: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct MkdirArgs {
+    /// Directories to create
+    #[arg(value_name = "PATH", required = true)]
+    pub paths: Vec<String>,
+
+    /// Create parent directories as needed, no error if existing
+    #[arg(long, short)]
+    pub parents: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct StatArgs {
+    /// Paths or glob patterns to print the status of
+    #[arg(value_name = "PATH", required = true)]
+    pub paths: Vec<String>,
+}
+
+pub fn do_ls(args: &LsArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let mut stdout = io::stdout().lock();
+    for pattern in &args.paths {
+        let file_system = file_system(pattern, profile)?;
+        for path in expand(&file_system, pattern)? {
+            let status = file_system.status(&path)?;
+            let statuses = match (status.is_dir, args.recursive) {
+                (false, _) => vec![status],
+                (true, false) => children(&file_system, &path)?,
+                (true, true) => descendants(&file_system, &path)?,
+            };
+            for status in statuses {
+                match args.long {
+                    true => writeln!(stdout, "{}", format_long(&status))?,
+                    false => writeln!(stdout, "{}", status.path)?,
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn do_cp(args: &CpArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    transfer(
+        &args.sources,
+        &args.destination,
+        args.recursive,
+        false,
+        profile,
+    )
+}
+
+pub fn do_mv(args: &MvArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    transfer(&args.sources, &args.destination, true, true, profile)
+}
+
+pub fn do_rm(args: &RmArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    for pattern in &args.paths {
+        let file_system = file_system(pattern, profile)?;
+        for path in expand(&file_system, pattern)? {
+            remove(&file_system, &file_system.status(&path)?, args.recursive)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn do_mkdir(args: &MkdirArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    for path in &args.paths {
+        let file_system = file_system(path, profile)?;
+        match args.parents {
+            true => file_system.create_dir_all(path)?,
+            false => file_system.create_dir(path)?,
+        }
+    }
+    Ok(())
+}
+
+pub fn do_stat(args: &StatArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let mut stdout = io::stdout().lock();
+    let mut first = true;
+    for pattern in &args.paths {
+        let file_system = file_system(pattern, profile)?;
+        for path in expand(&file_system, pattern)? {
+            let status = file_system.status(&path)?;
+            let version = match status.is_dir {
+                true => None,
+                false => file_system.open(&path)?.metadata()?.version(),
+            };
+            if !std::mem::take(&mut first) {
+                writeln!(stdout)?;
+            }
+            writeln!(stdout, "Path: {}", status.path)?;
+            let kind = if status.is_dir { "directory" } else { "file" };
+            writeln!(stdout, "Type: {kind}")?;
+            if !status.is_dir {
+                writeln!(stdout, "Size: {}", status.len)?;
+            }
+            if let Some(modified) = status.last_modified.and_then(format_time) {
+                writeln!(stdout, "Modified: {modified}")?;
+            }
+            if let Some(version) = version {
+                writeln!(stdout, "Version: {version}")?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copies `sources` to `destination`, removing the sources afterwards when moving.
+fn transfer(
+    sources: &[String],
+    destination: &str,
+    recursive: bool,
+    remove_sources: bool,
+    profile: Option<&str>,
+) -> InnoFileResult<()> {
+    let to_file_system = file_system(destination, profile)?;
+    let mut sources = sources
+        .iter()
+        .map(|pattern| {
+            let file_system = file_system(pattern, profile)?;
+            let paths = expand(&file_system, pattern)?;
+            Ok((file_system, paths))
+        })
+        .collect::<InnoFileResult<Vec<_>>>()?;
+
+    let into_dir = destination.ends_with('/')
+        || sources.iter().map(|(_, paths)| paths.len()).sum::<usize>() > 1
+        || is_dir(&to_file_system, destination)?;
+    for (from_file_system, paths) in &mut sources {
+        for path in paths {
+            let status = from_file_system.status(path)?;
+            if status.is_dir && !recursive {
+                Err(anyhow!("{path} is a directory, use --recursive to copy it"))?;
+            }
+            let to_path = match into_dir {
+                true => join(destination, name(path)),
+                false => destination.to_string(),
+            };
+            if is_within(&to_path, path) {
+                Err(anyhow!(
+                    "Cannot copy {path} to {to_path}, which is within it"
+                ))?;
+            }
+            copy(from_file_system, &status, &to_file_system, &to_path)?;
+            if remove_sources {
+                remove(from_file_system, &status, true)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn copy(
+    from_file_system: &dyn FileSystem,
+    status: &FileStatus,
+    to_file_system: &dyn FileSystem,
+    to_path: &str,
+) -> InnoFileResult<()> {
+    if status.is_dir {
+        to_file_system.create_dir_all(to_path)?;
+        for child in children(from_file_system, &status.path)? {
+            let to_path = join(to_path, name(&child.path));
+            copy(from_file_system, &child, to_file_system, &to_path)?;
+        }
+        return Ok(());
+    }
+
+    let mut reader = from_file_system.open(&status.path)?.reader()?;
+    let mut writer = to_file_system.create(to_path)?.writer()?;
+    io::copy(&mut reader, &mut writer).with_context(|| {
+        ErrorContext::new(Operation::Write)
+            .with_scheme(to_file_system.scheme())
+            .with_path(to_path)
+    })?;
+    writer.close()
+}
+
+fn remove(
+    file_system: &dyn FileSystem,
+    status: &FileStatus,
+    recursive: bool,
+) -> InnoFileResult<()> {
+    match (status.is_dir, recursive) {
+        (false, _) => file_system.remove_file(&status.path),
+        (true, true) => file_system.remove_dir_all(&status.path),
+        (true, false) => Err(anyhow!(
+            "{} is a directory, use --recursive to remove it",
+            status.path
+        ))?,
+    }
+}
+
+/// Whether `path` is `dir` itself or a path under it.
+fn is_within(path: &str, dir: &str) -> bool {
+    let rest = path
+        .trim_end_matches('/')
+        .strip_prefix(dir.trim_end_matches('/'));
+    rest.is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+fn is_dir(file_system: &dyn FileSystem, path: &str) -> InnoFileResult<bool> {
+    match file_system.status(path) {
+        Ok(status) => Ok(status.is_dir),
+        Err(error) if error.is_not_found() => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Lists the direct children of `dir`, with paths joined onto `dir` so they read the same
+/// regardless of how the file system reports them.
+fn children(file_system: &dyn FileSystem, dir: &str) -> InnoFileResult<Vec<FileStatus>> {
+    let list_path = if dir.is_empty() { "." } else { dir };
+    let mut statuses = match file_system.list(list_path) {
+        Ok(statuses) => statuses,
+        Err(error) if error.is_not_found() => vec![],
+        Err(error) => Err(error)?,
+    };
+    for status in &mut statuses {
+        status.path = join(dir, name(&status.path));
+    }
+    statuses.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(statuses)
+}
+
+fn descendants(file_system: &dyn FileSystem, dir: &str) -> InnoFileResult<Vec<FileStatus>> {
+    let mut statuses = vec![];
+    for status in children(file_system, dir)? {
+        let nested = match status.is_dir {
+            true => descendants(file_system, &status.path)?,
+            false => vec![],
+        };
+        statuses.push(status);
+        statuses.extend(nested);
+    }
+    Ok(statuses)
+}
+
+/// Expands the glob `pattern` into the sorted paths matching it, or returns the pattern itself
+/// when it contains no glob characters.
+pub fn expand(file_system: &dyn FileSystem, pattern: &str) -> InnoFileResult<Vec<String>> {
+    let Some(base) = glob_base(pattern) else {
+        return Ok(vec![pattern.to_string()]);
+    };
+    let segments = pattern.split('/').skip(base_segments(pattern));
+
+    let mut paths = vec![];
+    walk(
+        file_system,
+        &base,
+        &segments.collect::<Vec<_>>(),
+        &mut paths,
+    )?;
+    if paths.is_empty() {
+        Err(anyhow!("No paths matching {pattern}"))?;
+    }
+    paths.sort();
+    paths.dedup();
+    Ok(paths)
+}
+
+/// Returns the directory of `pattern` before its first glob segment, or `None` when it contains
+/// no glob characters.
+pub fn glob_base(pattern: &str) -> Option<String> {
+    let index = base_segments(pattern);
+    if index == pattern.split('/').count() {
+        return None;
+    }
+    match pattern.split('/').take(index).collect::<Vec<_>>().join("/") {
+        base if base.is_empty() && index > 0 => Some("/".to_string()),
+        base => Some(base),
+    }
+}
+
+fn base_segments(pattern: &str) -> usize {
+    pattern.split('/').take_while(|s| !is_glob(s)).count()
+}
+
+/// Collects into `paths` the paths under `dir` matching the remaining glob `segments`, where `**`
+/// matches any number of directories.
+fn walk(
+    file_system: &dyn FileSystem,
+    dir: &str,
+    segments: &[&str],
+    paths: &mut Vec<String>,
+) -> InnoFileResult<()> {
+    let Some((&segment, rest)) = segments.split_first() else {
+        paths.push(dir.to_string());
+        return Ok(());
+    };
+    if segment == "**" {
+        walk(file_system, dir, rest, paths)?;
+        for child in children(file_system, dir)? {
+            if child.is_dir {
+                walk(file_system, &child.path, segments, paths)?;
+            }
+        }
+    } else if is_glob(segment) {
+        for child in children(file_system, dir)? {
+            if matches_glob(segment, name(&child.path)) && (rest.is_empty() || child.is_dir) {
+                walk(file_system, &child.path, rest, paths)?;
+            }
+        }
+    } else {
+        let path = join(dir, segment);
+        match file_system.status(&path) {
+            Ok(_) => walk(file_system, &path, rest, paths)?,
+            Err(error) if error.is_not_found() => {}
+            Err(error) => Err(error)?,
+        }
+    }
+    Ok(())
+}
+
+fn is_glob(segment: &str) -> bool {
+    segment.contains(['*', '?', '['])
+}
+
+/// Matches a single path segment against a glob supporting `*`, `?` and character classes such as
+/// `[a-z]` or `[!0-9]`.
+fn matches_glob(pattern: &str, name: &str) -> bool {
+    fn matches(pattern: &[char], name: &[char]) -> bool {
+        match pattern.split_first() {
+            None => name.is_empty(),
+            Some(('*', rest)) => (0..=name.len()).any(|i| matches(rest, &name[i..])),
+            Some(('?', rest)) => !name.is_empty() && matches(rest, &name[1..]),
+            Some(('[', rest)) if class_end(rest).is_some() => {
+                let end = class_end(rest).unwrap();
+                match name.split_first() {
+                    Some((&c, name)) => {
+                        in_class(&rest[..end], c) && matches(&rest[end + 1..], name)
+                    }
+                    None => false,
+                }
+            }
+            Some((c, rest)) => name.first() == Some(c) && matches(rest, &name[1..]),
+        }
+    }
+
+    // Index of the closing bracket, where a leading `]` is part of the class.
+    fn class_end(class: &[char]) -> Option<usize> {
+        let start = usize::from(class.first() == Some(&'!'));
+        let start = start + usize::from(class.get(start) == Some(&']'));
+        class[start..]
+            .iter()
+            .position(|&c| c == ']')
+            .map(|i| i + start)
+    }
+
+    fn in_class(class: &[char], c: char) -> bool {
+        let (negated, mut class) = match class.split_first() {
+            Some(('!', rest)) => (true, rest),
+            _ => (false, class),
+        };
+        let mut found = false;
+        while let Some((&first, rest)) = class.split_first() {
+            match rest {
+                ['-', last, rest @ ..] => {
+                    found |= (first..=*last).contains(&c);
+                    class = rest;
+                }
+                _ => {
+                    found |= first == c;
+                    class = rest;
+                }
+            }
+        }
+        found != negated
+    }
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    matches(&pattern, &name)
+}
+
+pub fn name(path: &str) -> &str {
+    let path = path.trim_end_matches('/');
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub fn join(dir: &str, name: &str) -> String {
+    match dir {
+        "" => name.to_string(),
+        dir if dir.ends_with('/') => format!("{dir}{name}"),
+        dir => format!("{dir}/{name}"),
+    }
+}
+
+fn format_long(status: &FileStatus) -> String {
+    let kind = if status.is_dir { 'd' } else { '-' };
+    let modified = status.last_modified.and_then(format_time);
+    let modified = modified.as_deref().unwrap_or("-");
+    format!("{kind} {:>12} {modified:>19} {}", status.len, status.path)
+}
+
+fn format_time(time: SystemTime) -> Option<String> {
+    let secs = time.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    timestamp_s_to_datetime(secs as _).map(|t| t.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use innofile::sync::fs::local::LocalFS;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_matches_glob() {
+        assert!(matches_glob("*.csv", "data.csv"));
+        assert!(!matches_glob("*.csv", "data.csv.gz"));
+        assert!(matches_glob("part-?", "part-1"));
+        assert!(!matches_glob("part-?", "part-10"));
+        assert!(matches_glob("part-[0-4]*", "part-3.orc"));
+        assert!(!matches_glob("part-[!0-4]*", "part-3.orc"));
+        assert!(matches_glob("[]]", "]"));
+        assert!(matches_glob("[", "["));
+    }
+
+    #[test]
+    fn test_file_system_commands() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let dir = dir.path().to_str().unwrap();
+        let file_system = LocalFS;
+        for path in ["a/1.csv", "a/2.csv", "a/b/3.csv", "a/b/4.json"] {
+            let mut writer = file_system.create(&join(dir, path))?.writer()?;
+            writer.write_all(path.as_bytes())?;
+            writer.close()?;
+        }
+
+        let relative = |paths: Vec<String>| {
+            let prefix = format!("{dir}/");
+            paths
+                .into_iter()
+                .map(|p| p.strip_prefix(&prefix).unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        let paths = expand(&file_system, &format!("{dir}/a/*.csv"))?;
+        assert_eq!(relative(paths), ["a/1.csv", "a/2.csv"]);
+        let paths = expand(&file_system, &format!("{dir}/**/*.csv"))?;
+        assert_eq!(relative(paths), ["a/1.csv", "a/2.csv", "a/b/3.csv"]);
+        assert!(expand(&file_system, &format!("{dir}/*.orc")).is_err());
+        assert_eq!(
+            glob_base("s3://bucket/raw/**/*.csv").unwrap(),
+            "s3://bucket/raw"
+        );
+        assert_eq!(glob_base("/*.csv").unwrap(), "/");
+        assert_eq!(glob_base("*.csv").unwrap(), "");
+        assert_eq!(glob_base("raw/data.csv"), None);
+
+        let args = CpArgs {
+            sources: vec![format!("{dir}/a")],
+            destination: format!("{dir}/c"),
+            recursive: true,
+        };
+        do_cp(&args, None)?;
+        let paths = descendants(&file_system, &format!("{dir}/c"))?;
+        for destination in [format!("{dir}/c"), format!("{dir}/c/b/copy")] {
+            let args = CpArgs {
+                sources: vec![format!("{dir}/c")],
+                destination,
+                recursive: true,
+            };
+            assert!(do_cp(&args, None).is_err());
+        }
+        assert!(!is_within(&format!("{dir}/cd"), &format!("{dir}/c")));
+        let paths = relative(paths.into_iter().map(|s| s.path).collect());
+        assert_eq!(
+            paths,
+            ["c/1.csv", "c/2.csv", "c/b", "c/b/3.csv", "c/b/4.json"]
+        );
+
+        let args = MvArgs {
+            sources: vec![format!("{dir}/c/b/*.csv"), format!("{dir}/c/1.csv")],
+            destination: format!("{dir}/d/"),
+        };
+        do_mv(&args, None)?;
+        let paths = relative(expand(&file_system, &format!("{dir}/*/*.csv"))?);
+        assert_eq!(
+            paths,
+            ["a/1.csv", "a/2.csv", "c/2.csv", "d/1.csv", "d/3.csv"]
+        );
+
+        let args = RmArgs {
+            paths: vec![format!("{dir}/c")],
+            recursive: false,
+        };
+        assert!(do_rm(&args, None).is_err());
+        do_rm(
+            &RmArgs {
+                recursive: true,
+                ..args
+            },
+            None,
+        )?;
+        assert!(!file_system.exists(&format!("{dir}/c"))?);
+
+        Ok(())
+    }
+}
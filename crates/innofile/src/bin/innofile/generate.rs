@@ -0,0 +1,277 @@
+use std::num::NonZeroUsize;
+
+use anyhow::anyhow;
+use arrow::{
+    array::{RecordBatch, RecordBatchOptions},
+    datatypes::SchemaRef,
+};
+use clap::{ArgGroup, Args};
+use innofile::{
+    arrow::{ArrowReaderBuilder, ArrowWriterBuilder},
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+    io::SyncCloseable as _,
+};
+use rand::{rngs::StdRng, SeedableRng as _};
+
+use crate::{
+    convert::read_schema,
+    create_file, file_system,
+    generator::{ColumnGenerator, Spec},
+    open_file,
+};
+
+/// Number of records generated and written at once by default.
+const DEFAULT_BATCH_SIZE: usize = 8192;
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("source").required(true)))]
+pub struct GenerateArgs {
+    /// Example of records to generate, or `-` for standard input
+    #[arg(long = "example", group = "source")]
+    pub example_path: Option<String>,
+
+    /// Arrow JSON schema file, as printed by `schema --format json`, of records to generate
+    #[arg(long = "schema", value_name = "PATH", group = "source")]
+    pub schema_path: Option<String>,
+
+    /// JSON spec file of the generators of columns, such as
+    /// `{"columns": {"age": {"type": "range", "min": 18, "max": 99}}}`
+    #[arg(long = "spec", value_name = "PATH")]
+    pub spec_path: Option<String>,
+
+    /// File format of the example, required to read it from standard input with `-`
+    #[arg(long)]
+    pub from_format: Option<String>,
+
+    /// File format of all output files, required to write to standard output with `-`
+    #[arg(long)]
+    pub to_format: Option<String>,
+
+    /// Size of records to generate
+    #[arg(long, default_value_t = 1)]
+    pub size: usize,
+
+    /// Number of records generated and written at once
+    #[arg(long, default_value_t = NonZeroUsize::new(DEFAULT_BATCH_SIZE).unwrap())]
+    pub batch_size: NonZeroUsize,
+
+    /// Seed of random values, to generate the same records on each run
+    #[arg(long)]
+    pub seed: Option<u64>,
+
+    /// Null density of nullable fields to generate
+    #[arg(long, default_value_t = 0.0)]
+    pub null_density: f32,
+
+    /// True density of boolean fields to generate
+    #[arg(long, default_value_t = 0.5)]
+    pub true_density: f32,
+
+    /// Path of output file, or `-` for standard output
+    #[arg(value_name = "OUTPUT_PATH")]
+    pub output_paths: Vec<String>,
+}
+
+pub fn do_generate(args: &GenerateArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let schema = match (&args.example_path, &args.schema_path) {
+        (Some(example_path), _) => example_schema(example_path, args, profile)?,
+        (None, Some(schema_path)) => read_schema(schema_path, profile)?,
+        (None, None) => Err(anyhow!("--example or --schema is required"))?,
+    };
+    let mut spec = match &args.spec_path {
+        Some(spec_path) => read_spec(spec_path, profile)?,
+        None => Spec::default(),
+    };
+
+    // each column has its own random values, seeded by the ones of the command
+    let mut rng = match args.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+    let mut generators = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            ColumnGenerator::new(
+                field,
+                spec.columns.remove(field.name()),
+                args.null_density,
+                args.true_density,
+                StdRng::from_rng(&mut rng).map_err(anyhow::Error::from)?,
+                profile,
+            )
+        })
+        .collect::<InnoFileResult<Vec<_>>>()?;
+    if let Some(column) = spec.columns.keys().next() {
+        Err(anyhow!("Column of spec not found in schema: {column}"))?
+    }
+
+    let mut arrow_writers = args
+        .output_paths
+        .iter()
+        .map(|output_path| {
+            let file = create_file(output_path, args.to_format.as_deref(), profile)?;
+            let arrow_writer = ArrowWriterBuilder::new(schema.clone())
+                .with_file_format(args.to_format.as_deref())
+                .build_sync(file)?;
+            Ok((output_path, arrow_writer))
+        })
+        .collect::<InnoFileResult<Vec<_>>>()?;
+
+    // generate and write records batch by batch, so that their size is not bounded by memory
+    let mut remaining = args.size;
+    while remaining > 0 {
+        let len = remaining.min(args.batch_size.get());
+        let columns = generators
+            .iter_mut()
+            .map(|generator| generator.generate(len))
+            .collect::<InnoFileResult<Vec<_>>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(len));
+        let record_batch = RecordBatch::try_new_with_options(schema.clone(), columns, &options)?;
+        for (output_path, arrow_writer) in &mut arrow_writers {
+            arrow_writer
+                .write(&record_batch)
+                .with_context(|| ErrorContext::new(Operation::Write).with_path(output_path))?;
+        }
+        remaining -= len;
+    }
+
+    for (output_path, arrow_writer) in arrow_writers {
+        arrow_writer
+            .close()
+            .with_context(|| ErrorContext::new(Operation::Close).with_path(output_path))?;
+    }
+
+    Ok(())
+}
+
+/// Returns the schema of the records of the example file.
+fn example_schema(
+    example_path: &str,
+    args: &GenerateArgs,
+    profile: Option<&str>,
+) -> InnoFileResult<SchemaRef> {
+    let file = open_file(example_path, args.from_format.as_deref(), profile)?;
+    let mut arrow_reader = ArrowReaderBuilder::new()
+        .with_file_format(args.from_format.as_deref())
+        .build_sync(file)?;
+    Ok(arrow_reader
+        .next()
+        .ok_or_else(|| anyhow!("Example file contains no records"))??
+        .schema())
+}
+
+fn read_spec(path: &str, profile: Option<&str>) -> InnoFileResult<Spec> {
+    let reader = file_system(path, profile)?.open(path)?.reader()?;
+    let spec = serde_json::from_reader(reader)
+        .map_err(anyhow::Error::from)
+        .with_context(|| ErrorContext::new(Operation::Read).with_path(path))?;
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write as _, sync::Arc};
+
+    use arrow::{
+        array::{AsArray as _, Int64Array},
+        compute::concat_batches,
+        datatypes::{DataType, Field, Int64Type, Schema},
+    };
+    use clap::Parser as _;
+    use innofile::sync::fs::{local::LocalFS, FileSystem as _};
+    use tempfile::tempdir;
+
+    use crate::{Cli, Commands};
+
+    use super::*;
+
+    fn generate_args(args: &[&str]) -> GenerateArgs {
+        let args = ["innofile", "generate"].iter().chain(args);
+        match Cli::parse_from(args).command {
+            Commands::Generate(args) => args,
+            command => panic!("Generate command not parsed correctly: {command:?}"),
+        }
+    }
+
+    fn write_file(path: &str, content: &str) -> InnoFileResult<()> {
+        let mut writer = LocalFS.create(path)?.writer()?;
+        writer.write_all(content.as_bytes())?;
+        writer.close()
+    }
+
+    fn read_batch(path: &str) -> InnoFileResult<RecordBatch> {
+        let file = Arc::from(LocalFS.open(path)?);
+        let arrow_reader = ArrowReaderBuilder::new().build_sync(file)?;
+        let schema = arrow_reader.schema();
+        let batches = arrow_reader.collect::<InnoFileResult<Vec<_>>>()?;
+        Ok(concat_batches(&schema, &batches)?)
+    }
+
+    #[test]
+    fn test_generate_from_spec() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let dir = dir.path().to_str().unwrap();
+        let (schema_path, spec_path) = (format!("{dir}/schema.json"), format!("{dir}/spec.json"));
+        let customers_path = format!("{dir}/customers.csv");
+        write_file(&customers_path, "id,name\n7,a\n8,b\n9,c\n")?;
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("customer_id", DataType::Int64, false),
+            Field::new("email", DataType::Utf8, true),
+            Field::new("amount", DataType::Float64, true),
+            Field::new("status", DataType::Utf8, false),
+        ]);
+        write_file(&schema_path, &serde_json::to_string(&schema).unwrap())?;
+        let spec = format!(
+            r#"{{"columns": {{
+                "id": {{"type": "sequence", "start": 1}},
+                "customer_id": {{"type": "reference", "path": "{customers_path}", "column": "id"}},
+                "email": {{"type": "email", "unique": true, "null_density": 0.1}},
+                "amount": {{"type": "normal", "mean": 100, "std_dev": 15}},
+                "status": {{"type": "enum", "values": ["new", "paid"], "weights": [1, 3]}}
+            }}}}"#
+        );
+        write_file(&spec_path, &spec)?;
+
+        let output_path = format!("{dir}/orders.parquet");
+        let args = generate_args(&[
+            "--schema",
+            &schema_path,
+            "--spec",
+            &spec_path,
+            "--size",
+            "25",
+            "--batch-size",
+            "10",
+            "--seed",
+            "42",
+            &output_path,
+        ]);
+        do_generate(&args, None)?;
+        let batch = read_batch(&output_path)?;
+        assert_eq!(batch.schema().as_ref(), &schema);
+        assert_eq!(
+            batch.column(0).as_primitive(),
+            &Int64Array::from_iter_values(1..=25)
+        );
+        let customer_ids = batch.column(1).as_primitive::<Int64Type>();
+        assert!(customer_ids.values().iter().all(|id| (7..=9).contains(id)));
+        let mut emails = batch.column(2).as_string::<i32>().iter().flatten();
+        assert!(emails.all(|email| email.contains('@')));
+        let mut status = batch.column(4).as_string::<i32>().iter().flatten();
+        assert!(status.all(|status| ["new", "paid"].contains(&status)));
+
+        // the same seed generates the same records
+        do_generate(&args, None)?;
+        assert_eq!(read_batch(&output_path)?, batch);
+
+        write_file(
+            &spec_path,
+            r#"{"columns": {"missing": {"type": "random"}}}"#,
+        )?;
+        assert!(do_generate(&args, None).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,690 @@
+use std::{
+    collections::{HashMap, HashSet},
+    ops::RangeInclusive,
+    sync::Arc,
+};
+
+use anyhow::anyhow;
+use arrow::{
+    array::{
+        new_null_array, Array, ArrayRef, BinaryArray, BooleanArray, FixedSizeBinaryArray,
+        Float64Array, GenericListArray, Int16Array, Int32Array, Int64Array, Int8Array,
+        OffsetSizeTrait, StringArray, StructArray, TimestampMillisecondArray, UInt16Array,
+        UInt32Array, UInt64Array, UInt8Array,
+    },
+    buffer::OffsetBuffer,
+    compute::{
+        cast, cast_with_options, concat, kernels::cast_utils::string_to_timestamp_nanos, nullif,
+        take, CastOptions,
+    },
+    datatypes::{DataType, FieldRef, TimeUnit},
+    row::{OwnedRow, RowConverter, SortField},
+};
+use innofile::{
+    arrow::ArrowReaderBuilder,
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+};
+use rand::{
+    distributions::{Alphanumeric, Distribution as _, Uniform, WeightedIndex},
+    rngs::StdRng,
+    Rng,
+};
+use rand_distr::{Exp, LogNormal, Normal, Poisson};
+use serde::Deserialize;
+
+use crate::open_file;
+
+/// Maximum number of repetitions of unbounded regex repetitions such as `*` and `+`.
+const MAX_REPEAT: u32 = 16;
+
+/// Number of consecutive attempts generating no new value before giving up on unique values.
+const MAX_UNIQUE_ATTEMPTS: usize = 100;
+
+/// Milliseconds since epoch of 2100-01-01, the end of randomly generated timestamps.
+const MAX_TIMESTAMP_MS: i64 = 4_102_444_800_000;
+
+const FIRST_NAMES: [&str; 20] = [
+    "Alice", "Bob", "Carla", "David", "Emma", "Felix", "Grace", "Hugo", "Iris", "James", "Kira",
+    "Liam", "Maya", "Noah", "Olivia", "Paul", "Quinn", "Rosa", "Samuel", "Tara",
+];
+
+const LAST_NAMES: [&str; 20] = [
+    "Anderson", "Brown", "Chen", "Dubois", "Evans", "Fischer", "Garcia", "Hansen", "Ito",
+    "Johnson", "Kowalski", "Lopez", "Martin", "Nguyen", "Okafor", "Patel", "Rossi", "Smith",
+    "Tanaka", "Weber",
+];
+
+const CITIES: [&str; 16] = [
+    "Amsterdam",
+    "Berlin",
+    "Buenos Aires",
+    "Cairo",
+    "Chicago",
+    "Lagos",
+    "Lisbon",
+    "London",
+    "Melbourne",
+    "Mumbai",
+    "Paris",
+    "Seoul",
+    "Shanghai",
+    "Tokyo",
+    "Toronto",
+    "Vienna",
+];
+
+const EMAIL_DOMAINS: [&str; 4] = ["example.com", "example.net", "example.org", "mail.test"];
+
+/// Spec file of the generators of columns by name, the other columns being generated randomly.
+#[derive(Debug, Default, Deserialize)]
+pub struct Spec {
+    #[serde(default)]
+    pub columns: HashMap<String, ColumnSpec>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ColumnSpec {
+    #[serde(flatten)]
+    pub kind: Kind,
+
+    /// Null density of the column, instead of the one given for all columns
+    pub null_density: Option<f32>,
+
+    /// Whether non-null values of the column are all different
+    #[serde(default)]
+    pub unique: bool,
+}
+
+/// Kinds of column generators, whose values are cast to the type of the column.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Kind {
+    /// Random values of the type of the column
+    Random,
+    /// Integers from `start`, incremented by `step`
+    Sequence {
+        #[serde(default)]
+        start: i64,
+        #[serde(default = "default_step")]
+        step: i64,
+    },
+    /// Numbers uniformly distributed between `min` and `max`, both included
+    Range { min: f64, max: f64 },
+    /// Numbers normally distributed around `mean`, with standard deviation `std_dev`
+    Normal { mean: f64, std_dev: f64 },
+    /// Positive numbers whose logarithm is normally distributed around `mean`, with standard
+    /// deviation `std_dev`
+    LogNormal { mean: f64, std_dev: f64 },
+    /// Positive numbers exponentially distributed with rate `lambda`
+    Exponential { lambda: f64 },
+    /// Counts of events Poisson distributed with mean `lambda`
+    Poisson { lambda: f64 },
+    /// Strings matching a regular expression
+    Regex { pattern: String },
+    /// Values picked from `values`, uniformly or by `weights`
+    Enum {
+        values: Vec<serde_json::Value>,
+        weights: Option<Vec<f64>>,
+    },
+    /// First names such as `Alice`
+    FirstName,
+    /// Last names such as `Smith`
+    LastName,
+    /// First and last names separated by a space
+    Name,
+    /// Email addresses made of a first name, a last name and a number
+    Email,
+    /// Names of large cities
+    City,
+    /// Timestamps uniformly distributed between dates or times `start` and `end`
+    Date { start: String, end: String },
+    /// Values picked from `column` of the file at `path`, such as the keys of another table
+    Reference { path: String, column: String },
+}
+
+fn default_step() -> i64 {
+    1
+}
+
+type Sampler<T> = Box<dyn FnMut(&mut StdRng) -> T + Send>;
+
+enum Source {
+    Random {
+        true_density: f32,
+    },
+    Sequence {
+        next: i64,
+        step: i64,
+    },
+    Integers(RangeInclusive<i64>),
+    Numbers(Sampler<f64>),
+    Strings(Sampler<String>),
+    Timestamps(RangeInclusive<i64>),
+    Sample {
+        values: ArrayRef,
+        weights: Option<WeightedIndex<f64>>,
+    },
+}
+
+/// Generates the values of a column, chunk by chunk.
+pub struct ColumnGenerator {
+    field: FieldRef,
+    source: Source,
+    null_density: f32,
+    unique: Option<(RowConverter, HashSet<OwnedRow>)>,
+    rng: StdRng,
+}
+
+impl ColumnGenerator {
+    pub fn new(
+        field: &FieldRef,
+        spec: Option<ColumnSpec>,
+        null_density: f32,
+        true_density: f32,
+        rng: StdRng,
+        profile: Option<&str>,
+    ) -> InnoFileResult<Self> {
+        let spec = spec.unwrap_or(ColumnSpec {
+            kind: Kind::Random,
+            null_density: None,
+            unique: false,
+        });
+        let null_density = match spec.null_density {
+            Some(_) if !field.is_nullable() => Err(anyhow!(
+                "Null density given for non-nullable column {}",
+                field.name()
+            ))?,
+            Some(null_density) => null_density,
+            None if field.is_nullable() => null_density,
+            None => 0.0,
+        };
+        for density in [null_density, true_density] {
+            if !(0.0..=1.0).contains(&density) {
+                Err(anyhow!("Density must be between 0 and 1, found {density}"))?
+            }
+        }
+        if spec.unique && matches!(spec.kind, Kind::Sequence { .. }) {
+            // rejected candidates would consume values of the sequence
+            Err(anyhow!(
+                "Unique values of sequence column {} not supported, sequences with a non-zero \
+                 step are unique already",
+                field.name()
+            ))?
+        }
+        let unique = match spec.unique {
+            true => {
+                let converter = RowConverter::new(vec![SortField::new(field.data_type().clone())])?;
+                Some((converter, HashSet::new()))
+            }
+            false => None,
+        };
+        let source = Self::source(field, spec.kind, true_density, profile)
+            .map_err(|e| anyhow!("Invalid generator of column {}: {e}", field.name()))?;
+        Ok(Self {
+            field: Arc::clone(field),
+            source,
+            null_density,
+            unique,
+            rng,
+        })
+    }
+
+    fn source(
+        field: &FieldRef,
+        kind: Kind,
+        true_density: f32,
+        profile: Option<&str>,
+    ) -> InnoFileResult<Source> {
+        fn numbers<D: rand::distributions::Distribution<f64> + Send + 'static>(
+            distribution: D,
+        ) -> Source {
+            Source::Numbers(Box::new(move |rng| distribution.sample(rng)))
+        }
+
+        fn strings(sample: impl FnMut(&mut StdRng) -> String + Send + 'static) -> Source {
+            Source::Strings(Box::new(sample))
+        }
+
+        Ok(match kind {
+            Kind::Random => Source::Random { true_density },
+            Kind::Sequence { start, step } => Source::Sequence { next: start, step },
+            Kind::Range { min, max } if min > max => {
+                Err(anyhow!("Range minimum {min} greater than maximum {max}"))?
+            }
+            Kind::Range { min, max } if field.data_type().is_integer() => {
+                let (min, max) = (min.ceil() as i64, max.floor() as i64);
+                if min > max {
+                    Err(anyhow!("Range contains no integer"))?
+                }
+                Source::Integers(min..=max)
+            }
+            Kind::Range { min, max } => numbers(Uniform::new_inclusive(min, max)),
+            Kind::Normal { mean, std_dev } => {
+                numbers(Normal::new(mean, std_dev).map_err(anyhow::Error::from)?)
+            }
+            Kind::LogNormal { mean, std_dev } => {
+                numbers(LogNormal::new(mean, std_dev).map_err(anyhow::Error::from)?)
+            }
+            Kind::Exponential { lambda } => numbers(Exp::new(lambda).map_err(anyhow::Error::from)?),
+            Kind::Poisson { lambda } => numbers(Poisson::new(lambda).map_err(anyhow::Error::from)?),
+            Kind::Regex { pattern } => {
+                let regex = rand_regex::Regex::compile(&pattern, MAX_REPEAT)
+                    .map_err(anyhow::Error::from)?;
+                strings(move |rng| rng.sample(&regex))
+            }
+            Kind::Enum { values, weights } => {
+                if values.is_empty() {
+                    Err(anyhow!("Enum contains no value"))?
+                }
+                let weights = match weights {
+                    Some(weights) if weights.len() != values.len() => Err(anyhow!(
+                        "Enum contains {} values but {} weights",
+                        values.len(),
+                        weights.len()
+                    ))?,
+                    Some(weights) => {
+                        Some(WeightedIndex::new(weights).map_err(anyhow::Error::from)?)
+                    }
+                    None => None,
+                };
+                let values = values.into_iter().map(|value| match value {
+                    serde_json::Value::Null => None,
+                    serde_json::Value::String(value) => Some(value),
+                    value => Some(value.to_string()),
+                });
+                Source::Sample {
+                    values: Arc::new(StringArray::from_iter(values)),
+                    weights,
+                }
+            }
+            Kind::FirstName => strings(|rng| pick(rng, &FIRST_NAMES).to_string()),
+            Kind::LastName => strings(|rng| pick(rng, &LAST_NAMES).to_string()),
+            Kind::Name => {
+                strings(|rng| format!("{} {}", pick(rng, &FIRST_NAMES), pick(rng, &LAST_NAMES)))
+            }
+            Kind::Email => strings(|rng| {
+                format!(
+                    "{}.{}{}@{}",
+                    pick(rng, &FIRST_NAMES).to_lowercase(),
+                    pick(rng, &LAST_NAMES).to_lowercase(),
+                    rng.gen_range(1..1000),
+                    pick(rng, &EMAIL_DOMAINS)
+                )
+            }),
+            Kind::City => strings(|rng| pick(rng, &CITIES).to_string()),
+            Kind::Date { start, end } => {
+                let start = string_to_timestamp_nanos(&start)? / 1_000_000;
+                let end = string_to_timestamp_nanos(&end)? / 1_000_000;
+                if start > end {
+                    Err(anyhow!("Date start after end"))?
+                }
+                Source::Timestamps(start..=end)
+            }
+            Kind::Reference { path, column } => {
+                let values = read_column(&path, &column, profile)?;
+                if values.is_empty() {
+                    Err(anyhow!("Column {column} of {path} contains no value"))?
+                }
+                Source::Sample {
+                    values,
+                    weights: None,
+                }
+            }
+        })
+    }
+
+    /// Generates the next `len` values of the column.
+    pub fn generate(&mut self, len: usize) -> InnoFileResult<ArrayRef> {
+        let array = match self.unique.is_some() {
+            true => self.generate_unique(len)?,
+            false => self.generate_values(len)?,
+        };
+        if self.null_density == 0.0 {
+            return Ok(array);
+        }
+        let density = f64::from(self.null_density);
+        let mask = (0..len)
+            .map(|_| Some(self.rng.gen_bool(density)))
+            .collect::<BooleanArray>();
+        Ok(nullif(&array, &mask)?)
+    }
+
+    /// Generates values until `len` values never generated before are found.
+    fn generate_unique(&mut self, len: usize) -> InnoFileResult<ArrayRef> {
+        let mut chunks = vec![];
+        let (mut remaining, mut attempts) = (len, 0);
+        while remaining > 0 {
+            let candidates = self.generate_values(remaining)?;
+            let Some((converter, seen)) = &mut self.unique else {
+                unreachable!("unique values generated without seen values");
+            };
+            let rows = converter.convert_columns(&[Arc::clone(&candidates)])?;
+            let indices = rows
+                .iter()
+                .enumerate()
+                .filter(|(i, row)| candidates.is_valid(*i) && seen.insert(row.owned()))
+                .map(|(i, _)| i as u64)
+                .collect::<UInt64Array>();
+            if indices.is_empty() {
+                attempts += 1;
+                if attempts == MAX_UNIQUE_ATTEMPTS {
+                    Err(anyhow!(
+                        "Not enough unique values of column {} to generate",
+                        self.field.name()
+                    ))?
+                }
+                continue;
+            }
+            attempts = 0;
+            remaining -= indices.len();
+            chunks.push(take(&candidates, &indices, None)?);
+        }
+        match chunks.len() {
+            1 => Ok(chunks.remove(0)),
+            _ => Ok(concat(
+                &chunks.iter().map(|c| c.as_ref()).collect::<Vec<_>>(),
+            )?),
+        }
+    }
+
+    /// Generates `len` values of the column type, possibly repeated.
+    fn generate_values(&mut self, len: usize) -> InnoFileResult<ArrayRef> {
+        let rng = &mut self.rng;
+        let array: ArrayRef = match &mut self.source {
+            Source::Random { true_density } => {
+                random_array(rng, self.field.data_type(), len, *true_density)?
+            }
+            Source::Sequence { next, step } => {
+                let start = *next;
+                *next = next.wrapping_add(step.wrapping_mul(len as i64));
+                Arc::new(Int64Array::from_iter_values(
+                    (0..len as i64).map(|i| start.wrapping_add(i.wrapping_mul(*step))),
+                ))
+            }
+            Source::Integers(range) => Arc::new(Int64Array::from_iter_values(
+                (0..len).map(|_| rng.gen_range(range.clone())),
+            )),
+            Source::Numbers(sample) => Arc::new(Float64Array::from_iter_values(
+                (0..len).map(|_| sample(rng)),
+            )),
+            Source::Strings(sample) => {
+                Arc::new(StringArray::from_iter_values((0..len).map(|_| sample(rng))))
+            }
+            Source::Timestamps(range) => Arc::new(TimestampMillisecondArray::from_iter_values(
+                (0..len).map(|_| rng.gen_range(range.clone())),
+            )),
+            Source::Sample { values, weights } => {
+                let indices = (0..len).map(|_| match weights {
+                    Some(weights) => weights.sample(rng) as u64,
+                    None => rng.gen_range(0..values.len() as u64),
+                });
+                take(values, &UInt64Array::from_iter_values(indices), None)?
+            }
+        };
+        if array.data_type() == self.field.data_type() {
+            return Ok(array);
+        }
+        let options = CastOptions {
+            safe: false,
+            ..Default::default()
+        };
+        let array = cast_with_options(&array, self.field.data_type(), &options)
+            .map_err(|e| anyhow!("Invalid values of column {}: {e}", self.field.name()))?;
+        Ok(array)
+    }
+}
+
+fn pick<'a>(rng: &mut StdRng, values: &[&'a str]) -> &'a str {
+    values[rng.gen_range(0..values.len())]
+}
+
+/// Reads all values of `column` of the file at `path`.
+fn read_column(path: &str, column: &str, profile: Option<&str>) -> InnoFileResult<ArrayRef> {
+    let file = open_file(path, None, profile)?;
+    let arrow_reader = ArrowReaderBuilder::new().build_sync(file)?;
+    let index = arrow_reader.schema().index_of(column)?;
+    let read_context = || ErrorContext::new(Operation::Read).with_path(path);
+    let columns = arrow_reader
+        .map(|batch| Ok(Arc::clone(batch.with_context(read_context)?.column(index))))
+        .collect::<InnoFileResult<Vec<_>>>()?;
+    match columns.is_empty() {
+        true => Err(anyhow!("{path} contains no records"))?,
+        false => Ok(concat(
+            &columns.iter().map(|c| c.as_ref()).collect::<Vec<_>>(),
+        )?),
+    }
+}
+
+/// Generates `len` random values of `data_type` without nulls, nested ones included.
+fn random_array(
+    rng: &mut StdRng,
+    data_type: &DataType,
+    len: usize,
+    true_density: f32,
+) -> InnoFileResult<ArrayRef> {
+    macro_rules! primitive {
+        ($array:ty) => {
+            Arc::new(<$array>::from_iter_values((0..len).map(|_| rng.gen())))
+        };
+    }
+
+    // values of other types are cast from random values of a similar type
+    let similar = |data_type: &DataType| -> DataType {
+        match data_type {
+            DataType::Float16 | DataType::Float32 => DataType::Float64,
+            DataType::LargeUtf8 | DataType::Utf8View => DataType::Utf8,
+            DataType::LargeBinary | DataType::BinaryView => DataType::Binary,
+            DataType::Date32 | DataType::Date64 | DataType::Timestamp(_, _) => {
+                DataType::Timestamp(TimeUnit::Millisecond, None)
+            }
+            DataType::Time32(_) | DataType::Time64(_) => DataType::Time32(TimeUnit::Millisecond),
+            DataType::Duration(_) => DataType::Int32,
+            DataType::Decimal128(_, _) | DataType::Decimal256(_, _) => DataType::Float64,
+            DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+            data_type => data_type.clone(),
+        }
+    };
+
+    let array: ArrayRef = match data_type {
+        DataType::Null => new_null_array(data_type, len),
+        DataType::Boolean => {
+            let density = f64::from(true_density);
+            Arc::new(BooleanArray::from_iter(
+                (0..len).map(|_| Some(rng.gen_bool(density))),
+            ))
+        }
+        DataType::Int8 => primitive!(Int8Array),
+        DataType::Int16 => primitive!(Int16Array),
+        DataType::Int32 => primitive!(Int32Array),
+        DataType::Int64 => primitive!(Int64Array),
+        DataType::UInt8 => primitive!(UInt8Array),
+        DataType::UInt16 => primitive!(UInt16Array),
+        DataType::UInt32 => primitive!(UInt32Array),
+        DataType::UInt64 => primitive!(UInt64Array),
+        DataType::Float64 => primitive!(Float64Array),
+        DataType::Utf8 => Arc::new(StringArray::from_iter_values((0..len).map(|_| {
+            let len = rng.gen_range(1..=16);
+            (0..len)
+                .map(|_| char::from(rng.sample(Alphanumeric)))
+                .collect::<String>()
+        }))),
+        DataType::Binary => Arc::new(BinaryArray::from_iter_values((0..len).map(|_| {
+            let len = rng.gen_range(1..=16);
+            (0..len).map(|_| rng.gen::<u8>()).collect::<Vec<_>>()
+        }))),
+        DataType::FixedSizeBinary(size) => {
+            let values = (0..len).map(|_| (0..*size).map(|_| rng.gen::<u8>()).collect::<Vec<_>>());
+            match len {
+                0 => Arc::new(FixedSizeBinaryArray::new_null(*size, 0)),
+                _ => Arc::new(FixedSizeBinaryArray::try_from_iter(values)?),
+            }
+        }
+        DataType::Timestamp(TimeUnit::Millisecond, None) => {
+            Arc::new(TimestampMillisecondArray::from_iter_values(
+                (0..len).map(|_| rng.gen_range(0..MAX_TIMESTAMP_MS)),
+            ))
+        }
+        DataType::Time32(TimeUnit::Millisecond) => {
+            let values = (0..len).map(|_| rng.gen_range(0..86_400_000));
+            cast(&Int32Array::from_iter_values(values), data_type)?
+        }
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale)
+            if *scale >= 0 =>
+        {
+            let digits = i32::from(*precision) - i32::from(*scale);
+            let max = 10f64.powi(digits.min(15));
+            let values = (0..len).map(|_| rng.gen_range(0.0..max));
+            cast(&Float64Array::from_iter_values(values), data_type)?
+        }
+        DataType::List(field) => random_list::<i32>(rng, field, len, true_density)?,
+        DataType::LargeList(field) => random_list::<i64>(rng, field, len, true_density)?,
+        DataType::Struct(fields) => {
+            let columns = fields
+                .iter()
+                .map(|field| random_array(rng, field.data_type(), len, true_density))
+                .collect::<InnoFileResult<Vec<_>>>()?;
+            Arc::new(StructArray::try_new(fields.clone(), columns, None)?)
+        }
+        data_type if similar(data_type) != *data_type => {
+            let array = random_array(rng, &similar(data_type), len, true_density)?;
+            cast(&array, data_type)?
+        }
+        data_type => Err(anyhow!(
+            "Generating random values of {data_type} not supported"
+        ))?,
+    };
+    Ok(array)
+}
+
+/// Generates `len` random lists of up to 4 random values each.
+fn random_list<O: OffsetSizeTrait>(
+    rng: &mut StdRng,
+    field: &FieldRef,
+    len: usize,
+    true_density: f32,
+) -> InnoFileResult<ArrayRef> {
+    let lengths = (0..len)
+        .map(|_| rng.gen_range(0..=4))
+        .collect::<Vec<usize>>();
+    let values = random_array(rng, field.data_type(), lengths.iter().sum(), true_density)?;
+    let offsets = OffsetBuffer::<O>::from_lengths(lengths);
+    Ok(Arc::new(GenericListArray::<O>::try_new(
+        Arc::clone(field),
+        offsets,
+        values,
+        None,
+    )?))
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::AsArray as _,
+        datatypes::{Field, Fields, Int64Type},
+    };
+    use rand::SeedableRng as _;
+
+    use super::*;
+
+    fn generator(data_type: DataType, spec: &str) -> InnoFileResult<ColumnGenerator> {
+        let field = Arc::new(Field::new("column", data_type, true));
+        let spec = serde_json::from_str(spec).map_err(anyhow::Error::from)?;
+        ColumnGenerator::new(&field, spec, 0.0, 0.5, StdRng::seed_from_u64(7), None)
+    }
+
+    #[test]
+    fn test_column_generators() -> InnoFileResult<()> {
+        let values = generator(DataType::Int32, r#"{"type": "range", "min": 1, "max": 3}"#)?
+            .generate(100)?;
+        let values = values.as_primitive::<arrow::datatypes::Int32Type>();
+        assert!(values.values().iter().all(|v| (1..=3).contains(v)));
+
+        let mut sequence = generator(
+            DataType::Int64,
+            r#"{"type": "sequence", "start": 10, "step": 5}"#,
+        )?;
+        sequence.generate(2)?;
+        let values = sequence.generate(2)?;
+        assert_eq!(values.as_primitive::<Int64Type>().values(), &[20, 25]);
+        assert!(generator(DataType::Int64, r#"{"type": "sequence", "unique": true}"#).is_err());
+
+        let values = generator(
+            DataType::Utf8,
+            r#"{"type": "regex", "pattern": "[A-Z]{3}-[0-9]{2}", "unique": true}"#,
+        )?
+        .generate(50)?;
+        let values = values.as_string::<i32>();
+        assert!(values.iter().flatten().all(|v| {
+            let (letters, digits) = v.split_once('-').unwrap();
+            letters.len() == 3
+                && letters.chars().all(|c| c.is_ascii_uppercase())
+                && digits.len() == 2
+                && digits.chars().all(|c| c.is_ascii_digit())
+        }));
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 50);
+
+        // unique values run out after all enum values were generated
+        let mut unique = generator(
+            DataType::Int64,
+            r#"{"type": "enum", "values": [1, 2, 3], "unique": true}"#,
+        )?;
+        let mut values = unique
+            .generate(3)?
+            .as_primitive::<Int64Type>()
+            .values()
+            .to_vec();
+        values.sort();
+        assert_eq!(values, [1, 2, 3]);
+        assert!(unique.generate(1).is_err());
+
+        let values = generator(
+            DataType::Date32,
+            r#"{"type": "date", "start": "2024-01-01", "end": "2024-01-31", "null_density": 0.5}"#,
+        )?
+        .generate(100)?;
+        let days = values.as_primitive::<arrow::datatypes::Date32Type>();
+        assert!(days.null_count() > 0 && days.null_count() < 100);
+        assert!(days
+            .iter()
+            .flatten()
+            .all(|day| (19723..=19753).contains(&day)));
+
+        assert!(generator(DataType::Utf8, r#"{"type": "range", "min": 2, "max": 1}"#).is_err());
+        assert!(generator(DataType::Utf8, r#"{"type": "enum", "values": []}"#).is_err());
+        assert!(generator(DataType::Utf8, r#"{"type": "unknown"}"#).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_random_array() -> InnoFileResult<()> {
+        let mut rng = StdRng::seed_from_u64(7);
+        let struct_type = DataType::Struct(Fields::from(vec![
+            Field::new("a", DataType::Utf8, true),
+            Field::new_list("b", Field::new_list_field(DataType::Float32, true), true),
+        ]));
+        for data_type in [
+            DataType::Boolean,
+            DataType::UInt16,
+            DataType::Float16,
+            DataType::LargeUtf8,
+            DataType::Date64,
+            DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            DataType::Time64(TimeUnit::Nanosecond),
+            DataType::Decimal128(10, 2),
+            DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+            struct_type,
+        ] {
+            let array = random_array(&mut rng, &data_type, 10, 0.5)?;
+            assert_eq!(array.data_type(), &data_type);
+            assert_eq!(array.len(), 10);
+        }
+
+        // the same seed generates the same values
+        let random = |seed| random_array(&mut StdRng::seed_from_u64(seed), &DataType::Utf8, 5, 0.5);
+        assert_eq!(random(1)?.as_ref(), random(1)?.as_ref());
+        assert_ne!(random(1)?.as_ref(), random(2)?.as_ref());
+
+        Ok(())
+    }
+}
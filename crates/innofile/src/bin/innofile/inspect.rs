@@ -0,0 +1,502 @@
+use std::{fmt::Write as _, sync::Arc};
+
+use clap::{Args, ValueEnum};
+use innofile::{
+    error::{InnoFileError, InnoFileResult},
+    sync::fs::FileRef,
+    utils::path_extension,
+};
+use serde_json::{json, Value};
+
+use crate::file_system;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InspectFormat {
+    /// Indented fields
+    Text,
+    /// JSON object
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct InspectArgs {
+    /// Parquet or ORC file path to print the physical metadata of
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// Output format of the metadata
+    #[arg(long, value_enum, default_value_t = InspectFormat::Text)]
+    pub format: InspectFormat,
+}
+
+pub fn do_inspect(args: &InspectArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let file_system = file_system(&args.path, profile)?;
+    let file: FileRef = Arc::from(file_system.open(&args.path)?);
+
+    let file_format = path_extension(&args.path)?.ok_or(InnoFileError::FileFormatNotFound)?;
+    let metadata = match file_format.to_lowercase().as_str() {
+        #[cfg(feature = "parquet")]
+        "parquet" => {
+            use innofile::sync::arrow::parquet::ArrowParquetReader;
+            parquet_file::inspect(ArrowParquetReader::new(file)?.metadata())
+        }
+        #[cfg(feature = "inspect")]
+        "orc" => {
+            use innofile::sync::arrow::orc::ArrowOrcReader;
+            let arrow_reader = ArrowOrcReader::new(Arc::clone(&file))?;
+            orc_file::inspect(&file, arrow_reader.metadata())?
+        }
+        _ => Err(InnoFileError::FileFormatNotSupported(file_format))?,
+    };
+
+    match args.format {
+        InspectFormat::Text => print!("{}", format_text(&metadata)),
+        InspectFormat::Json => println!("{:#}", metadata),
+    }
+    Ok(())
+}
+
+/// Formats metadata as indented `key: value` lines, leaving out null values.
+fn format_text(metadata: &Value) -> String {
+    let mut text = String::new();
+    write_text(&mut text, metadata, 0);
+    text
+}
+
+fn write_text(text: &mut String, value: &Value, indent: usize) {
+    let Value::Object(fields) = value else {
+        _ = writeln!(text, "{:indent$}{}", "", scalar(value));
+        return;
+    };
+    for (key, value) in fields {
+        match value {
+            Value::Null => {}
+            Value::Object(object) if !object.is_empty() => {
+                _ = writeln!(text, "{:indent$}{}:", "", key);
+                write_text(text, value, indent + 2);
+            }
+            Value::Array(items) if items.iter().any(Value::is_object) => {
+                _ = writeln!(text, "{:indent$}{}:", "", key);
+                for item in items {
+                    // start the first field of each item on the line of its dash
+                    let mut item_text = String::new();
+                    write_text(&mut item_text, item, indent + 2);
+                    let item_text = item_text.get(indent + 2..).unwrap_or_default();
+                    _ = write!(text, "{:indent$}- {}", "", item_text);
+                }
+            }
+            value => _ = writeln!(text, "{:indent$}{}: {}", "", key, scalar(value)),
+        }
+    }
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Array(items) => {
+            let items = items.iter().map(scalar).collect::<Vec<_>>();
+            format!("[{}]", items.join(", "))
+        }
+        Value::Object(_) => "{}".to_string(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_file {
+    use parquet::file::metadata::ParquetMetaData;
+
+    use super::*;
+
+    /// Describes the row groups and column chunks of Parquet metadata.
+    pub fn inspect(metadata: &ParquetMetaData) -> Value {
+        let file_metadata = metadata.file_metadata();
+        let key_value_metadata = file_metadata
+            .key_value_metadata()
+            .into_iter()
+            .flatten()
+            .map(|key_value| (key_value.key.clone(), json!(key_value.value)))
+            .collect::<serde_json::Map<_, _>>();
+        let row_groups = metadata
+            .row_groups()
+            .iter()
+            .enumerate()
+            .map(|(index, row_group)| {
+                let columns = row_group
+                    .columns()
+                    .iter()
+                    .map(|column| {
+                        let encodings = column.encodings().iter().map(ToString::to_string);
+                        let statistics = column.statistics();
+                        json!({
+                            "path": column.column_path().string(),
+                            "physical_type": column.column_type().to_string(),
+                            "compression": column.compression().to_string(),
+                            "encodings": encodings.collect::<Vec<_>>(),
+                            "values": column.num_values(),
+                            "null_count": statistics.and_then(|s| s.null_count_opt()),
+                            "compressed_size": column.compressed_size(),
+                            "uncompressed_size": column.uncompressed_size(),
+                            "dictionary_page_offset": column.dictionary_page_offset(),
+                            "data_page_offset": column.data_page_offset(),
+                            "column_index": column.column_index_offset().is_some(),
+                            "offset_index": column.offset_index_offset().is_some(),
+                            "bloom_filter": column.bloom_filter_offset().is_some(),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                json!({
+                    "index": index,
+                    "rows": row_group.num_rows(),
+                    "total_byte_size": row_group.total_byte_size(),
+                    "compressed_size": row_group.compressed_size(),
+                    "columns": columns,
+                })
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "format": "parquet",
+            "version": file_metadata.version(),
+            "created_by": file_metadata.created_by(),
+            "rows": file_metadata.num_rows(),
+            "key_value_metadata": key_value_metadata,
+            "row_groups": row_groups,
+        })
+    }
+}
+
+#[cfg(feature = "inspect")]
+mod orc_file {
+    use std::io::Read as _;
+
+    use anyhow::anyhow;
+    use orc_rust::{compression::CompressionType, reader::metadata::FileMetadata};
+    use prost::Message as _;
+
+    use crate::stats::orc_min_max;
+
+    use super::*;
+
+    /// Upper bound of the decompressed size of an LZ4 chunk, the largest length of a chunk header.
+    const MAX_CHUNK_SIZE: usize = 1 << 23;
+
+    /// Stripe footer fields of the ORC protobuf schema, since orc-rust keeps its own private.
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct StripeFooter {
+        #[prost(message, repeated, tag = "1")]
+        streams: Vec<Stream>,
+        #[prost(message, repeated, tag = "2")]
+        columns: Vec<ColumnEncoding>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct Stream {
+        #[prost(int32, optional, tag = "1")]
+        kind: Option<i32>,
+        #[prost(uint32, optional, tag = "2")]
+        column: Option<u32>,
+        #[prost(uint64, optional, tag = "3")]
+        length: Option<u64>,
+    }
+
+    #[derive(Clone, PartialEq, prost::Message)]
+    struct ColumnEncoding {
+        #[prost(int32, optional, tag = "1")]
+        kind: Option<i32>,
+        #[prost(uint32, optional, tag = "2")]
+        dictionary_size: Option<u32>,
+    }
+
+    /// Describes the stripes of ORC metadata, reading the stripe footers from `file`.
+    pub fn inspect(file: &FileRef, metadata: &FileMetadata) -> InnoFileResult<Value> {
+        let compression = metadata.compression();
+        let columns = metadata
+            .root_data_type()
+            .children()
+            .iter()
+            .map(|column| {
+                (
+                    column.name().to_string(),
+                    json!(column.data_type().column_index()),
+                )
+            })
+            .collect::<serde_json::Map<_, _>>();
+        let user_metadata = metadata
+            .user_custom_metadata()
+            .iter()
+            .map(|(key, value)| (key.clone(), json!(String::from_utf8_lossy(value))))
+            .collect::<serde_json::Map<_, _>>();
+
+        let mut stripes = vec![];
+        for (index, stripe) in metadata.stripe_metadatas().iter().enumerate() {
+            let footer_offset = stripe.footer_offset();
+            let footer = file.read_range(footer_offset..footer_offset + stripe.footer_length())?;
+            let footer = decompress(&footer, compression.map(|c| c.compression_type()))?;
+            let footer = StripeFooter::decode(footer.as_slice()).map_err(anyhow::Error::from)?;
+
+            // streams are stored one after another from the start of the stripe
+            let mut offset = stripe.offset();
+            let streams = footer
+                .streams
+                .iter()
+                .map(|stream| {
+                    let length = stream.length.unwrap_or_default();
+                    offset += length;
+                    json!({
+                        "column": stream.column,
+                        "kind": stream_kind(stream.kind.unwrap_or_default()),
+                        "offset": offset - length,
+                        "length": length,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let encodings = footer
+                .columns
+                .iter()
+                .enumerate()
+                .map(|(column, encoding)| {
+                    json!({
+                        "column": column,
+                        "kind": encoding_kind(encoding.kind.unwrap_or_default()),
+                        "dictionary_size": encoding.dictionary_size,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let statistics = stripe
+                .column_statistics()
+                .iter()
+                .enumerate()
+                .map(|(column, statistics)| {
+                    let min_max = match statistics.type_statistics() {
+                        Some(type_statistics) => orc_min_max(type_statistics)?,
+                        None => None,
+                    };
+                    let (min, max) = min_max.unzip();
+                    Ok(json!({
+                        "column": column,
+                        "values": statistics.number_of_values(),
+                        "has_null": statistics.has_null(),
+                        "min": min,
+                        "max": max,
+                    }))
+                })
+                .collect::<InnoFileResult<Vec<_>>>()?;
+            stripes.push(json!({
+                "index": index,
+                "offset": stripe.offset(),
+                "rows": stripe.number_of_rows(),
+                "index_length": stripe.index_length(),
+                "data_length": stripe.data_length(),
+                "footer_length": stripe.footer_length(),
+                "streams": streams,
+                "encodings": encodings,
+                "statistics": statistics,
+            }));
+        }
+
+        Ok(json!({
+            "format": "orc",
+            "version": metadata.file_format_version(),
+            "compression": compression.map(|c| c.to_string()),
+            "rows": metadata.number_of_rows(),
+            "columns": columns,
+            "user_metadata": user_metadata,
+            "stripes": stripes,
+        }))
+    }
+
+    /// Decompresses ORC metadata, stored in chunks each starting with a 3 byte header of the chunk
+    /// length and whether the chunk is stored uncompressed.
+    pub(super) fn decompress(
+        bytes: &[u8],
+        compression: Option<CompressionType>,
+    ) -> InnoFileResult<Vec<u8>> {
+        let Some(compression) = compression else {
+            return Ok(bytes.to_vec());
+        };
+        let mut decompressed = vec![];
+        let mut rest = bytes;
+        while let [first, second, third, ..] = *rest {
+            let header = u32::from_le_bytes([first, second, third, 0]);
+            let length = (header >> 1) as usize;
+            let chunk = rest
+                .get(3..3 + length)
+                .ok_or_else(|| anyhow!("ORC compression chunk truncated"))?;
+            rest = &rest[3 + length..];
+            if header & 1 == 1 {
+                decompressed.extend_from_slice(chunk);
+                continue;
+            }
+            match compression {
+                CompressionType::Zlib => {
+                    flate2::read::DeflateDecoder::new(chunk).read_to_end(&mut decompressed)?;
+                }
+                CompressionType::Snappy => {
+                    let chunk = snap::raw::Decoder::new()
+                        .decompress_vec(chunk)
+                        .map_err(anyhow::Error::from)?;
+                    decompressed.extend(chunk);
+                }
+                CompressionType::Lz4 => {
+                    let chunk = lz4_flex::block::decompress(chunk, MAX_CHUNK_SIZE)
+                        .map_err(anyhow::Error::from)?;
+                    decompressed.extend(chunk);
+                }
+                CompressionType::Zstd => {
+                    zstd::Decoder::new(chunk)?.read_to_end(&mut decompressed)?;
+                }
+                CompressionType::Lzo => Err(anyhow!("LZO compressed ORC files not supported"))?,
+            }
+        }
+        Ok(decompressed)
+    }
+
+    fn stream_kind(kind: i32) -> String {
+        match kind {
+            0 => "PRESENT",
+            1 => "DATA",
+            2 => "LENGTH",
+            3 => "DICTIONARY_DATA",
+            4 => "DICTIONARY_COUNT",
+            5 => "SECONDARY",
+            6 => "ROW_INDEX",
+            7 => "BLOOM_FILTER",
+            8 => "BLOOM_FILTER_UTF8",
+            9 => "ENCRYPTED_INDEX",
+            10 => "ENCRYPTED_DATA",
+            64 => "STRIPE_STATISTICS",
+            65 => "FILE_STATISTICS",
+            kind => return kind.to_string(),
+        }
+        .to_string()
+    }
+
+    fn encoding_kind(kind: i32) -> String {
+        match kind {
+            0 => "DIRECT",
+            1 => "DICTIONARY",
+            2 => "DIRECT_V2",
+            3 => "DICTIONARY_V2",
+            kind => return kind.to_string(),
+        }
+        .to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::{
+        array::{Int64Array, RecordBatch, StringArray},
+        datatypes::{DataType, Field, Schema},
+    };
+    use innofile::fs::FileSystemBuilder;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn test_inspect() -> InnoFileResult<()> {
+        use innofile::sync::arrow::parquet::ArrowParquetReader;
+        use parquet::{
+            arrow::ArrowWriter,
+            file::{metadata::KeyValue, properties::WriterProperties},
+        };
+
+        let dir = tempdir()?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+            ],
+        )?;
+
+        let path = dir.path().join("inspect.parquet");
+        let path = path.to_str().unwrap();
+        let file_system = FileSystemBuilder::from_path(path)?.build_sync()?;
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .set_bloom_filter_enabled(true)
+            .set_key_value_metadata(Some(vec![KeyValue::new("k".into(), "v".to_string())]))
+            .build();
+        let file = file_system.create_new(path)?;
+        let mut writer =
+            ArrowWriter::try_new(file.writer()?, Arc::clone(&schema), Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let arrow_reader = ArrowParquetReader::new(Arc::from(file_system.open(path)?))?;
+        let metadata = parquet_file::inspect(arrow_reader.metadata());
+        assert_eq!(metadata["rows"], 3);
+        assert_eq!(metadata["key_value_metadata"]["k"], "v");
+        assert_eq!(metadata["row_groups"].as_array().unwrap().len(), 2);
+        let column = &metadata["row_groups"][1]["columns"][1];
+        assert_eq!(column["path"], "name");
+        assert_eq!(column["null_count"], 0);
+        assert_eq!(column["bloom_filter"], true);
+        let text = format_text(&metadata);
+        assert!(text.contains("\nrow_groups:\n- index: 0\n  rows: 2\n"));
+        assert!(text.contains("\n  - path: id\n    physical_type: INT64\n"));
+
+        Ok(())
+    }
+
+    #[cfg(feature = "inspect")]
+    #[test]
+    fn test_inspect_orc() -> InnoFileResult<()> {
+        use innofile::{arrow::ArrowWriterBuilder, sync::io::Closeable as _};
+
+        let dir = tempdir()?;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from(vec![1, 2, 3])),
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("c")])),
+            ],
+        )?;
+
+        let path = dir.path().join("inspect.orc");
+        let path = path.to_str().unwrap();
+        let file_system = FileSystemBuilder::from_path(path)?.build_sync()?;
+        let file: FileRef = Arc::from(file_system.create_new(path)?);
+        let mut arrow_writer = ArrowWriterBuilder::new(schema).build_sync(file)?;
+        arrow_writer.write(&batch)?;
+        arrow_writer.close()?;
+
+        let file: FileRef = Arc::from(file_system.open(path)?);
+        let arrow_reader = innofile::sync::arrow::orc::ArrowOrcReader::new(Arc::clone(&file))?;
+        let metadata = orc_file::inspect(&file, arrow_reader.metadata())?;
+        assert_eq!(metadata["rows"], 3);
+        assert_eq!(metadata["columns"]["name"], 2);
+        let stripe = &metadata["stripes"][0];
+        assert_eq!(stripe["rows"], 3);
+        let streams = stripe["streams"].as_array().unwrap();
+        assert!(streams
+            .iter()
+            .any(|stream| stream["column"] == 2 && stream["kind"] == "PRESENT"));
+
+        // a compressed chunk followed by an uncompressed one
+        let mut chunk = vec![];
+        let mut encoder = flate2::write::DeflateEncoder::new(&mut chunk, Default::default());
+        std::io::Write::write_all(&mut encoder, b"compressed ")?;
+        encoder.finish()?;
+        let mut bytes = ((chunk.len() as u32) << 1).to_le_bytes()[..3].to_vec();
+        bytes.extend(chunk);
+        bytes.extend([(8 << 1) | 1, 0, 0]);
+        bytes.extend(b"original");
+        let decompressed =
+            orc_file::decompress(&bytes, Some(orc_rust::compression::CompressionType::Zlib))?;
+        assert_eq!(decompressed, b"compressed original");
+
+        Ok(())
+    }
+}
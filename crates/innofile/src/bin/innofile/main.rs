@@ -0,0 +1,268 @@
+use std::process::ExitCode;
+#[cfg(feature = "sync")]
+use std::sync::Arc;
+
+#[cfg(feature = "sync")]
+use anyhow::anyhow;
+#[cfg(feature = "sync")]
+use clap::{Parser, Subcommand};
+#[cfg(feature = "sync")]
+use innofile::{
+    error::InnoFileResult,
+    fs::FileSystemBuilder,
+    sync::fs::{
+        stdio::{StdinFile, StdoutFile, STDIO_PATH},
+        FileRef, FileSystem,
+    },
+};
+
+#[cfg(all(feature = "sync", feature = "generate"))]
+use self::generate::{do_generate, GenerateArgs};
+#[cfg(all(feature = "sync", any(feature = "parquet", feature = "inspect")))]
+use self::inspect::{do_inspect, InspectArgs};
+#[cfg(feature = "sync")]
+use self::{
+    cat::{do_cat, CatArgs, Rows},
+    convert::{do_convert, ConvertArgs},
+    fs::{
+        do_cp, do_ls, do_mkdir, do_mv, do_rm, do_stat, CpArgs, LsArgs, MkdirArgs, MvArgs, RmArgs,
+        StatArgs,
+    },
+    schema::{do_schema, SchemaArgs},
+    stats::{do_count, do_stats, CountArgs, StatsArgs},
+};
+
+#[cfg(feature = "sync")]
+mod cat;
+#[cfg(feature = "sync")]
+mod convert;
+#[cfg(feature = "sync")]
+mod filter;
+#[cfg(feature = "sync")]
+mod fs;
+#[cfg(all(feature = "sync", feature = "generate"))]
+mod generate;
+#[cfg(all(feature = "sync", feature = "generate"))]
+mod generator;
+#[cfg(all(feature = "sync", any(feature = "parquet", feature = "inspect")))]
+mod inspect;
+#[cfg(feature = "sync")]
+mod schema;
+#[cfg(feature = "sync")]
+mod stats;
+
+#[cfg(feature = "sync")]
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Profile of the config file to apply to all file systems
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[cfg(feature = "sync")]
+#[derive(Debug, Subcommand)]
+enum Commands {
+    /// Print rows of a file
+    Cat(CatArgs),
+
+    /// Convert files between supported formats
+    Convert(Box<ConvertArgs>),
+
+    /// Copy files and directories, across file systems
+    Cp(CpArgs),
+
+    /// Print the number of rows of a file
+    Count(CountArgs),
+
+    /// Generate files of random records based on an example or schema file
+    #[cfg(feature = "generate")]
+    Generate(GenerateArgs),
+
+    /// Print the first rows of a file
+    Head(CatArgs),
+
+    /// Print the physical metadata of a Parquet or ORC file
+    #[cfg(any(feature = "parquet", feature = "inspect"))]
+    Inspect(InspectArgs),
+
+    /// List files and directories
+    Ls(LsArgs),
+
+    /// Create directories
+    Mkdir(MkdirArgs),
+
+    /// Move files and directories, across file systems
+    Mv(MvArgs),
+
+    /// Remove files and directories
+    Rm(RmArgs),
+
+    /// Print the schema of a file
+    Schema(SchemaArgs),
+
+    /// Print statistics of the columns of a file
+    Stats(StatsArgs),
+
+    /// Print the status of files and directories
+    Stat(StatArgs),
+
+    /// Print the last rows of a file
+    Tail(CatArgs),
+}
+
+#[cfg(feature = "sync")]
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let profile = cli.profile.as_deref();
+    let result = match &cli.command {
+        Commands::Cat(args) => do_cat(args, Rows::All, profile),
+        Commands::Convert(args) => do_convert(args, profile),
+        Commands::Count(args) => do_count(args, profile),
+        Commands::Cp(args) => do_cp(args, profile),
+        #[cfg(feature = "generate")]
+        Commands::Generate(args) => do_generate(args, profile),
+        Commands::Head(args) => do_cat(args, Rows::Head, profile),
+        #[cfg(any(feature = "parquet", feature = "inspect"))]
+        Commands::Inspect(args) => do_inspect(args, profile),
+        Commands::Ls(args) => do_ls(args, profile),
+        Commands::Mkdir(args) => do_mkdir(args, profile),
+        Commands::Mv(args) => do_mv(args, profile),
+        Commands::Rm(args) => do_rm(args, profile),
+        Commands::Schema(args) => do_schema(args, profile),
+        Commands::Stat(args) => do_stat(args, profile),
+        Commands::Stats(args) => do_stats(args, profile),
+        Commands::Tail(args) => do_cat(args, Rows::Tail, profile),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("Error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Builds the file system of `path`, applying the named profile instead of the profile of the
+/// config file matching `path`, if any. Profiles never apply to local paths.
+#[cfg(all(feature = "sync", feature = "config"))]
+fn file_system(path: &str, profile: Option<&str>) -> InnoFileResult<Box<dyn FileSystem>> {
+    FileSystemBuilder::from_path(path)?
+        .with_profile(profile)?
+        .build_sync()
+}
+
+/// Builds the file system of `path`, profiles requiring the config file.
+#[cfg(all(feature = "sync", not(feature = "config")))]
+fn file_system(path: &str, profile: Option<&str>) -> InnoFileResult<Box<dyn FileSystem>> {
+    if let Some(profile) = profile {
+        Err(anyhow!(
+            "Profile {profile} not supported, since the config feature is disabled"
+        ))?
+    }
+    FileSystemBuilder::from_path(path)?.build_sync()
+}
+
+/// Opens `path` to read, or standard input for `-`, whose file format must be given since it has
+/// no extension.
+#[cfg(feature = "sync")]
+fn open_file(path: &str, format: Option<&str>, profile: Option<&str>) -> InnoFileResult<FileRef> {
+    if path != STDIO_PATH {
+        return Ok(Arc::from(file_system(path, profile)?.open(path)?));
+    }
+    match format {
+        Some(_) => Ok(Arc::new(StdinFile::new())),
+        None => Err(anyhow!(
+            "--from-format is required to read from standard input"
+        ))?,
+    }
+}
+
+/// Creates `path` atomically to write, or standard output for `-`, whose file format must be
+/// given since it has no extension.
+#[cfg(feature = "sync")]
+fn create_file(path: &str, format: Option<&str>, profile: Option<&str>) -> InnoFileResult<FileRef> {
+    if path != STDIO_PATH {
+        return Ok(Arc::from(file_system(path, profile)?.create_atomic(path)?));
+    }
+    match format {
+        Some(_) => Ok(Arc::new(StdoutFile::new())),
+        None => Err(anyhow!(
+            "--to-format is required to write to standard output"
+        ))?,
+    }
+}
+
+#[cfg(not(feature = "sync"))]
+fn main() -> ExitCode {
+    eprintln!("Error: Commands not supported, since the sync feature is disabled");
+    ExitCode::FAILURE
+}
+
+#[cfg(feature = "sync")]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsing_convert_command() -> InnoFileResult<()> {
+        let (from_path, to_path) = ("from.orc", "to.parquet");
+        let cmd = format!("innofile convert --from {from_path} --to {to_path} --profile dev");
+        let cli = Cli::parse_from(cmd.split_whitespace());
+        assert_eq!(cli.profile.as_deref(), Some("dev"));
+        if let Commands::Convert(args) = cli.command {
+            assert_eq!(args.from_path, from_path);
+            assert_eq!(args.to_path, to_path);
+        } else {
+            panic!("Convert command not parsed correctly");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_profile_of_local_path() -> InnoFileResult<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("data.csv");
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "a\n1\n")?;
+
+        // profiles never apply to local paths, which do not even load the config file
+        let cmd = format!("innofile cat {path} --profile warehouse");
+        let cli = Cli::parse_from(cmd.split_whitespace());
+        let file = open_file(path, None, cli.profile.as_deref())?;
+        assert_eq!(file.metadata()?.len(), 4);
+        Ok(())
+    }
+
+    #[cfg(feature = "generate")]
+    #[test]
+    fn test_parsing_generate_command() -> InnoFileResult<()> {
+        let (example_path, size, null_density, true_density) = ("example.csv", 10, 0.2, 0.6);
+        let output_paths = [
+            "output.csv",
+            "output.dsv",
+            "output.psv",
+            "output.tsv",
+            "output.json",
+            "output.orc",
+            "output.parquet",
+        ];
+        let cmd = format!(
+            "innofile generate --example {example_path} --size {size} --null-density {null_density} --true-density {true_density} {}",
+            output_paths.join(" ")
+        );
+        if let Commands::Generate(args) = Cli::parse_from(cmd.split_whitespace()).command {
+            assert_eq!(args.example_path.as_deref(), Some(example_path));
+            assert_eq!(args.size, size);
+            assert_eq!(args.null_density, null_density);
+            assert_eq!(args.true_density, true_density);
+            assert_eq!(args.output_paths, output_paths);
+        } else {
+            panic!("Generate command not parsed correctly");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use std::{fmt::Write as _, path::Path, sync::Arc};
+
+use anyhow::anyhow;
+use arrow::datatypes::{DataType, FieldRef, Fields, Schema};
+use clap::{Args, ValueEnum};
+use innofile::{arrow::ArrowReaderBuilder, error::InnoFileResult};
+
+use crate::file_system;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SchemaFormat {
+    /// Tree of fields
+    Tree,
+    /// Arrow schema as JSON
+    Json,
+    /// Spark SQL table DDL
+    Spark,
+    /// Hive table DDL
+    Hive,
+    /// PostgreSQL table DDL
+    Postgres,
+}
+
+#[derive(Debug, Args)]
+pub struct SchemaArgs {
+    /// File path to print the schema of
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// Output format of the schema
+    #[arg(long, value_enum, default_value_t = SchemaFormat::Tree)]
+    pub format: SchemaFormat,
+
+    /// Number of records to infer the schema of CSV and JSON files from
+    #[arg(long)]
+    pub infer_records: Option<usize>,
+
+    /// Table name of DDL, the file name without extension by default
+    #[arg(long)]
+    pub table: Option<String>,
+}
+
+pub fn do_schema(args: &SchemaArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let file_system = file_system(&args.path, profile)?;
+    let file = Arc::from(file_system.open(&args.path)?);
+    let arrow_reader = ArrowReaderBuilder::new()
+        .with_infer_records(args.infer_records)
+        .build_sync(file)?;
+
+    let table = match &args.table {
+        Some(table) => table.clone(),
+        None => Path::new(&args.path)
+            .file_stem()
+            .map_or("data".to_string(), |stem| {
+                stem.to_string_lossy().to_string()
+            }),
+    };
+    println!(
+        "{}",
+        format_schema(&arrow_reader.schema(), args.format, &table)?
+    );
+    Ok(())
+}
+
+pub fn format_schema(schema: &Schema, format: SchemaFormat, table: &str) -> InnoFileResult<String> {
+    match format {
+        SchemaFormat::Tree => {
+            let mut tree = "root".to_string();
+            write_tree(&mut tree, schema.fields(), 1);
+            Ok(tree)
+        }
+        SchemaFormat::Json => {
+            Ok(serde_json::to_string_pretty(schema).map_err(anyhow::Error::from)?)
+        }
+        format => ddl(schema, format, table),
+    }
+}
+
+fn write_tree(tree: &mut String, fields: &Fields, depth: usize) {
+    for field in fields {
+        let type_name = match field.data_type() {
+            DataType::Struct(_) => "Struct".to_string(),
+            DataType::List(_) | DataType::LargeList(_) | DataType::FixedSizeList(_, _) => {
+                "List".to_string()
+            }
+            DataType::Map(_, _) => "Map".to_string(),
+            data_type => data_type.to_string(),
+        };
+        _ = write!(
+            tree,
+            "\n{}|-- {}: {} (nullable = {})",
+            " |   ".repeat(depth - 1) + " ",
+            field.name(),
+            type_name,
+            field.is_nullable()
+        );
+        if let Some(children) = children(field.data_type()) {
+            write_tree(tree, &children, depth + 1);
+        }
+    }
+}
+
+/// Fields nested in a data type: the fields of structs, the element of lists and the key and
+/// value of maps.
+fn children(data_type: &DataType) -> Option<Fields> {
+    match data_type {
+        DataType::Struct(fields) => Some(fields.clone()),
+        DataType::List(field) | DataType::LargeList(field) | DataType::FixedSizeList(field, _) => {
+            Some(Fields::from(vec![Arc::clone(field)]))
+        }
+        DataType::Map(field, _) => children(field.data_type()),
+        _ => None,
+    }
+}
+
+fn ddl(schema: &Schema, dialect: SchemaFormat, table: &str) -> InnoFileResult<String> {
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let not_null = match dialect {
+                // Hive doesn't support NOT NULL constraints
+                SchemaFormat::Hive => "",
+                _ if field.is_nullable() => "",
+                _ => " NOT NULL",
+            };
+            Ok(format!(
+                "  {} {}{}",
+                quote(field.name(), dialect),
+                sql_type(field.data_type(), dialect)?,
+                not_null
+            ))
+        })
+        .collect::<InnoFileResult<Vec<_>>>()?;
+    Ok(format!(
+        "CREATE TABLE {} (\n{}\n);",
+        quote(table, dialect),
+        columns.join(",\n")
+    ))
+}
+
+fn quote(name: &str, dialect: SchemaFormat) -> String {
+    match dialect {
+        SchemaFormat::Postgres => format!("\"{}\"", name.replace('"', "\"\"")),
+        _ => format!("`{}`", name.replace('`', "``")),
+    }
+}
+
+fn sql_type(data_type: &DataType, dialect: SchemaFormat) -> InnoFileResult<String> {
+    let postgres = dialect == SchemaFormat::Postgres;
+    let element = |field: &FieldRef| sql_type(field.data_type(), dialect);
+    Ok(match data_type {
+        DataType::Boolean => "BOOLEAN".to_string(),
+        DataType::Int8 if !postgres => "TINYINT".to_string(),
+        DataType::Int8 | DataType::Int16 | DataType::UInt8 => "SMALLINT".to_string(),
+        DataType::Int32 | DataType::UInt16 if postgres => "INTEGER".to_string(),
+        DataType::Int32 | DataType::UInt16 => "INT".to_string(),
+        DataType::Int64 | DataType::UInt32 => "BIGINT".to_string(),
+        DataType::UInt64 if postgres => "NUMERIC(20)".to_string(),
+        DataType::UInt64 => "DECIMAL(20,0)".to_string(),
+        DataType::Float16 | DataType::Float32 if postgres => "REAL".to_string(),
+        DataType::Float16 | DataType::Float32 => "FLOAT".to_string(),
+        DataType::Float64 if postgres => "DOUBLE PRECISION".to_string(),
+        DataType::Float64 => "DOUBLE".to_string(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View if postgres => "TEXT".to_string(),
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => "STRING".to_string(),
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_)
+            if postgres =>
+        {
+            "BYTEA".to_string()
+        }
+        DataType::Binary
+        | DataType::LargeBinary
+        | DataType::BinaryView
+        | DataType::FixedSizeBinary(_) => "BINARY".to_string(),
+        DataType::Date32 | DataType::Date64 => "DATE".to_string(),
+        DataType::Timestamp(_, Some(_)) if postgres => "TIMESTAMPTZ".to_string(),
+        DataType::Timestamp(_, _) => "TIMESTAMP".to_string(),
+        DataType::Time32(_) | DataType::Time64(_) if postgres => "TIME".to_string(),
+        DataType::Duration(_) | DataType::Interval(_) if postgres => "INTERVAL".to_string(),
+        DataType::Decimal128(precision, scale) | DataType::Decimal256(precision, scale) => {
+            let name = if postgres { "NUMERIC" } else { "DECIMAL" };
+            format!("{}({},{})", name, precision, scale)
+        }
+        DataType::List(field) | DataType::LargeList(field) | DataType::FixedSizeList(field, _) => {
+            match dialect {
+                SchemaFormat::Postgres => format!("{}[]", element(field)?),
+                _ => format!("ARRAY<{}>", element(field)?),
+            }
+        }
+        DataType::Struct(_) | DataType::Map(_, _) if postgres => "JSONB".to_string(),
+        DataType::Struct(fields) => {
+            let separator = if dialect == SchemaFormat::Hive {
+                ":"
+            } else {
+                ": "
+            };
+            let fields = fields
+                .iter()
+                .map(|field| {
+                    let name = quote(field.name(), dialect);
+                    Ok(format!("{}{}{}", name, separator, element(field)?))
+                })
+                .collect::<InnoFileResult<Vec<_>>>()?;
+            format!("STRUCT<{}>", fields.join(", "))
+        }
+        DataType::Map(field, _) => {
+            let key_value = children(field.data_type()).unwrap_or_default();
+            let [key, value] = &key_value[..] else {
+                Err(anyhow!("Map entries not a key and value: {}", data_type))?
+            };
+            format!("MAP<{}, {}>", element(key)?, element(value)?)
+        }
+        data_type => Err(anyhow!(
+            "Data type not supported by {:?} DDL: {}",
+            dialect,
+            data_type
+        ))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::datatypes::{Field, TimeUnit};
+
+    use super::*;
+
+    #[test]
+    fn test_format_schema() -> InnoFileResult<()> {
+        let address = Fields::from(vec![Field::new("city", DataType::Utf8, true)]);
+        let schema = Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new(
+                "created_at",
+                DataType::Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+                true,
+            ),
+            Field::new("tags", DataType::new_list(DataType::Utf8, true), true),
+            Field::new("address", DataType::Struct(address), true),
+        ]);
+
+        let tree = format_schema(&schema, SchemaFormat::Tree, "users")?;
+        assert!(tree.starts_with("root\n |-- id: Int64 (nullable = false)"));
+        assert!(tree.ends_with("\n |    |-- city: Utf8 (nullable = true)"));
+
+        let json = format_schema(&schema, SchemaFormat::Json, "users")?;
+        let parsed: Schema = serde_json::from_str(&json).map_err(anyhow::Error::from)?;
+        assert_eq!(parsed, schema);
+
+        assert_eq!(
+            format_schema(&schema, SchemaFormat::Spark, "users")?,
+            "CREATE TABLE `users` (\n  `id` BIGINT NOT NULL,\n  `created_at` TIMESTAMP,\n  \
+             `tags` ARRAY<STRING>,\n  `address` STRUCT<`city`: STRING>\n);"
+        );
+        assert!(format_schema(&schema, SchemaFormat::Hive, "users")?
+            .contains("`id` BIGINT,\n  `created_at` TIMESTAMP"));
+        assert_eq!(
+            format_schema(&schema, SchemaFormat::Postgres, "users")?,
+            "CREATE TABLE \"users\" (\n  \"id\" BIGINT NOT NULL,\n  \"created_at\" TIMESTAMPTZ,\n  \
+             \"tags\" TEXT[],\n  \"address\" JSONB\n);"
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,501 @@
+use std::{
+    hash::{DefaultHasher, Hash as _, Hasher as _},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{Array, ArrayRef, RecordBatch, StringArray},
+    compute::{concat, sort_to_indices, SortOptions},
+    datatypes::{DataType, Field, Schema},
+    row::{RowConverter, SortField},
+    util::display::{ArrayFormatter, FormatOptions},
+};
+use clap::{Args, ValueEnum};
+use innofile::{
+    arrow::ArrowReaderBuilder,
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+    sync::{arrow::ArrowReader, fs::FileRef},
+    utils::path_extension,
+};
+use serde_json::json;
+
+use crate::file_system;
+
+#[derive(Debug, Args)]
+pub struct CountArgs {
+    /// File path to count rows of
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// Count rows by reading the file even if its metadata has the row count
+    #[arg(long)]
+    pub scan: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum StatsFormat {
+    /// Table of columns
+    Text,
+    /// JSON object
+    Json,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// File path to compute column statistics of
+    #[arg(value_name = "PATH")]
+    pub path: String,
+
+    /// Compute statistics by reading the file instead of from Parquet and ORC metadata. Byte
+    /// sizes are compressed sizes from metadata, or in memory Arrow sizes when scanning.
+    #[arg(long)]
+    pub scan: bool,
+
+    /// Output format of the statistics
+    #[arg(long, value_enum, default_value_t = StatsFormat::Text)]
+    pub format: StatsFormat,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnStats {
+    pub name: String,
+    pub data_type: String,
+    pub null_count: Option<u64>,
+    pub min: Option<String>,
+    pub max: Option<String>,
+    pub distinct_count: Option<u64>,
+    pub byte_size: Option<u64>,
+}
+
+impl ColumnStats {
+    fn new(field: &Field) -> Self {
+        Self {
+            name: field.name().clone(),
+            data_type: field.data_type().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+pub fn do_count(args: &CountArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let file_system = file_system(&args.path, profile)?;
+    let file = Arc::from(file_system.open(&args.path)?);
+    let arrow_reader = ArrowReaderBuilder::new().build_sync(file)?;
+
+    let num_rows = match arrow_reader.num_rows() {
+        Some(num_rows) if !args.scan => num_rows,
+        _ => {
+            let read_context = || ErrorContext::new(Operation::Read).with_path(&args.path);
+            let mut num_rows = 0;
+            for batch in arrow_reader {
+                num_rows += batch.with_context(read_context)?.num_rows();
+            }
+            num_rows
+        }
+    };
+    println!("{}", num_rows);
+    Ok(())
+}
+
+pub fn do_stats(args: &StatsArgs, profile: Option<&str>) -> InnoFileResult<()> {
+    let file_system = file_system(&args.path, profile)?;
+    let file: FileRef = Arc::from(file_system.open(&args.path)?);
+
+    let (num_rows, columns) = match path_extension(&args.path)?
+        .map(|extension| extension.to_lowercase())
+        .as_deref()
+    {
+        #[cfg(feature = "parquet")]
+        Some("parquet") if !args.scan => {
+            use innofile::sync::arrow::parquet::ArrowParquetReader;
+            let arrow_reader = ArrowParquetReader::new(file)?;
+            let columns = parquet_stats(&arrow_reader.schema(), arrow_reader.metadata())?;
+            (arrow_reader.num_rows().unwrap_or_default(), columns)
+        }
+        #[cfg(feature = "orc")]
+        Some("orc") if !args.scan => {
+            use innofile::sync::arrow::orc::ArrowOrcReader;
+            let arrow_reader = ArrowOrcReader::new(file)?;
+            let schema = arrow_reader.schema();
+            if arrow_reader.metadata().column_file_statistics().is_empty() {
+                // files written without statistics, such as by orc-rust
+                let read_context = || ErrorContext::new(Operation::Read).with_path(&args.path);
+                let batches = arrow_reader.map(|batch| batch.with_context(read_context));
+                scan_stats(&schema, batches)?
+            } else {
+                let columns = orc_stats(&schema, arrow_reader.metadata())?;
+                (arrow_reader.num_rows().unwrap_or_default(), columns)
+            }
+        }
+        _ => {
+            let arrow_reader = ArrowReaderBuilder::new().build_sync(file)?;
+            let schema = arrow_reader.schema();
+            let read_context = || ErrorContext::new(Operation::Read).with_path(&args.path);
+            let batches = arrow_reader.map(|batch| batch.with_context(read_context));
+            scan_stats(&schema, batches)?
+        }
+    };
+
+    match args.format {
+        StatsFormat::Text => {
+            println!("rows: {}", num_rows);
+            println!("{}", format_stats(&columns)?);
+        }
+        StatsFormat::Json => {
+            let columns = columns
+                .iter()
+                .map(|column| {
+                    json!({
+                        "name": column.name,
+                        "data_type": column.data_type,
+                        "null_count": column.null_count,
+                        "min": column.min,
+                        "max": column.max,
+                        "distinct_count": column.distinct_count,
+                        "byte_size": column.byte_size,
+                    })
+                })
+                .collect::<Vec<_>>();
+            let stats = json!({ "path": args.path, "rows": num_rows, "columns": columns });
+            println!("{:#}", stats);
+        }
+    }
+    Ok(())
+}
+
+/// Formats column statistics as a table, with empty cells for unknown statistics.
+fn format_stats(columns: &[ColumnStats]) -> InnoFileResult<String> {
+    let column = |name: &str, value: fn(&ColumnStats) -> Option<String>| {
+        let values: ArrayRef = Arc::new(columns.iter().map(value).collect::<StringArray>());
+        (name.to_string(), values)
+    };
+    let batch = RecordBatch::try_from_iter([
+        column("column", |c| Some(c.name.clone())),
+        column("type", |c| Some(c.data_type.clone())),
+        column("nulls", |c| c.null_count.map(|n| n.to_string())),
+        column("min", |c| c.min.clone()),
+        column("max", |c| c.max.clone()),
+        column("distinct", |c| c.distinct_count.map(|n| n.to_string())),
+        column("bytes", |c| c.byte_size.map(|n| n.to_string())),
+    ])?;
+    Ok(arrow::util::pretty::pretty_format_batches(&[batch])?.to_string())
+}
+
+/// Computes column statistics by reading all record batches, returning the number of rows too.
+fn scan_stats(
+    schema: &Schema,
+    batches: impl Iterator<Item = InnoFileResult<RecordBatch>>,
+) -> InnoFileResult<(usize, Vec<ColumnStats>)> {
+    let mut num_rows = 0;
+    let mut scanners = schema
+        .fields()
+        .iter()
+        .map(|field| ColumnScanner::new(field))
+        .collect::<Vec<_>>();
+    for batch in batches {
+        let batch = batch?;
+        num_rows += batch.num_rows();
+        for (scanner, array) in scanners.iter_mut().zip(batch.columns()) {
+            scanner.update(array)?;
+        }
+    }
+    let columns = scanners
+        .into_iter()
+        .map(ColumnScanner::finish)
+        .collect::<InnoFileResult<_>>()?;
+    Ok((num_rows, columns))
+}
+
+/// Statistics of a column accumulated over record batches.
+struct ColumnScanner {
+    stats: ColumnStats,
+    min: Option<ArrayRef>,
+    max: Option<ArrayRef>,
+    distinct: Option<(RowConverter, HyperLogLog)>,
+}
+
+impl ColumnScanner {
+    fn new(field: &Field) -> Self {
+        let converter = RowConverter::new(vec![SortField::new(field.data_type().clone())]).ok();
+        Self {
+            stats: ColumnStats {
+                null_count: Some(0),
+                byte_size: Some(0),
+                ..ColumnStats::new(field)
+            },
+            min: None,
+            max: None,
+            distinct: converter.map(|converter| (converter, HyperLogLog::new())),
+        }
+    }
+
+    fn update(&mut self, array: &ArrayRef) -> InnoFileResult<()> {
+        let stats = &mut self.stats;
+        stats.null_count = stats.null_count.map(|n| n + array.null_count() as u64);
+        stats.byte_size = stats
+            .byte_size
+            .map(|n| n + array.get_array_memory_size() as u64);
+        self.min = extreme(&merge(self.min.take(), extreme(array, false))?, false);
+        self.max = extreme(&merge(self.max.take(), extreme(array, true))?, true);
+        if let Some((converter, hyper_log_log)) = &mut self.distinct {
+            let rows = converter.convert_columns(&[Arc::clone(array)])?;
+            for (index, row) in rows.iter().enumerate() {
+                if array.is_valid(index) {
+                    hyper_log_log.insert(row.as_ref());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> InnoFileResult<ColumnStats> {
+        Ok(ColumnStats {
+            min: self.min.as_ref().map(display).transpose()?,
+            max: self.max.as_ref().map(display).transpose()?,
+            distinct_count: self
+                .distinct
+                .map(|(_, hyper_log_log)| hyper_log_log.estimate()),
+            ..self.stats
+        })
+    }
+}
+
+/// Concatenates the single value arrays of the running and the batch extremes.
+fn merge(running: Option<ArrayRef>, batch: Option<ArrayRef>) -> InnoFileResult<ArrayRef> {
+    Ok(match (running, batch) {
+        (Some(running), Some(batch)) => concat(&[&running, &batch])?,
+        (Some(array), None) | (None, Some(array)) => array,
+        (None, None) => arrow::array::new_null_array(&DataType::Null, 0),
+    })
+}
+
+/// Returns the smallest, or the largest if `descending`, non-null value of `array` as a single
+/// value array, or `None` if all values are null or the data type isn't sortable.
+fn extreme(array: &ArrayRef, descending: bool) -> Option<ArrayRef> {
+    let options = SortOptions {
+        descending,
+        nulls_first: false,
+    };
+    let indices = sort_to_indices(array, Some(options), Some(1)).ok()?;
+    let index = *indices.values().first()? as usize;
+    array.is_valid(index).then(|| array.slice(index, 1))
+}
+
+fn display(array: &ArrayRef) -> InnoFileResult<String> {
+    let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+    Ok(formatter.value(0).to_string())
+}
+
+/// Computes column statistics from the row group statistics of Parquet metadata.
+#[cfg(feature = "parquet")]
+fn parquet_stats(
+    schema: &Schema,
+    metadata: &parquet::file::metadata::ParquetMetaData,
+) -> InnoFileResult<Vec<ColumnStats>> {
+    use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
+
+    let row_groups = metadata.row_groups();
+    let parquet_schema = metadata.file_metadata().schema_descr();
+    schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let converter = StatisticsConverter::try_new(field.name(), schema, parquet_schema)?;
+            let null_counts = converter.row_group_null_counts(row_groups)?;
+            let mins = converter.row_group_mins(row_groups)?;
+            let maxes = converter.row_group_maxes(row_groups)?;
+            // distinct counts of row groups can't be added up
+            let distinct_count = match (converter.parquet_column_index(), row_groups) {
+                (Some(index), [row_group]) => row_group
+                    .column(index)
+                    .statistics()
+                    .and_then(|statistics| statistics.distinct_count_opt()),
+                _ => None,
+            };
+            let byte_size = row_groups
+                .iter()
+                .flat_map(|row_group| row_group.columns())
+                .filter(|column| column.column_path().parts().first() == Some(field.name()))
+                .map(|column| column.compressed_size() as u64)
+                .sum();
+            Ok(ColumnStats {
+                null_count: (null_counts.null_count() == 0)
+                    .then(|| null_counts.values().iter().sum()),
+                min: extreme(&mins, false).as_ref().map(display).transpose()?,
+                max: extreme(&maxes, true).as_ref().map(display).transpose()?,
+                distinct_count,
+                byte_size: Some(byte_size),
+                ..ColumnStats::new(field)
+            })
+        })
+        .collect()
+}
+
+/// Computes column statistics from the file statistics of ORC metadata.
+#[cfg(feature = "orc")]
+fn orc_stats(
+    schema: &Schema,
+    metadata: &orc_rust::reader::metadata::FileMetadata,
+) -> InnoFileResult<Vec<ColumnStats>> {
+    let num_rows = metadata.number_of_rows();
+    let columns = metadata.root_data_type().children();
+    schema
+        .fields()
+        .iter()
+        .zip(columns)
+        .map(|(field, column)| {
+            let mut stats = ColumnStats::new(field);
+            let index = column.data_type().column_index();
+            let Some(statistics) = metadata.column_file_statistics().get(index) else {
+                return Ok(stats);
+            };
+            stats.null_count = Some(match statistics.has_null() {
+                true => num_rows.saturating_sub(statistics.number_of_values()),
+                false => 0,
+            });
+            if let Some(type_statistics) = statistics.type_statistics() {
+                (stats.min, stats.max) = orc_min_max(type_statistics)?.unzip();
+            }
+            Ok(stats)
+        })
+        .collect()
+}
+
+/// Formats the minimum and maximum of ORC statistics, if the type of statistics has them.
+#[cfg(feature = "orc")]
+pub fn orc_min_max(
+    statistics: &orc_rust::statistics::TypeStatistics,
+) -> InnoFileResult<Option<(String, String)>> {
+    use arrow::array::{Date32Array, TimestampMillisecondArray};
+    use orc_rust::statistics::TypeStatistics;
+
+    let (min, max): (ArrayRef, ArrayRef) = match statistics {
+        TypeStatistics::Integer { min, max, .. } => {
+            return Ok(Some((min.to_string(), max.to_string())))
+        }
+        TypeStatistics::Double { min, max, .. } => {
+            return Ok(Some((min.to_string(), max.to_string())))
+        }
+        TypeStatistics::String { min, max, .. } | TypeStatistics::Decimal { min, max, .. } => {
+            return Ok(Some((min.clone(), max.clone())))
+        }
+        TypeStatistics::Date { min, max } => (
+            Arc::new(Date32Array::from(vec![*min])),
+            Arc::new(Date32Array::from(vec![*max])),
+        ),
+        TypeStatistics::Timestamp {
+            min_utc, max_utc, ..
+        } => (
+            Arc::new(TimestampMillisecondArray::from(vec![*min_utc])),
+            Arc::new(TimestampMillisecondArray::from(vec![*max_utc])),
+        ),
+        _ => return Ok(None),
+    };
+    Ok(Some((display(&min)?, display(&max)?)))
+}
+
+/// Number of bits of a hash indexing the registers of [`HyperLogLog`].
+const PRECISION: u32 = 12;
+
+/// HyperLogLog estimator of the number of distinct values, with a standard error of about 1.6%.
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; 1 << PRECISION],
+        }
+    }
+
+    fn insert(&mut self, value: &[u8]) {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        let hash = hasher.finish();
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // position of the first set bit in the remaining bits, bounded by a sentinel bit
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum = self
+            .registers
+            .iter()
+            .map(|&register| 2f64.powi(-i32::from(register)))
+            .sum::<f64>();
+        let estimate = alpha * m * m / sum;
+        let zeros = self
+            .registers
+            .iter()
+            .filter(|&&register| register == 0)
+            .count();
+        // linear counting is more accurate for small cardinalities
+        if estimate <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            estimate.round() as u64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int64Array, StringArray};
+
+    use super::*;
+
+    #[test]
+    fn test_scan_stats() -> InnoFileResult<()> {
+        let batch = |ids: Vec<Option<i64>>, names: Vec<Option<&str>>| {
+            RecordBatch::try_from_iter([
+                ("id", Arc::new(Int64Array::from(ids)) as ArrayRef),
+                ("name", Arc::new(StringArray::from(names)) as ArrayRef),
+            ])
+        };
+        let batches = vec![
+            batch(
+                vec![Some(3), None, Some(7)],
+                vec![Some("b"), Some("c"), None],
+            )?,
+            batch(vec![Some(-1), Some(3)], vec![None, Some("a")])?,
+        ];
+
+        let schema = batches[0].schema();
+        let (num_rows, columns) = scan_stats(&schema, batches.into_iter().map(Ok))?;
+        assert_eq!(num_rows, 5);
+        let [id, name] = &columns[..] else {
+            panic!("Expected 2 columns, got {}", columns.len());
+        };
+        assert_eq!(id.data_type, "Int64");
+        assert_eq!(id.null_count, Some(1));
+        assert_eq!(
+            (id.min.as_deref(), id.max.as_deref()),
+            (Some("-1"), Some("7"))
+        );
+        assert_eq!(id.distinct_count, Some(3));
+        assert_eq!(name.null_count, Some(2));
+        assert_eq!(
+            (name.min.as_deref(), name.max.as_deref()),
+            (Some("a"), Some("c"))
+        );
+        assert_eq!(name.distinct_count, Some(3));
+        assert!(format_stats(&columns)?.contains("| id     | Int64 | 1     | -1  | 7   |"));
+
+        let mut hyper_log_log = HyperLogLog::new();
+        for value in 0..100_000u32 {
+            hyper_log_log.insert(&(value % 50_000).to_le_bytes());
+        }
+        let estimate = hyper_log_log.estimate() as f64;
+        assert!(
+            (estimate - 50_000.0).abs() / 50_000.0 < 0.05,
+            "{}",
+            estimate
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,15 @@
+use crate::error::InnoFileResult;
+
+pub mod arrow;
+pub mod fs;
+
+/// Runs a blocking closure on the blocking thread pool of the current tokio runtime.
+async fn spawn_blocking<F, T>(f: F) -> InnoFileResult<T>
+where
+    F: FnOnce() -> InnoFileResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(std::io::Error::from)?
+}
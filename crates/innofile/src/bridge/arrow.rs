@@ -0,0 +1,258 @@
+use std::{
+    future::Future as _,
+    pin::Pin,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{ready, Context, Poll},
+};
+
+use anyhow::anyhow;
+use arrow::{array::RecordBatch, datatypes::SchemaRef};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt as _};
+use tokio::{runtime::Handle, task::JoinHandle};
+
+use crate::{
+    error::{InnoFileError, InnoFileResult},
+    runtime::{block_on, shared_handle},
+    sync::{
+        arrow::{ArrowReader as SyncArrowReader, ArrowWriter as SyncArrowWriter},
+        io::Closeable as SyncCloseable,
+    },
+    tokio::{
+        arrow::{ArrowReader as AsyncArrowReader, ArrowWriter as AsyncArrowWriter},
+        io::Closeable as AsyncCloseable,
+    },
+};
+
+use super::spawn_blocking;
+
+/// Exposes a sync [`SyncArrowReader`] or [`SyncArrowWriter`] to async code by running its
+/// blocking calls on the blocking thread pool of the current tokio runtime.
+pub struct AsyncFromSync<T> {
+    inner: Arc<Mutex<T>>,
+    pending: Option<JoinHandle<Option<InnoFileResult<RecordBatch>>>>,
+    schema: Option<SchemaRef>,
+}
+
+impl<T> AsyncFromSync<T> {
+    /// Wraps a writer, or a reader whose schema is only read by locking it, which blocks while a
+    /// batch is being read. Readers are better wrapped by [`AsyncFromSync::from_reader`].
+    pub fn new(inner: T) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(inner)),
+            pending: None,
+            schema: None,
+        }
+    }
+
+    fn lock(inner: &Mutex<T>) -> InnoFileResult<MutexGuard<'_, T>> {
+        inner
+            .lock()
+            .map_err(|_| InnoFileError::Other(anyhow!("Sync Arrow reader or writer poisoned")))
+    }
+}
+
+impl<R: SyncArrowReader> AsyncFromSync<R> {
+    /// Wraps a reader, keeping its schema so that it's returned without locking the reader.
+    pub fn from_reader(inner: R) -> Self {
+        let schema = inner.schema();
+        Self {
+            schema: Some(schema),
+            ..Self::new(inner)
+        }
+    }
+}
+
+impl<R: SyncArrowReader + Send + 'static> Stream for AsyncFromSync<R> {
+    type Item = InnoFileResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let inner = Arc::clone(&self.inner);
+        let pending = self.pending.get_or_insert_with(|| {
+            tokio::task::spawn_blocking(move || match Self::lock(&inner) {
+                Ok(mut inner) => inner.next(),
+                Err(error) => Some(Err(error)),
+            })
+        });
+        let next = ready!(Pin::new(pending).poll(cx));
+        self.pending = None;
+        Poll::Ready(next.unwrap_or_else(|error| Some(Err(std::io::Error::from(error).into()))))
+    }
+}
+
+#[async_trait]
+impl<R: SyncArrowReader + Send + 'static> AsyncArrowReader for AsyncFromSync<R> {
+    fn schema(&self) -> SchemaRef {
+        if let Some(schema) = &self.schema {
+            return Arc::clone(schema);
+        }
+        // Only contended while a batch is being read on the blocking thread pool.
+        match self.inner.lock() {
+            Ok(inner) => inner.schema(),
+            Err(poisoned) => poisoned.into_inner().schema(),
+        }
+    }
+}
+
+#[async_trait]
+impl<W: SyncArrowWriter + Send + 'static> AsyncCloseable for AsyncFromSync<W> {
+    async fn close(self) -> InnoFileResult<()> {
+        let inner = Arc::into_inner(self.inner)
+            .ok_or_else(|| InnoFileError::Other(anyhow!("Sync Arrow writer still in use")))?;
+        let inner = inner
+            .into_inner()
+            .map_err(|_| InnoFileError::Other(anyhow!("Sync Arrow writer poisoned")))?;
+        spawn_blocking(move || inner.close()).await
+    }
+}
+
+#[async_trait]
+impl<W: SyncArrowWriter + Send + 'static> AsyncArrowWriter for AsyncFromSync<W> {
+    async fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
+        let (inner, batch) = (Arc::clone(&self.inner), batch.clone());
+        spawn_blocking(move || Self::lock(&inner)?.write(&batch)).await
+    }
+}
+
+/// Exposes an async [`AsyncArrowReader`] or [`AsyncArrowWriter`] to sync code by driving its
+/// futures on a tokio runtime [`Handle`].
+pub struct SyncFromAsync<T> {
+    inner: T,
+    handle: Handle,
+}
+
+impl<T> SyncFromAsync<T> {
+    /// Drives `inner` on the runtime shared across the crate.
+    pub fn new(inner: T) -> Self {
+        Self::with_handle(inner, shared_handle())
+    }
+
+    /// Drives `inner` on the runtime behind `handle`, which should be a multi-thread runtime.
+    pub fn with_handle(inner: T, handle: Handle) -> Self {
+        Self { inner, handle }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<R: AsyncArrowReader + Send> Iterator for SyncFromAsync<R> {
+    type Item = InnoFileResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        block_on(&self.handle, self.inner.next()).unwrap_or_else(|error| Some(Err(error)))
+    }
+}
+
+impl<R: AsyncArrowReader + Send> SyncArrowReader for SyncFromAsync<R> {
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+}
+
+impl<W: AsyncArrowWriter + Send> SyncCloseable for SyncFromAsync<W> {
+    fn close(self) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.close())?
+    }
+}
+
+impl<W: AsyncArrowWriter + Send> SyncArrowWriter for SyncFromAsync<W> {
+    fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.write(batch))?
+    }
+}
+
+#[cfg(all(test, feature = "parquet"))]
+mod tests {
+    use arrow::{
+        array::{PrimitiveArray, StringArray},
+        datatypes::{DataType, Field, Int64Type, Schema},
+    };
+    use tempfile::tempdir;
+
+    use crate::{
+        bridge::fs::{AsyncFromSync as AsyncFromSyncFS, SyncFromAsync as SyncFromAsyncFS},
+        sync::{
+            arrow::parquet::{ArrowParquetReader, ArrowParquetWriter},
+            fs::{local::LocalFS as SyncLocalFS, FileSystem as _},
+        },
+        tokio::{
+            arrow::parquet::{
+                ArrowParquetReader as AsyncArrowParquetReader,
+                ArrowParquetWriter as AsyncArrowParquetWriter,
+            },
+            fs::{local::LocalFS as AsyncLocalFS, FileSystem as _},
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_from_sync() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("async_from_sync.parquet");
+        let path = path.to_str().unwrap();
+        let file_system = Arc::new(SyncLocalFS);
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, false),
+            Field::new("name", DataType::Utf8, false),
+        ]));
+        let ids = PrimitiveArray::<Int64Type>::from(vec![1, 2]);
+        let names = StringArray::from(vec!["Alex", "Bob"]);
+        let batch =
+            RecordBatch::try_new(Arc::clone(&schema), vec![Arc::new(ids), Arc::new(names)])?;
+
+        let file = Arc::from(file_system.create_new(path)?);
+        let mut arrow_writer =
+            AsyncFromSync::new(ArrowParquetWriter::new(file, Arc::clone(&schema))?);
+        arrow_writer.write(&batch).await?;
+        arrow_writer.close().await?;
+
+        let file = Arc::from(file_system.open(path)?);
+        let mut arrow_reader = AsyncFromSync::from_reader(ArrowParquetReader::new(file)?);
+        assert_eq!(arrow_reader.schema(), schema);
+        assert_eq!(arrow_reader.next().await.transpose()?, Some(batch));
+        assert!(arrow_reader.next().await.is_none());
+
+        let file_system = AsyncFromSyncFS::new(file_system);
+        file_system.remove_file(path).await?;
+        assert!(!file_system.exists(path).await?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_from_async() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("sync_from_async.parquet");
+        let path = path.to_str().unwrap();
+        let file_system = Arc::new(AsyncLocalFS);
+        let handle = shared_handle();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int64, false)]));
+        let ids = PrimitiveArray::<Int64Type>::from(vec![1, 2, 3]);
+        let batch = RecordBatch::try_new(Arc::clone(&schema), vec![Arc::new(ids)])?;
+
+        let file = Arc::from(block_on(&handle, file_system.create_new(path))??);
+        let arrow_writer = block_on(
+            &handle,
+            AsyncArrowParquetWriter::new(file, Arc::clone(&schema)),
+        )??;
+        let mut arrow_writer = SyncFromAsync::new(arrow_writer);
+        arrow_writer.write(&batch)?;
+        arrow_writer.close()?;
+
+        let file = Arc::from(block_on(&handle, file_system.open(path))??);
+        let arrow_reader = block_on(&handle, AsyncArrowParquetReader::new(file))??;
+        let mut arrow_reader = SyncFromAsync::new(arrow_reader);
+        assert_eq!(arrow_reader.schema(), schema);
+        assert_eq!(arrow_reader.next().transpose()?, Some(batch));
+        assert!(arrow_reader.next().is_none());
+
+        let file_system = SyncFromAsyncFS::new(file_system);
+        file_system.remove_file(path)?;
+        assert!(!file_system.exists(path)?);
+
+        Ok(())
+    }
+}
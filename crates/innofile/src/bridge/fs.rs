@@ -0,0 +1,719 @@
+use std::{
+    future::Future as _,
+    io::{BufRead, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::{
+    io::{
+        AsyncBufRead, AsyncBufReadExt as _, AsyncRead, AsyncReadExt as _, AsyncSeek,
+        AsyncSeekExt as _, AsyncWrite, AsyncWriteExt as _, ReadBuf,
+    },
+    runtime::Handle,
+    task::JoinHandle,
+};
+
+use crate::{
+    error::InnoFileResult,
+    fs::FileStatus,
+    runtime::{block_on, shared_handle},
+    sync::{
+        fs::{
+            File as SyncFile, FileRead as SyncFileRead, FileSystem as SyncFileSystem,
+            FileWrite as SyncFileWrite, Metadata as SyncMetadata,
+        },
+        io::Closeable as SyncCloseable,
+    },
+    tokio::{
+        fs::{
+            File as AsyncFile, FileRead as AsyncFileRead, FileSystem as AsyncFileSystem,
+            FileWrite as AsyncFileWrite, Metadata as AsyncMetadata,
+        },
+        io::Closeable as AsyncCloseable,
+    },
+};
+
+use super::spawn_blocking;
+
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+type PendingRead = JoinHandle<(Box<dyn SyncFileRead>, ReadOp)>;
+type PendingWrite = JoinHandle<(Box<dyn SyncFileWrite>, std::io::Result<()>)>;
+
+/// Exposes a sync [`SyncFileSystem`], [`SyncFile`] or [`SyncMetadata`] to async code by running
+/// its blocking calls on the blocking thread pool of the current tokio runtime.
+#[derive(Debug)]
+pub struct AsyncFromSync<T: ?Sized> {
+    inner: Arc<T>,
+}
+
+impl<T: ?Sized> AsyncFromSync<T> {
+    pub fn new(inner: Arc<T>) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> Arc<T> {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<FS: SyncFileSystem + ?Sized + 'static> AsyncFileSystem for AsyncFromSync<FS> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.exists(&path)).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn AsyncFile>> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        let file = spawn_blocking(move || inner.open(&path)).await?;
+        Ok(Box::new(AsyncFromSync::<dyn SyncFile>::new(file.into())))
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn AsyncFile>> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        let file = spawn_blocking(move || inner.create(&path)).await?;
+        Ok(Box::new(AsyncFromSync::<dyn SyncFile>::new(file.into())))
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn AsyncFile>> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        let file = spawn_blocking(move || inner.create_new(&path)).await?;
+        Ok(Box::new(AsyncFromSync::<dyn SyncFile>::new(file.into())))
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn AsyncFile>> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        let file = spawn_blocking(move || inner.create_atomic(&path)).await?;
+        Ok(Box::new(AsyncFromSync::<dyn SyncFile>::new(file.into())))
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn AsyncFile>> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        let file = spawn_blocking(move || inner.append(&path)).await?;
+        Ok(Box::new(AsyncFromSync::<dyn SyncFile>::new(file.into())))
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.create_dir(&path)).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.create_dir_all(&path)).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.remove_dir(&path)).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.remove_dir_all(&path)).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.remove_file(&path)).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.list(&path)).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let (inner, path) = (Arc::clone(&self.inner), path.to_string());
+        spawn_blocking(move || inner.status(&path)).await
+    }
+}
+
+#[async_trait]
+impl<F: SyncFile + ?Sized + 'static> AsyncFile for AsyncFromSync<F> {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    async fn metadata(&self) -> InnoFileResult<Box<dyn AsyncMetadata>> {
+        let inner = Arc::clone(&self.inner);
+        let metadata = spawn_blocking(move || inner.metadata()).await?;
+        Ok(Box::new(AsyncFromSync::<dyn SyncMetadata>::new(
+            metadata.into(),
+        )))
+    }
+
+    async fn reader(&self) -> InnoFileResult<Box<dyn AsyncFileRead>> {
+        let inner = Arc::clone(&self.inner);
+        let reader = spawn_blocking(move || inner.reader()).await?;
+        Ok(Box::new(AsyncReadFromSync::new(reader)))
+    }
+
+    async fn writer(&self) -> InnoFileResult<Box<dyn AsyncFileWrite>> {
+        let inner = Arc::clone(&self.inner);
+        let writer = spawn_blocking(move || inner.writer()).await?;
+        Ok(Box::new(AsyncWriteFromSync::new(writer)))
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        let inner = Arc::clone(&self.inner);
+        spawn_blocking(move || inner.read_range(range)).await
+    }
+
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let (inner, ranges) = (Arc::clone(&self.inner), ranges.to_vec());
+        spawn_blocking(move || inner.read_ranges(&ranges)).await
+    }
+}
+
+impl<M: SyncMetadata + ?Sized> AsyncMetadata for AsyncFromSync<M> {
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.inner.last_modified()
+    }
+
+    fn version(&self) -> Option<String> {
+        self.inner.version()
+    }
+}
+
+/// Exposes an async [`AsyncFileSystem`], [`AsyncFile`] or [`AsyncMetadata`] to sync code by
+/// driving its futures on a tokio runtime [`Handle`].
+#[derive(Debug)]
+pub struct SyncFromAsync<T: ?Sized> {
+    inner: Arc<T>,
+    handle: Handle,
+}
+
+impl<T: ?Sized> SyncFromAsync<T> {
+    /// Drives `inner` on the runtime shared across the crate.
+    pub fn new(inner: Arc<T>) -> Self {
+        Self::with_handle(inner, shared_handle())
+    }
+
+    /// Drives `inner` on the runtime behind `handle`, which should be a multi-thread runtime.
+    pub fn with_handle(inner: Arc<T>, handle: Handle) -> Self {
+        Self { inner, handle }
+    }
+
+    pub fn into_inner(self) -> Arc<T> {
+        self.inner
+    }
+}
+
+impl<FS: AsyncFileSystem + ?Sized + 'static> SyncFileSystem for SyncFromAsync<FS> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        block_on(&self.handle, self.inner.exists(path))?
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn SyncFile>> {
+        let file = block_on(&self.handle, self.inner.open(path))??;
+        Ok(Box::new(SyncFromAsync::<dyn AsyncFile>::with_handle(
+            file.into(),
+            self.handle.clone(),
+        )))
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn SyncFile>> {
+        let file = block_on(&self.handle, self.inner.create(path))??;
+        Ok(Box::new(SyncFromAsync::<dyn AsyncFile>::with_handle(
+            file.into(),
+            self.handle.clone(),
+        )))
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn SyncFile>> {
+        let file = block_on(&self.handle, self.inner.create_new(path))??;
+        Ok(Box::new(SyncFromAsync::<dyn AsyncFile>::with_handle(
+            file.into(),
+            self.handle.clone(),
+        )))
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn SyncFile>> {
+        let file = block_on(&self.handle, self.inner.create_atomic(path))??;
+        Ok(Box::new(SyncFromAsync::<dyn AsyncFile>::with_handle(
+            file.into(),
+            self.handle.clone(),
+        )))
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn SyncFile>> {
+        let file = block_on(&self.handle, self.inner.append(path))??;
+        Ok(Box::new(SyncFromAsync::<dyn AsyncFile>::with_handle(
+            file.into(),
+            self.handle.clone(),
+        )))
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.create_dir(path))?
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.create_dir_all(path))?
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.remove_dir(path))?
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.remove_dir_all(path))?
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        block_on(&self.handle, self.inner.remove_file(path))?
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        block_on(&self.handle, self.inner.list(path))?
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        block_on(&self.handle, self.inner.status(path))?
+    }
+}
+
+impl<F: AsyncFile + ?Sized + 'static> SyncFile for SyncFromAsync<F> {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn SyncMetadata>> {
+        let metadata = block_on(&self.handle, self.inner.metadata())??;
+        Ok(Box::new(SyncFromAsync::<dyn AsyncMetadata>::with_handle(
+            metadata.into(),
+            self.handle.clone(),
+        )))
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn SyncFileRead>> {
+        let reader = block_on(&self.handle, self.inner.reader())??;
+        Ok(Box::new(SyncReadFromAsync::new(
+            reader,
+            self.handle.clone(),
+        )))
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn SyncFileWrite>> {
+        let writer = block_on(&self.handle, self.inner.writer())??;
+        Ok(Box::new(SyncWriteFromAsync::new(
+            writer,
+            self.handle.clone(),
+        )))
+    }
+}
+
+impl<M: AsyncMetadata + ?Sized> SyncMetadata for SyncFromAsync<M> {
+    fn len(&self) -> u64 {
+        self.inner.len()
+    }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.inner.last_modified()
+    }
+
+    fn version(&self) -> Option<String> {
+        self.inner.version()
+    }
+}
+
+#[derive(Debug)]
+enum ReadOp {
+    Read(std::io::Result<Vec<u8>>),
+    Seek(std::io::Result<u64>),
+}
+
+/// Async reader over a sync [`SyncFileRead`], reading and seeking on the blocking thread pool.
+#[derive(Debug)]
+pub struct AsyncReadFromSync {
+    inner: Option<Box<dyn SyncFileRead>>,
+    buf: Vec<u8>,
+    pos: usize,
+    /// Position of `inner` after the last completed read or seek.
+    position: u64,
+    pending: Option<PendingRead>,
+}
+
+impl AsyncReadFromSync {
+    pub fn new(inner: Box<dyn SyncFileRead>) -> Self {
+        Self {
+            inner: Some(inner),
+            buf: Vec::new(),
+            pos: 0,
+            position: 0,
+            pending: None,
+        }
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<Option<ReadOp>>> {
+        let Some(pending) = &mut self.pending else {
+            return Poll::Ready(Ok(None));
+        };
+        let (inner, op) = ready!(Pin::new(pending).poll(cx))?;
+        self.pending = None;
+        self.inner = Some(inner);
+        Poll::Ready(Ok(Some(op)))
+    }
+
+    fn spawn(
+        &mut self,
+        f: impl FnOnce(&mut Box<dyn SyncFileRead>) -> ReadOp + Send + 'static,
+    ) -> std::io::Result<()> {
+        let mut inner = self.inner.take().ok_or_else(operation_in_progress)?;
+        self.pending = Some(tokio::task::spawn_blocking(move || {
+            let op = f(&mut inner);
+            (inner, op)
+        }));
+        Ok(())
+    }
+}
+
+impl AsyncRead for AsyncReadFromSync {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let available = ready!(self.as_mut().poll_fill_buf(cx))?;
+        let len = available.len().min(buf.remaining());
+        buf.put_slice(&available[..len]);
+        self.consume(len);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncBufRead for AsyncReadFromSync {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        loop {
+            match ready!(this.poll_pending(cx))? {
+                Some(ReadOp::Read(result)) => {
+                    this.buf = result?;
+                    this.pos = 0;
+                    this.position += this.buf.len() as u64;
+                    return Poll::Ready(Ok(&this.buf));
+                }
+                Some(ReadOp::Seek(_)) => Err(operation_in_progress())?,
+                None if this.pos < this.buf.len() => return Poll::Ready(Ok(&this.buf[this.pos..])),
+                None => this.spawn(|inner| {
+                    let mut buf = vec![0; READ_BUFFER_SIZE];
+                    ReadOp::Read(inner.read(&mut buf).map(|len| {
+                        buf.truncate(len);
+                        buf
+                    }))
+                })?,
+            }
+        }
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+    }
+}
+
+impl AsyncSeek for AsyncReadFromSync {
+    fn start_seek(mut self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        // Bytes already buffered are ahead of the logical position of this reader.
+        let unread = (self.buf.len() - self.pos) as i64;
+        let position = match position {
+            SeekFrom::Current(offset) => SeekFrom::Current(offset - unread),
+            position => position,
+        };
+        self.buf.clear();
+        self.pos = 0;
+        self.spawn(move |inner| ReadOp::Seek(inner.seek(position)))
+    }
+
+    fn poll_complete(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        match ready!(self.poll_pending(cx))? {
+            Some(ReadOp::Seek(result)) => {
+                self.position = result?;
+                Poll::Ready(Ok(self.position))
+            }
+            Some(ReadOp::Read(_)) => Err(operation_in_progress())?,
+            None => Poll::Ready(Ok(self.position - (self.buf.len() - self.pos) as u64)),
+        }
+    }
+}
+
+/// Async writer over a sync [`SyncFileWrite`], writing and flushing on the blocking thread pool.
+#[derive(Debug)]
+pub struct AsyncWriteFromSync {
+    inner: Option<Box<dyn SyncFileWrite>>,
+    pending: Option<PendingWrite>,
+    flushing: bool,
+}
+
+impl AsyncWriteFromSync {
+    pub fn new(inner: Box<dyn SyncFileWrite>) -> Self {
+        Self {
+            inner: Some(inner),
+            pending: None,
+            flushing: false,
+        }
+    }
+
+    fn poll_pending(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let Some(pending) = &mut self.pending else {
+            return Poll::Ready(Ok(()));
+        };
+        let (inner, result) = ready!(Pin::new(pending).poll(cx))?;
+        self.pending = None;
+        self.inner = Some(inner);
+        Poll::Ready(result)
+    }
+
+    fn spawn(
+        &mut self,
+        f: impl FnOnce(&mut Box<dyn SyncFileWrite>) -> std::io::Result<()> + Send + 'static,
+    ) -> std::io::Result<()> {
+        let mut inner = self.inner.take().ok_or_else(operation_in_progress)?;
+        self.pending = Some(tokio::task::spawn_blocking(move || {
+            let result = f(&mut inner);
+            (inner, result)
+        }));
+        Ok(())
+    }
+}
+
+impl AsyncWrite for AsyncWriteFromSync {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        let this = self.get_mut();
+        ready!(this.poll_pending(cx))?;
+        let buf_owned = buf.to_vec();
+        this.spawn(move |inner| inner.write_all(&buf_owned))?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            let result = ready!(this.poll_pending(cx));
+            if this.flushing {
+                this.flushing = false;
+                return Poll::Ready(result);
+            }
+            result?;
+            this.spawn(|inner| inner.flush())?;
+            this.flushing = true;
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.poll_flush(cx)
+    }
+}
+
+#[async_trait]
+impl AsyncCloseable for AsyncWriteFromSync {
+    async fn close(mut self) -> InnoFileResult<()> {
+        std::future::poll_fn(|cx| self.poll_pending(cx)).await?;
+        let inner = self.inner.take().ok_or_else(operation_in_progress)?;
+        spawn_blocking(move || inner.close_boxed()).await
+    }
+
+    async fn abort(mut self) -> InnoFileResult<()> {
+        std::future::poll_fn(|cx| self.poll_pending(cx)).await?;
+        let inner = self.inner.take().ok_or_else(operation_in_progress)?;
+        spawn_blocking(move || inner.abort_boxed()).await
+    }
+}
+
+/// Sync reader over an async [`AsyncFileRead`], driven on a tokio runtime [`Handle`].
+#[derive(Debug)]
+pub struct SyncReadFromAsync {
+    inner: Box<dyn AsyncFileRead>,
+    handle: Handle,
+}
+
+impl SyncReadFromAsync {
+    pub fn new(inner: Box<dyn AsyncFileRead>, handle: Handle) -> Self {
+        Self { inner, handle }
+    }
+}
+
+impl Read for SyncReadFromAsync {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        block_on(&self.handle, self.inner.read(buf))?
+    }
+}
+
+impl BufRead for SyncReadFromAsync {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        block_on(&self.handle, self.inner.fill_buf())?
+    }
+
+    fn consume(&mut self, amt: usize) {
+        Pin::new(&mut self.inner).consume(amt)
+    }
+}
+
+impl Seek for SyncReadFromAsync {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        block_on(&self.handle, self.inner.seek(pos))?
+    }
+}
+
+/// Sync writer over an async [`AsyncFileWrite`], driven on a tokio runtime [`Handle`].
+#[derive(Debug)]
+pub struct SyncWriteFromAsync {
+    inner: Option<Box<dyn AsyncFileWrite>>,
+    handle: Handle,
+}
+
+impl SyncWriteFromAsync {
+    pub fn new(inner: Box<dyn AsyncFileWrite>, handle: Handle) -> Self {
+        Self {
+            inner: Some(inner),
+            handle,
+        }
+    }
+
+    fn inner(&mut self) -> std::io::Result<&mut Box<dyn AsyncFileWrite>> {
+        self.inner
+            .as_mut()
+            .ok_or_else(|| std::io::Error::other("Writer already closed"))
+    }
+}
+
+impl Drop for SyncWriteFromAsync {
+    fn drop(&mut self) {
+        // Writers dropped without closing are shut down on a best effort basis, as there is no
+        // error path left in `Drop`.
+        if let Some(inner) = &mut self.inner {
+            _ = block_on(&self.handle, inner.shutdown());
+        }
+    }
+}
+
+impl SyncCloseable for SyncWriteFromAsync {
+    fn close(mut self) -> InnoFileResult<()> {
+        match self.inner.take() {
+            Some(inner) => block_on(&self.handle, inner.close_boxed())?,
+            None => Ok(()),
+        }
+    }
+
+    fn abort(mut self) -> InnoFileResult<()> {
+        match self.inner.take() {
+            Some(inner) => block_on(&self.handle, inner.abort_boxed())?,
+            None => Ok(()),
+        }
+    }
+}
+
+impl Write for SyncWriteFromAsync {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let handle = self.handle.clone();
+        block_on(&handle, self.inner()?.write(buf))?
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        let handle = self.handle.clone();
+        block_on(&handle, self.inner()?.flush())?
+    }
+}
+
+fn operation_in_progress() -> std::io::Error {
+    std::io::Error::other("Another operation is still in progress")
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use crate::{
+        sync::fs::local::LocalFS as SyncLocalFS, tokio::fs::local::LocalFS as AsyncLocalFS,
+    };
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_async_from_sync() -> InnoFileResult<()> {
+        let content = "Hello, AsyncFromSync!\n";
+
+        let path = tempdir()?.path().join("bridge").join("async_from_sync.txt");
+        let path = path.to_str().unwrap();
+        let file_system = AsyncFromSync::new(Arc::new(SyncLocalFS));
+        assert!(!file_system.exists(path).await?);
+
+        let output_file = file_system.create_new(path).await?;
+        let mut writer = output_file.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.shutdown().await?;
+
+        let input_file = file_system.open(path).await?;
+        assert_eq!(input_file.metadata().await?.len(), content.len() as u64);
+
+        let mut reader = input_file.reader().await?;
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, content);
+        assert_eq!(reader.stream_position().await?, content.len() as u64);
+
+        reader.seek(SeekFrom::Start(7)).await?;
+        buf.clear();
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, &content[7..]);
+
+        file_system.remove_file(path).await?;
+        assert!(!file_system.exists(path).await?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sync_from_async() -> InnoFileResult<()> {
+        let content = "Hello, SyncFromAsync!\n";
+
+        let path = tempdir()?.path().join("bridge").join("sync_from_async.txt");
+        let path = path.to_str().unwrap();
+        let file_system = SyncFromAsync::new(Arc::new(AsyncLocalFS));
+        assert!(!file_system.exists(path)?);
+
+        let output_file = file_system.create_new(path)?;
+        output_file.writer()?.write_all(content.as_bytes())?;
+
+        let input_file = file_system.open(path)?;
+        assert_eq!(input_file.metadata()?.len(), content.len() as u64);
+
+        let mut reader = input_file.reader()?;
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+
+        reader.seek(SeekFrom::Start(7))?;
+        buf.clear();
+        reader.read_to_string(&mut buf)?;
+        assert_eq!(buf, &content[7..]);
+
+        file_system.remove_file(path)?;
+        assert!(!file_system.exists(path)?);
+
+        Ok(())
+    }
+}
@@ -1,12 +1,34 @@
+use std::{
+    error::Error as StdError,
+    fmt::{Display, Formatter},
+    io::ErrorKind,
+};
+
 use thiserror::Error as ThisError;
 
 pub type InnoFileResult<T, E = InnoFileError> = Result<T, E>;
 
 #[derive(Debug, ThisError)]
 pub enum InnoFileError {
+    #[error("Appending not supported for file format or scheme: {0}")]
+    AppendNotSupported(String),
+
     #[error("ArrowError: {0}")]
     Arrow(#[from] arrow::error::ArrowError),
 
+    #[error("Cannot block on the current-thread runtime running the caller")]
+    BlockingInRuntime,
+
+    #[error("Failed to {context}: {source}")]
+    Context {
+        context: ErrorContext,
+        source: Box<InnoFileError>,
+    },
+
+    #[cfg(feature = "config")]
+    #[error("ConfigError: {0}")]
+    Config(#[from] toml::de::Error),
+
     #[error("File format not found")]
     FileFormatNotFound,
 
@@ -19,10 +41,22 @@ pub enum InnoFileError {
     #[error("IoError: {0}")]
     Io(#[from] std::io::Error),
 
+    #[error("No file system mounted at path: {0}")]
+    MountNotFound(String),
+
     #[cfg(feature = "object_store")]
     #[error("ObjectStoreError: {0}")]
     ObjectStore(#[from] object_store::Error),
 
+    #[error("Operation {operation} not supported by file system: {scheme}")]
+    OperationNotSupported {
+        operation: Operation,
+        scheme: String,
+    },
+
+    #[error("Option {option} not supported for file format: {file_format}")]
+    OptionNotSupported { option: String, file_format: String },
+
     #[cfg(feature = "orc")]
     #[error("OrcError: {0}")]
     Orc(#[from] orc_rust::error::OrcError),
@@ -37,6 +71,15 @@ pub enum InnoFileError {
     #[error("ParseIntError: {0}")]
     ParseInt(#[from] core::num::ParseIntError),
 
+    #[error("Path outside root {root}: {path}")]
+    PathOutsideRoot { path: String, root: String },
+
+    #[error("Profile not found: {0}")]
+    ProfileNotFound(String),
+
+    #[error("File system is read-only")]
+    ReadOnlyFileSystem,
+
     #[error("Scheme not supported: {0}")]
     SchemeNotSupported(String),
 
@@ -50,6 +93,190 @@ pub enum InnoFileError {
     UriResolve(#[from] fluent_uri::error::ResolveError),
 }
 
+impl InnoFileError {
+    /// Attaches `context` to this error.
+    pub fn with_context(self, context: ErrorContext) -> Self {
+        Self::Context {
+            context,
+            source: Box::new(self),
+        }
+    }
+
+    /// Returns the context of the outermost [`InnoFileError::Context`] wrapper, if any.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            Self::Context { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns the underlying error without any [`InnoFileError::Context`] wrapper.
+    pub fn root(&self) -> &Self {
+        match self {
+            Self::Context { source, .. } => source.root(),
+            error => error,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        match self.root() {
+            #[cfg(feature = "object_store")]
+            Self::ObjectStore(object_store::Error::NotFound { .. }) => true,
+            error => error.io_error_kind() == Some(ErrorKind::NotFound),
+        }
+    }
+
+    pub fn is_already_exists(&self) -> bool {
+        match self.root() {
+            #[cfg(feature = "object_store")]
+            Self::ObjectStore(object_store::Error::AlreadyExists { .. }) => true,
+            error => error.io_error_kind() == Some(ErrorKind::AlreadyExists),
+        }
+    }
+
+    pub fn is_permission_denied(&self) -> bool {
+        match self.root() {
+            #[cfg(feature = "object_store")]
+            Self::ObjectStore(
+                object_store::Error::PermissionDenied { .. }
+                | object_store::Error::Unauthenticated { .. },
+            ) => true,
+            Self::PathOutsideRoot { .. } | Self::ReadOnlyFileSystem => true,
+            error => error.io_error_kind() == Some(ErrorKind::PermissionDenied),
+        }
+    }
+
+    /// Whether the failed operation may succeed when retried, such as after a connection reset
+    /// or a timeout.
+    pub fn is_retryable(&self) -> bool {
+        // remote stores already retry transient failures of requests themselves, and report
+        // the permanent ones, such as denied or invalid requests, as generic errors
+        matches!(
+            self.root().io_error_kind(),
+            Some(
+                ErrorKind::ConnectionAborted
+                    | ErrorKind::ConnectionRefused
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::BrokenPipe
+                    | ErrorKind::Interrupted
+                    | ErrorKind::NotConnected
+                    | ErrorKind::TimedOut
+                    | ErrorKind::WouldBlock
+            )
+        )
+    }
+
+    /// Finds the kind of the first [`std::io::Error`] in the chain of sources of this error.
+    pub fn io_error_kind(&self) -> Option<ErrorKind> {
+        let mut error: Option<&(dyn StdError + 'static)> = Some(self);
+        while let Some(current) = error {
+            if let Some(io_error) = current.downcast_ref::<std::io::Error>() {
+                // errors converted from `InnoFileError` into `std::io::Error` keep it as source
+                return match io_error.get_ref().and_then(|e| e.downcast_ref::<Self>()) {
+                    Some(inner) => inner.root().io_error_kind(),
+                    None => Some(io_error.kind()),
+                };
+            }
+            error = current.source();
+        }
+        None
+    }
+}
+
+/// The file operation that failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Open,
+    Create,
+    Append,
+    Read,
+    Write,
+    Close,
+    Remove,
+    Stat,
+    List,
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Open => "open",
+            Self::Create => "create",
+            Self::Append => "append to",
+            Self::Read => "read",
+            Self::Write => "write",
+            Self::Close => "close",
+            Self::Remove => "remove",
+            Self::Stat => "stat",
+            Self::List => "list",
+        })
+    }
+}
+
+/// Where an [`InnoFileError`] happened: the operation, and the path, scheme and format of the
+/// file it was applied to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub operation: Operation,
+    pub path: Option<String>,
+    pub scheme: Option<String>,
+    pub format: Option<String>,
+}
+
+impl ErrorContext {
+    pub fn new(operation: Operation) -> Self {
+        Self {
+            operation,
+            path: None,
+            scheme: None,
+            format: None,
+        }
+    }
+
+    pub fn with_path(mut self, path: impl ToString) -> Self {
+        self.path = Some(path.to_string());
+        self
+    }
+
+    pub fn with_scheme(mut self, scheme: impl ToString) -> Self {
+        self.scheme = Some(scheme.to_string());
+        self
+    }
+
+    pub fn with_format(mut self, format: impl ToString) -> Self {
+        self.format = Some(format.to_string());
+        self
+    }
+}
+
+impl Display for ErrorContext {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.operation)?;
+        if let Some(path) = &self.path {
+            write!(f, " {}", path)?;
+        }
+        let details = [("scheme", &self.scheme), ("format", &self.format)]
+            .into_iter()
+            .filter_map(|(name, value)| value.as_ref().map(|value| format!("{name}: {value}")))
+            .collect::<Vec<_>>();
+        if !details.is_empty() {
+            write!(f, " ({})", details.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Extension of results to attach an [`ErrorContext`] to their errors.
+pub trait ResultExt<T> {
+    fn with_context(self, context: impl FnOnce() -> ErrorContext) -> InnoFileResult<T>;
+}
+
+impl<T, E: Into<InnoFileError>> ResultExt<T> for Result<T, E> {
+    fn with_context(self, context: impl FnOnce() -> ErrorContext) -> InnoFileResult<T> {
+        self.map_err(|error| error.into().with_context(context()))
+    }
+}
+
 #[cfg(feature = "object_store")]
 impl From<object_store::path::Error> for InnoFileError {
     fn from(value: object_store::path::Error) -> Self {
@@ -68,7 +295,56 @@ impl From<InnoFileError> for std::io::Error {
     fn from(value: InnoFileError) -> Self {
         match value {
             InnoFileError::Io(error) => error,
-            error => std::io::Error::other(error),
+            error => std::io::Error::new(error.io_error_kind().unwrap_or(ErrorKind::Other), error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_context() {
+        let error = InnoFileError::from(std::io::Error::from(ErrorKind::NotFound)).with_context(
+            ErrorContext::new(Operation::Open)
+                .with_path("s3://bucket/file.parquet")
+                .with_scheme("s3")
+                .with_format("parquet"),
+        );
+        assert_eq!(
+            error.to_string(),
+            "Failed to open s3://bucket/file.parquet (scheme: s3, format: parquet): IoError: entity not found"
+        );
+        assert_eq!(error.context().unwrap().operation, Operation::Open);
+        assert!(matches!(error.root(), InnoFileError::Io(_)));
+    }
+
+    #[test]
+    fn test_error_classification() {
+        let error = |kind| {
+            InnoFileError::from(std::io::Error::from(kind))
+                .with_context(ErrorContext::new(Operation::Read))
+        };
+        assert!(error(ErrorKind::NotFound).is_not_found());
+        assert!(error(ErrorKind::AlreadyExists).is_already_exists());
+        assert!(error(ErrorKind::PermissionDenied).is_permission_denied());
+        assert!(error(ErrorKind::ConnectionReset).is_retryable());
+        assert!(!error(ErrorKind::NotFound).is_retryable());
+
+        let io_error = std::io::Error::from(error(ErrorKind::TimedOut));
+        assert_eq!(io_error.kind(), ErrorKind::TimedOut);
+        assert!(InnoFileError::Io(io_error).is_retryable());
+
+        // generic store errors, such as invalid credentials, are permanent
+        #[cfg(feature = "object_store")]
+        {
+            let source = std::io::Error::other("InvalidAccessKeyId").into();
+            let error = object_store::Error::Generic {
+                store: "S3",
+                source,
+            };
+            assert!(!InnoFileError::from(error).is_retryable());
         }
     }
 }
@@ -1,19 +1,50 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, time::SystemTime};
 
 use fluent_uri::UriRef;
+#[cfg(feature = "object_store")]
+use tokio::runtime::Handle;
 
 #[cfg(feature = "sync")]
-use crate::sync::fs::{local::LocalFS as SyncLocalFS, FileSystem as SyncFileSystem};
+use crate::sync::fs::{
+    cache::CachingFS as SyncCachingFS, local::LocalFS as SyncLocalFS,
+    parent_check::ParentCheckFS as SyncParentCheckFS, retry::RetryFS as SyncRetryFS,
+    FileSystem as SyncFileSystem,
+};
 #[cfg(feature = "tokio")]
-use crate::tokio::fs::{local::LocalFS as AsyncLocalFS, FileSystem as AsyncFileSystem};
+use crate::tokio::fs::{
+    cache::CachingFS as AsyncCachingFS, local::LocalFS as AsyncLocalFS,
+    parent_check::ParentCheckFS as AsyncParentCheckFS, retry::RetryFS as AsyncRetryFS,
+    FileSystem as AsyncFileSystem,
+};
 use crate::{
     error::{InnoFileError, InnoFileResult},
     with_field,
 };
 
+use self::{cache::CacheOptions, retry::RetryPolicy};
+
 #[cfg(feature = "object_store")]
 use self::object_store::*;
 
+pub mod cache;
+#[cfg(feature = "config")]
+pub mod config;
+#[cfg(feature = "s3")]
+pub mod credential;
+pub mod faulty;
+pub(crate) mod mount;
+pub(crate) mod prefix;
+pub mod retry;
+#[cfg(feature = "object_store")]
+pub mod upload;
+
+#[cfg(feature = "config")]
+use self::config::Profile;
+#[cfg(feature = "s3")]
+use self::credential::CredentialProvider;
+#[cfg(feature = "object_store")]
+use self::upload::UploadOptions;
+
 #[cfg(feature = "object_store")]
 mod object_store {
     #[cfg(feature = "sync")]
@@ -22,12 +53,55 @@ mod object_store {
     pub use crate::tokio::fs::object_store::ObjectFS as AsyncObjectFS;
 }
 
+/// Entry of a directory listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileStatus {
+    pub path: String,
+    pub len: u64,
+    pub is_dir: bool,
+    pub last_modified: Option<SystemTime>,
+}
+
+impl FileStatus {
+    pub fn dir(path: impl ToString) -> Self {
+        Self {
+            path: path.to_string(),
+            len: 0,
+            is_dir: true,
+            last_modified: None,
+        }
+    }
+}
+
+#[cfg(feature = "object_store")]
+impl From<::object_store::ObjectMeta> for FileStatus {
+    fn from(value: ::object_store::ObjectMeta) -> Self {
+        Self {
+            path: value.location.to_string(),
+            len: value.size as _,
+            is_dir: false,
+            last_modified: Some(value.last_modified.into()),
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct FileSystemBuilder {
     scheme: Option<String>,
     host: Option<String>,
     port: Option<u16>,
     properties: HashMap<String, String>,
+    #[cfg(feature = "object_store")]
+    runtime_handle: Option<Handle>,
+    #[cfg(feature = "object_store")]
+    upload_options: Option<UploadOptions>,
+    #[cfg(feature = "s3")]
+    credential_provider: Option<CredentialProvider>,
+    create_parents: Option<bool>,
+    retry_policy: Option<RetryPolicy>,
+    cache_options: Option<CacheOptions>,
+    #[cfg(feature = "config")]
+    profile: Option<Profile>,
 }
 
 impl FileSystemBuilder {
@@ -35,8 +109,18 @@ impl FileSystemBuilder {
         Default::default()
     }
 
+    /// Creates a builder of the file system of `path`, applying the profile of the config file
+    /// matching it, if any, to the settings not set explicitly.
     pub fn from_path(path: impl AsRef<str>) -> InnoFileResult<Self> {
-        let uri = UriRef::parse(path.as_ref())?;
+        let builder = Self::parse_path(path.as_ref())?;
+        #[cfg(feature = "config")]
+        let builder = builder.with_config_file()?;
+        Ok(builder)
+    }
+
+    /// Creates a builder of the file system of `path`, without any config file.
+    fn parse_path(path: &str) -> InnoFileResult<Self> {
+        let uri = UriRef::parse(path)?;
 
         let scheme = uri.scheme();
 
@@ -58,6 +142,30 @@ impl FileSystemBuilder {
 
     with_field!(with_port, port, u16);
 
+    #[cfg(feature = "object_store")]
+    with_field!(with_runtime_handle, runtime_handle, Handle);
+
+    #[cfg(feature = "object_store")]
+    with_field!(with_upload_options, upload_options, UploadOptions);
+
+    #[cfg(feature = "s3")]
+    with_field!(
+        with_credential_provider,
+        credential_provider,
+        CredentialProvider
+    );
+
+    with_field!(
+        /// Whether creating a local file creates its missing parent directories, true by default.
+        with_create_parents,
+        create_parents,
+        bool
+    );
+
+    with_field!(with_retry_policy, retry_policy, RetryPolicy);
+
+    with_field!(with_cache_options, cache_options, CacheOptions);
+
     pub fn with_property(mut self, key: impl ToString, value: impl ToString) -> Self {
         self.properties.insert(key.to_string(), value.to_string());
         self
@@ -75,37 +183,91 @@ impl FileSystemBuilder {
         self
     }
 
+    /// Applies the profile found by [`Self::from_path`] to the settings not set explicitly.
+    #[cfg(feature = "config")]
+    fn resolve_profile(&mut self) {
+        if let Some(profile) = self.profile.take() {
+            self.merge_profile(&profile, false);
+        }
+    }
+
+    #[cfg(not(feature = "config"))]
+    fn resolve_profile(&mut self) {}
+
     #[cfg(feature = "tokio")]
-    pub async fn build_async(self) -> InnoFileResult<Box<dyn AsyncFileSystem>> {
-        Ok(match self.scheme {
-            None => Box::new(AsyncLocalFS),
+    pub async fn build_async(mut self) -> InnoFileResult<Box<dyn AsyncFileSystem>> {
+        self.resolve_profile();
+        let local_fs: Box<dyn AsyncFileSystem> = match self.create_parents {
+            Some(false) => Box::new(AsyncParentCheckFS::new(AsyncLocalFS)),
+            _ => Box::new(AsyncLocalFS),
+        };
+        let file_system: Box<dyn AsyncFileSystem> = match self.scheme {
+            None => local_fs,
 
             Some(scheme) => match scheme.to_lowercase().as_str() {
-                "file" => Box::new(AsyncLocalFS),
+                "file" => local_fs,
 
                 #[cfg(feature = "s3")]
                 "s3" | "s3a" => {
-                    Box::new(AsyncObjectFS::new_s3(scheme, self.host, self.properties)?)
+                    let mut file_system =
+                        AsyncObjectFS::new_s3(scheme, self.host, self.properties)?;
+                    if let Some(provider) = self.credential_provider {
+                        file_system = file_system.with_credential_provider(provider)?;
+                    }
+                    Box::new(
+                        file_system.with_upload_options(self.upload_options.unwrap_or_default()),
+                    )
                 }
 
                 _ => Err(InnoFileError::SchemeNotSupported(scheme))?,
             },
+        };
+        let file_system: Box<dyn AsyncFileSystem> = match self.retry_policy {
+            Some(policy) => Box::new(AsyncRetryFS::new(file_system, policy)),
+            None => file_system,
+        };
+        Ok(match self.cache_options {
+            Some(options) => Box::new(AsyncCachingFS::new(file_system, options)?),
+            None => file_system,
         })
     }
 
     #[cfg(feature = "sync")]
-    pub fn build_sync(self) -> InnoFileResult<Box<dyn SyncFileSystem>> {
-        Ok(match self.scheme {
-            None => Box::new(SyncLocalFS),
+    pub fn build_sync(mut self) -> InnoFileResult<Box<dyn SyncFileSystem>> {
+        self.resolve_profile();
+        let local_fs: Box<dyn SyncFileSystem> = match self.create_parents {
+            Some(false) => Box::new(SyncParentCheckFS::new(SyncLocalFS)),
+            _ => Box::new(SyncLocalFS),
+        };
+        let file_system: Box<dyn SyncFileSystem> = match self.scheme {
+            None => local_fs,
 
             Some(scheme) => match scheme.to_lowercase().as_str() {
-                "file" => Box::new(SyncLocalFS),
+                "file" => local_fs,
 
                 #[cfg(feature = "s3")]
-                "s3" | "s3a" => Box::new(SyncObjectFS::new_s3(scheme, self.host, self.properties)?),
+                "s3" | "s3a" => {
+                    let mut file_system = SyncObjectFS::new_s3(scheme, self.host, self.properties)?
+                        .with_upload_options(self.upload_options.unwrap_or_default());
+                    if let Some(provider) = self.credential_provider {
+                        file_system = file_system.with_credential_provider(provider)?;
+                    }
+                    match self.runtime_handle {
+                        Some(handle) => Box::new(file_system.with_handle(handle)),
+                        None => Box::new(file_system),
+                    }
+                }
 
                 _ => Err(InnoFileError::SchemeNotSupported(scheme))?,
             },
+        };
+        let file_system: Box<dyn SyncFileSystem> = match self.retry_policy {
+            Some(policy) => Box::new(SyncRetryFS::new(file_system, policy)),
+            None => file_system,
+        };
+        Ok(match self.cache_options {
+            Some(options) => Box::new(SyncCachingFS::new(file_system, options)?),
+            None => file_system,
         })
     }
 }
@@ -0,0 +1,333 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File as FsFile},
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::utils::random_u64;
+
+/// Options of the local disk cache of a caching file system.
+///
+/// Cached content lives as long as the file system, content left in the directory by previous
+/// runs isn't reused.
+#[derive(Debug, Clone)]
+pub struct CacheOptions {
+    dir: PathBuf,
+    max_size: u64,
+    ttl: Option<Duration>,
+}
+
+impl CacheOptions {
+    /// Caches files in `dir`, up to 1 GiB by default, without expiry.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            max_size: 1024 * 1024 * 1024,
+            ttl: None,
+        }
+    }
+
+    /// Sets the total size of the cached content, beyond which the least recently used entries
+    /// are evicted.
+    pub fn with_max_size(mut self, max_size: u64) -> Self {
+        self.max_size = max_size;
+        self
+    }
+
+    /// Expires entries `ttl` after they were cached.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub entries: usize,
+    pub size: u64,
+}
+
+/// Identifies cached content: a whole file, or a range of it, at a given version.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct CacheKey {
+    path: String,
+    version: String,
+    range: Option<Range<u64>>,
+}
+
+impl CacheKey {
+    /// Keys the content of the file at `path`, versioned by its ETag if known, otherwise by its
+    /// modification time and length.
+    pub(crate) fn new(
+        path: impl ToString,
+        version: Option<String>,
+        last_modified: Option<SystemTime>,
+        len: u64,
+    ) -> Self {
+        let version = version.unwrap_or_else(|| {
+            let modified = last_modified
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_nanos());
+            format!("{}-{}", modified, len)
+        });
+        Self {
+            path: path.to_string(),
+            version,
+            range: None,
+        }
+    }
+
+    pub(crate) fn with_range(&self, range: Range<u64>) -> Self {
+        Self {
+            range: Some(range),
+            ..self.clone()
+        }
+    }
+}
+
+/// Metadata of a file when it was opened, which keys its cached content.
+#[derive(Debug, Clone)]
+pub(crate) struct CachedMetadata {
+    pub(crate) len: u64,
+    pub(crate) last_modified: Option<SystemTime>,
+    pub(crate) version: Option<String>,
+}
+
+impl CachedMetadata {
+    pub(crate) fn key(&self, path: impl ToString) -> CacheKey {
+        CacheKey::new(path, self.version.clone(), self.last_modified, self.len)
+    }
+}
+
+#[derive(Debug)]
+struct Entry {
+    file_path: PathBuf,
+    len: u64,
+    cached_at: Instant,
+    last_used: u64,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    entries: HashMap<CacheKey, Entry>,
+    clock: u64,
+    stats: CacheStats,
+}
+
+impl State {
+    fn remove(&mut self, key: &CacheKey) {
+        if let Some(entry) = self.entries.remove(key) {
+            _ = fs::remove_file(&entry.file_path);
+            self.stats.size -= entry.len;
+        }
+    }
+}
+
+/// Least recently used cache of file content on local disk, shared by the files of a caching
+/// file system.
+#[derive(Debug)]
+pub(crate) struct Cache {
+    options: CacheOptions,
+    state: Mutex<State>,
+}
+
+impl Cache {
+    pub(crate) fn new(options: CacheOptions) -> std::io::Result<Self> {
+        fs::create_dir_all(&options.dir)?;
+        Ok(Self {
+            options,
+            state: Default::default(),
+        })
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn stats(&self) -> CacheStats {
+        let state = self.state();
+        CacheStats {
+            entries: state.entries.len(),
+            ..state.stats.clone()
+        }
+    }
+
+    /// Whether content of `len` bytes fits in the cache at all.
+    pub(crate) fn admits(&self, len: u64) -> bool {
+        len <= self.options.max_size
+    }
+
+    /// Opens the cached content of `key`, counting a hit or a miss.
+    pub(crate) fn get(&self, key: &CacheKey) -> Option<FsFile> {
+        let mut state = self.state();
+        let expired = state.entries.get(key).is_some_and(|entry| {
+            self.options
+                .ttl
+                .is_some_and(|ttl| entry.cached_at.elapsed() > ttl)
+        });
+        if expired {
+            state.remove(key);
+        }
+        state.clock += 1;
+        let clock = state.clock;
+        let file = state.entries.get_mut(key).and_then(|entry| {
+            entry.last_used = clock;
+            FsFile::open(&entry.file_path).ok()
+        });
+        match file {
+            Some(_) => state.stats.hits += 1,
+            None => state.stats.misses += 1,
+        }
+        file
+    }
+
+    /// Caches `content` as the content of `key` and opens it.
+    pub(crate) fn insert(&self, key: CacheKey, content: &[u8]) -> std::io::Result<FsFile> {
+        let file_path = self.file_path();
+        fs::write(&file_path, content)?;
+        self.insert_file(key, file_path, content.len() as u64)
+    }
+
+    /// Returns the path of a new file in the cache directory, to write content to cache to.
+    pub(crate) fn file_path(&self) -> PathBuf {
+        self.options.dir.join(format!("{:016x}", random_u64()))
+    }
+
+    /// Caches the file at `file_path` of `len` bytes as the content of `key`, evicting the least
+    /// recently used entries to make room for it, and opens it.
+    pub(crate) fn insert_file(
+        &self,
+        key: CacheKey,
+        file_path: PathBuf,
+        len: u64,
+    ) -> std::io::Result<FsFile> {
+        let file = match FsFile::open(&file_path) {
+            Ok(file) => file,
+            Err(error) => {
+                _ = fs::remove_file(&file_path);
+                return Err(error);
+            }
+        };
+
+        let mut state = self.state();
+        state.remove(&key);
+        while state.stats.size + len > self.options.max_size {
+            let Some(lru) = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(key, _)| key.clone())
+            else {
+                break;
+            };
+            state.remove(&lru);
+            state.stats.evictions += 1;
+        }
+        state.clock += 1;
+        let entry = Entry {
+            file_path,
+            len,
+            cached_at: Instant::now(),
+            last_used: state.clock,
+        };
+        state.entries.insert(key, entry);
+        state.stats.size += len;
+        Ok(file)
+    }
+
+    /// Removes the cached content of the file at `path`, or of the files under it.
+    pub(crate) fn invalidate(&self, path: &str) {
+        let dir = format!("{}/", path.trim_end_matches('/'));
+        let mut state = self.state();
+        let keys = state
+            .entries
+            .keys()
+            .filter(|key| key.path == path || key.path.starts_with(&dir))
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in keys {
+            state.remove(&key);
+        }
+    }
+}
+
+impl Drop for Cache {
+    fn drop(&mut self) {
+        for entry in self.state().entries.values() {
+            _ = fs::remove_file(&entry.file_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read as _;
+
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_cache() -> std::io::Result<()> {
+        let dir = tempdir()?;
+        let cache = Cache::new(CacheOptions::new(dir.path()).with_max_size(8))?;
+        let key = CacheKey::new("a.txt", Some("v1".to_string()), None, 4);
+
+        assert!(cache.get(&key).is_none());
+        cache.insert(key.clone(), b"aaaa")?;
+        let mut buf = String::new();
+        cache.get(&key).unwrap().read_to_string(&mut buf)?;
+        assert_eq!(buf, "aaaa");
+
+        // the whole file is now the least recently used entry
+        cache.insert(key.with_range(0..2), b"aa")?;
+        cache.get(&key.with_range(0..2)).unwrap();
+        cache.insert(CacheKey::new("b.txt", None, None, 4), b"bbbb")?;
+        assert!(cache.get(&key).is_none());
+        assert!(!cache.admits(9));
+
+        cache.invalidate("a.txt");
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 2,
+                misses: 2,
+                evictions: 1,
+                entries: 1,
+                size: 4,
+            }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalidate() -> std::io::Result<()> {
+        let dir = tempdir()?;
+        let cache = Cache::new(CacheOptions::new(dir.path()))?;
+        let paths = [
+            "data/a.txt",
+            "data/a.txt.bak",
+            "data/b/c.txt",
+            "data/bc.txt",
+        ];
+        for path in paths {
+            cache.insert(CacheKey::new(path, None, None, 1), b"x")?;
+        }
+
+        cache.invalidate("data/a.txt");
+        cache.invalidate("data/b");
+        for (path, cached) in paths.into_iter().zip([false, true, false, true]) {
+            let key = CacheKey::new(path, None, None, 1);
+            assert_eq!(cache.get(&key).is_some(), cached, "{path}");
+        }
+
+        Ok(())
+    }
+}
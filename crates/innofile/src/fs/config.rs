@@ -0,0 +1,440 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+#[cfg(feature = "s3")]
+use super::credential::{CredentialProvider, Credentials};
+use super::{cache::CacheOptions, retry::RetryPolicy, FileSystemBuilder};
+use crate::error::{InnoFileError, InnoFileResult};
+
+/// Named profiles of file system settings, loaded from a TOML file such as the following. Other
+/// formats, such as YAML, are out of scope.
+///
+/// ```toml
+/// [profiles.warehouse]
+/// scheme = "s3"
+/// bucket = "analytics-*"
+/// region = "eu-west-1"
+/// credentials = { type = "profile", name = "analytics" }
+/// retry = { max_attempts = 5 }
+/// cache = { dir = "/var/cache/innofile", max_size = 10737418240 }
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Profile {
+    /// Scheme of the paths the profile applies to.
+    pub scheme: Option<String>,
+    /// Pattern of the buckets, or hosts, the profile applies to, where `*` matches any text.
+    pub bucket: Option<String>,
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+    pub credentials: Option<CredentialsConfig>,
+    pub properties: HashMap<String, String>,
+    pub retry: Option<RetryConfig>,
+    pub cache: Option<CacheConfig>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CredentialsConfig {
+    Static {
+        access_key_id: String,
+        secret_access_key: String,
+        session_token: Option<String>,
+    },
+    Env,
+    Profile {
+        name: Option<String>,
+        path: Option<PathBuf>,
+    },
+    WebIdentity {
+        token_file: PathBuf,
+        role_arn: String,
+        session_name: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    pub max_attempts: Option<u32>,
+    pub initial_backoff_ms: Option<u64>,
+    pub max_backoff_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CacheConfig {
+    pub dir: PathBuf,
+    pub max_size: Option<u64>,
+    pub ttl_secs: Option<u64>,
+}
+
+impl Config {
+    pub fn parse(content: &str) -> InnoFileResult<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> InnoFileResult<Self> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Path of the config file: `INNOFILE_CONFIG` if set, or else `innofile/config.toml` in the
+    /// user config directory.
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = std::env::var_os("INNOFILE_CONFIG") {
+            return Some(path.into());
+        }
+        let config_dir = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) => PathBuf::from(dir),
+            None => PathBuf::from(std::env::var_os("HOME")?).join(".config"),
+        };
+        Some(config_dir.join("innofile/config.toml"))
+    }
+
+    /// Loads the config file, if any. A missing `INNOFILE_CONFIG` file is an error, while a
+    /// missing default config file is not.
+    pub fn load() -> InnoFileResult<Option<Self>> {
+        let Some(path) = Self::path() else {
+            return Ok(None);
+        };
+        if std::env::var_os("INNOFILE_CONFIG").is_none() && !path.exists() {
+            return Ok(None);
+        }
+        Self::from_file(path).map(Some)
+    }
+
+    pub fn profile(&self, name: &str) -> InnoFileResult<&Profile> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| InnoFileError::ProfileNotFound(name.to_string()))
+    }
+
+    /// Finds the profile applying to paths of `scheme` and `host`, preferring profiles with the
+    /// longest bucket pattern, then profiles with a scheme. Local paths match no profile.
+    pub fn matching_profile(&self, scheme: Option<&str>, host: Option<&str>) -> Option<&Profile> {
+        self.profiles
+            .values()
+            .filter(|profile| profile.matches(scheme, host))
+            .rev()
+            .max_by_key(|profile| {
+                let bucket_len = profile.bucket.as_ref().map(|bucket| bucket.len() + 1);
+                (bucket_len, profile.scheme.is_some())
+            })
+    }
+}
+
+impl Profile {
+    fn matches(&self, scheme: Option<&str>, host: Option<&str>) -> bool {
+        let bucket_matches = match &self.bucket {
+            Some(pattern) => host.is_some_and(|host| matches_pattern(pattern, host)),
+            None => true,
+        };
+        self.matches_scheme(scheme) && bucket_matches
+    }
+
+    /// Whether the profile applies to paths of `scheme`, which local paths never do.
+    fn matches_scheme(&self, scheme: Option<&str>) -> bool {
+        match scheme {
+            _ if is_local(scheme) => false,
+            Some(scheme) => self
+                .scheme
+                .as_ref()
+                .is_none_or(|pattern| scheme.eq_ignore_ascii_case(pattern)),
+            None => false,
+        }
+    }
+}
+
+/// Whether paths of `scheme` are local paths.
+fn is_local(scheme: Option<&str>) -> bool {
+    scheme.is_none_or(|scheme| scheme.eq_ignore_ascii_case("file"))
+}
+
+/// Whether `value` matches `pattern`, where `*` matches any text.
+fn matches_pattern(pattern: &str, value: &str) -> bool {
+    match pattern.split_once('*') {
+        None => pattern == value,
+        Some((prefix, rest)) => {
+            let Some(value) = value.strip_prefix(prefix) else {
+                return false;
+            };
+            (0..=value.len())
+                .filter(|&i| value.is_char_boundary(i))
+                .any(|i| matches_pattern(rest, &value[i..]))
+        }
+    }
+}
+
+impl FileSystemBuilder {
+    /// Applies the settings of `profile`, overriding those set already.
+    pub fn apply_profile(mut self, profile: &Profile) -> Self {
+        self.merge_profile(profile, true);
+        self
+    }
+
+    /// Applies the settings of `profile`, overriding those set already if `overwrite`, or else
+    /// only setting the others.
+    pub(super) fn merge_profile(&mut self, profile: &Profile, overwrite: bool) {
+        let mut properties = HashMap::new();
+        if let Some(endpoint) = &profile.endpoint {
+            properties.insert("endpoint".to_string(), endpoint.clone());
+        }
+        if let Some(region) = &profile.region {
+            properties.insert("region".to_string(), region.clone());
+        }
+        properties.extend(profile.properties.clone());
+        for (key, value) in properties {
+            if overwrite || !self.properties.contains_key(&key) {
+                self.properties.insert(key, value);
+            }
+        }
+
+        if let Some(retry) = &profile.retry {
+            if overwrite || self.retry_policy.is_none() {
+                self.retry_policy = Some(retry.clone().into());
+            }
+        }
+
+        if let Some(cache) = &profile.cache {
+            if overwrite || self.cache_options.is_none() {
+                self.cache_options = Some(cache.clone().into());
+            }
+        }
+
+        #[cfg(feature = "s3")]
+        if let Some(credentials) = &profile.credentials {
+            if overwrite || self.credential_provider.is_none() {
+                self.credential_provider = Some(credentials.clone().into());
+            }
+        }
+    }
+
+    /// Applies the profile of `config` matching the scheme and host set already, if any.
+    pub fn with_config(self, config: &Config) -> Self {
+        match config.matching_profile(self.scheme.as_deref(), self.host.as_deref()) {
+            Some(profile) => self.apply_profile(profile),
+            None => self,
+        }
+    }
+
+    /// Finds the profile of the config file matching the scheme and host set already, which
+    /// building applies to the settings not set explicitly. The config file is not loaded for
+    /// local paths, which match no profile.
+    pub(super) fn with_config_file(self) -> InnoFileResult<Self> {
+        if is_local(self.scheme.as_deref()) {
+            return Ok(self);
+        }
+        Ok(self.with_matching_profile(Config::load()?.as_ref()))
+    }
+
+    /// Sets the profile of `config` matching the file system, if any.
+    fn with_matching_profile(mut self, config: Option<&Config>) -> Self {
+        self.profile = config
+            .and_then(|config| {
+                config.matching_profile(self.scheme.as_deref(), self.host.as_deref())
+            })
+            .cloned();
+        self
+    }
+
+    /// Does not apply the profile of the config file matching the path given to
+    /// [`FileSystemBuilder::from_path`].
+    pub fn without_config(mut self) -> Self {
+        self.profile = None;
+        self
+    }
+
+    /// Applies the named profile of the config file instead of the matching one, if a name is
+    /// given. The config file is not loaded for local paths, to which profiles never apply.
+    pub fn with_profile(self, name: Option<impl AsRef<str>>) -> InnoFileResult<Self> {
+        let Some(name) = name else {
+            return Ok(self);
+        };
+        if is_local(self.scheme.as_deref()) {
+            return Ok(self);
+        }
+        let name = name.as_ref();
+        let config =
+            Config::load()?.ok_or_else(|| InnoFileError::ProfileNotFound(name.to_string()))?;
+        self.with_named_profile(&config, name)
+    }
+
+    /// Applies the profile of `config` named `name` instead of the matching one, if it applies to
+    /// the scheme set already.
+    pub fn with_named_profile(mut self, config: &Config, name: &str) -> InnoFileResult<Self> {
+        let profile = config.profile(name)?;
+        if !profile.matches_scheme(self.scheme.as_deref()) {
+            return Ok(self);
+        }
+        self.profile = None;
+        Ok(self.apply_profile(profile))
+    }
+}
+
+impl From<RetryConfig> for RetryPolicy {
+    fn from(value: RetryConfig) -> Self {
+        let mut policy = RetryPolicy::new();
+        if let Some(max_attempts) = value.max_attempts {
+            policy = policy.with_max_attempts(max_attempts);
+        }
+        if let Some(initial_backoff_ms) = value.initial_backoff_ms {
+            policy = policy.with_initial_backoff(Duration::from_millis(initial_backoff_ms));
+        }
+        if let Some(max_backoff_ms) = value.max_backoff_ms {
+            policy = policy.with_max_backoff(Duration::from_millis(max_backoff_ms));
+        }
+        policy
+    }
+}
+
+impl From<CacheConfig> for CacheOptions {
+    fn from(value: CacheConfig) -> Self {
+        let mut options = CacheOptions::new(&value.dir);
+        if let Some(max_size) = value.max_size {
+            options = options.with_max_size(max_size);
+        }
+        if let Some(ttl_secs) = value.ttl_secs {
+            options = options.with_ttl(Duration::from_secs(ttl_secs));
+        }
+        options
+    }
+}
+
+#[cfg(feature = "s3")]
+impl From<CredentialsConfig> for CredentialProvider {
+    fn from(value: CredentialsConfig) -> Self {
+        match value {
+            CredentialsConfig::Static {
+                access_key_id,
+                secret_access_key,
+                session_token,
+            } => {
+                let credentials = Credentials::new(access_key_id, secret_access_key);
+                Self::Static(match session_token {
+                    Some(token) => credentials.with_session_token(token),
+                    None => credentials,
+                })
+            }
+            CredentialsConfig::Env => Self::Env,
+            CredentialsConfig::Profile { name, path } => Self::Profile { name, path },
+            CredentialsConfig::WebIdentity {
+                token_file,
+                role_arn,
+                session_name,
+            } => Self::WebIdentity {
+                token_file,
+                role_arn,
+                session_name,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config() -> InnoFileResult<()> {
+        let config = Config::parse(
+            r#"
+            [profiles.default]
+            retry = { max_attempts = 2 }
+
+            [profiles.s3]
+            scheme = "s3"
+            region = "us-east-1"
+
+            [profiles.warehouse]
+            scheme = "s3"
+            bucket = "analytics-*"
+            endpoint = "http://localhost:9000"
+            credentials = { type = "profile", name = "analytics" }
+            properties = { allow_http = "true" }
+            cache = { dir = "/tmp/innofile", ttl_secs = 60 }
+            "#,
+        )?;
+
+        let profile = config.matching_profile(Some("s3"), Some("analytics-eu"));
+        assert_eq!(profile.unwrap().bucket.as_deref(), Some("analytics-*"));
+        let profile = config.matching_profile(Some("S3"), Some("reference"));
+        assert_eq!(profile.unwrap().region.as_deref(), Some("us-east-1"));
+        let profile = config
+            .matching_profile(Some("hdfs"), Some("namenode"))
+            .unwrap();
+        assert_eq!(profile.retry.as_ref().unwrap().max_attempts, Some(2));
+        assert!(config.matching_profile(None, None).is_none());
+        assert!(config.matching_profile(Some("file"), None).is_none());
+
+        let path = "s3://analytics-eu/data.parquet";
+        let builder = FileSystemBuilder::parse_path(path)?.with_matching_profile(Some(&config));
+        let profile = builder.profile.as_ref().unwrap();
+        assert_eq!(profile.bucket.as_deref(), Some("analytics-*"));
+        let builder = builder.without_config();
+        assert!(builder.properties.is_empty() && builder.profile.is_none());
+        let builder = builder.with_config(&config);
+        assert_eq!(builder.properties["endpoint"], "http://localhost:9000");
+        let builder = FileSystemBuilder::from_path("/tmp/data.parquet")?.with_config(&config);
+        assert!(builder.retry_policy.is_none() && builder.cache_options.is_none());
+
+        // the matching profile of the config file only sets the settings not set explicitly
+        let mut builder = FileSystemBuilder::parse_path(path)?
+            .with_matching_profile(Some(&config))
+            .with_property("endpoint", "http://localhost:9001");
+        builder.resolve_profile();
+        assert_eq!(builder.properties["endpoint"], "http://localhost:9001");
+        assert_eq!(builder.properties["allow_http"], "true");
+
+        // named profiles only apply to paths of their scheme, never to local paths
+        let builder = FileSystemBuilder::from_path("/tmp/data.parquet")?
+            .with_named_profile(&config, "warehouse")?;
+        assert!(builder.properties.is_empty() && builder.cache_options.is_none());
+        let builder = FileSystemBuilder::parse_path("s3://reference/data.parquet")?
+            .with_named_profile(&config, "warehouse")?;
+        assert_eq!(builder.properties["endpoint"], "http://localhost:9000");
+
+        let builder = FileSystemBuilder::new().apply_profile(config.profile("warehouse")?);
+        assert_eq!(builder.properties["endpoint"], "http://localhost:9000");
+        assert_eq!(builder.properties["allow_http"], "true");
+        assert!(builder.cache_options.is_some());
+        assert!(matches!(
+            config.profile("missing"),
+            Err(InnoFileError::ProfileNotFound(_))
+        ));
+        assert!(Config::parse("[profiles.typo]\nregoin = \"eu\"").is_err());
+
+        let config = Config::parse(
+            r#"
+            [profiles.eks.credentials]
+            type = "web_identity"
+            token_file = "/var/run/secrets/token"
+            role_arn = "arn:aws:iam::123456789012:role/reader"
+            "#,
+        )?;
+        let credentials = config.profile("eks")?.credentials.clone();
+        assert!(matches!(
+            credentials,
+            Some(CredentialsConfig::WebIdentity {
+                session_name: None,
+                ..
+            })
+        ));
+
+        assert!(matches_pattern("analytics-*-raw", "analytics-eu-raw"));
+        assert!(!matches_pattern("analytics-*", "reference"));
+
+        Ok(())
+    }
+}
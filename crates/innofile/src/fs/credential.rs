@@ -0,0 +1,428 @@
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::anyhow;
+use async_trait::async_trait;
+use object_store::aws::AwsCredential;
+use tokio::sync::Mutex;
+
+use crate::error::InnoFileResult;
+
+/// Credentials are refreshed this long before they expire.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(5 * 60);
+
+/// Session name of the credentials of web identity tokens, unless set otherwise.
+const DEFAULT_SESSION_NAME: &str = "innofile";
+
+/// Keys to access an object store, which may expire.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+    pub expiry: Option<SystemTime>,
+}
+
+impl Credentials {
+    pub fn new(access_key_id: impl ToString, secret_access_key: impl ToString) -> Self {
+        Self {
+            access_key_id: access_key_id.to_string(),
+            secret_access_key: secret_access_key.to_string(),
+            session_token: None,
+            expiry: None,
+        }
+    }
+
+    pub fn with_session_token(mut self, session_token: impl ToString) -> Self {
+        self.session_token = Some(session_token.to_string());
+        self
+    }
+
+    pub fn with_expiry(mut self, expiry: SystemTime) -> Self {
+        self.expiry = Some(expiry);
+        self
+    }
+
+    fn is_fresh(&self) -> bool {
+        self.expiry
+            .is_none_or(|expiry| SystemTime::now() + EXPIRY_MARGIN < expiry)
+    }
+}
+
+impl Debug for Credentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("access_key_id", &self.access_key_id)
+            .field("expiry", &self.expiry)
+            .finish_non_exhaustive()
+    }
+}
+
+impl From<Credentials> for AwsCredential {
+    fn from(value: Credentials) -> Self {
+        Self {
+            key_id: value.access_key_id,
+            secret_key: value.secret_access_key,
+            token: value.session_token,
+        }
+    }
+}
+
+pub type CredentialCallback = Arc<dyn Fn() -> InnoFileResult<Credentials> + Send + Sync>;
+
+/// Source of the credentials of an object store, fetched again once they expire.
+///
+/// Stores without a provider resolve their credentials from the environment, including web
+/// identity tokens of `AWS_WEB_IDENTITY_TOKEN_FILE` exchanged for the role of `AWS_ROLE_ARN`.
+#[derive(Clone)]
+pub enum CredentialProvider {
+    Static(Credentials),
+
+    /// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY` and `AWS_SESSION_TOKEN`.
+    Env,
+
+    /// Named profile of a shared credentials file, by default the `AWS_PROFILE` profile of
+    /// `~/.aws/credentials`, or its `default` profile.
+    Profile {
+        name: Option<String>,
+        path: Option<PathBuf>,
+    },
+
+    /// Web identity token of a file, such as a Kubernetes service account token, exchanged for
+    /// temporary credentials of `role_arn` with STS `AssumeRoleWithWebIdentity`. The file is read
+    /// again on each refresh, since such tokens are rotated.
+    WebIdentity {
+        token_file: PathBuf,
+        role_arn: String,
+        session_name: Option<String>,
+    },
+
+    /// Callback to a custom source, such as a vault client, called on the blocking thread pool.
+    Callback(CredentialCallback),
+}
+
+impl CredentialProvider {
+    pub fn profile(name: impl ToString) -> Self {
+        Self::Profile {
+            name: Some(name.to_string()),
+            path: None,
+        }
+    }
+
+    pub fn callback(
+        callback: impl Fn() -> InnoFileResult<Credentials> + Send + Sync + 'static,
+    ) -> Self {
+        Self::Callback(Arc::new(callback))
+    }
+
+    /// Fetches new credentials from this source.
+    pub async fn fetch(&self) -> InnoFileResult<Credentials> {
+        match self {
+            Self::Static(credentials) => Ok(credentials.clone()),
+            Self::Env => {
+                let var = |name| std::env::var(name).map_err(|_| anyhow!("{name} not set"));
+                let credentials =
+                    Credentials::new(var("AWS_ACCESS_KEY_ID")?, var("AWS_SECRET_ACCESS_KEY")?);
+                Ok(match std::env::var("AWS_SESSION_TOKEN") {
+                    Ok(token) => credentials.with_session_token(token),
+                    Err(_) => credentials,
+                })
+            }
+            Self::Profile { name, path } => profile_credentials(name.as_deref(), path.clone()),
+            Self::WebIdentity {
+                token_file,
+                role_arn,
+                session_name,
+            } => {
+                let session_name = session_name.as_deref().unwrap_or(DEFAULT_SESSION_NAME);
+                web_identity_credentials(&sts_endpoint(), token_file, role_arn, session_name).await
+            }
+            Self::Callback(callback) => {
+                let callback = Arc::clone(callback);
+                tokio::task::spawn_blocking(move || callback())
+                    .await
+                    .map_err(std::io::Error::from)?
+            }
+        }
+    }
+}
+
+impl Debug for CredentialProvider {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Static(credentials) => f.debug_tuple("Static").field(credentials).finish(),
+            Self::Env => f.write_str("Env"),
+            Self::Profile { name, path } => f
+                .debug_struct("Profile")
+                .field("name", name)
+                .field("path", path)
+                .finish(),
+            Self::WebIdentity {
+                token_file,
+                role_arn,
+                session_name,
+            } => f
+                .debug_struct("WebIdentity")
+                .field("token_file", token_file)
+                .field("role_arn", role_arn)
+                .field("session_name", session_name)
+                .finish(),
+            Self::Callback(_) => f.write_str("Callback"),
+        }
+    }
+}
+
+/// Reads a profile of a shared credentials file, made of `[profile]` sections of `key = value`
+/// lines.
+fn profile_credentials(name: Option<&str>, path: Option<PathBuf>) -> InnoFileResult<Credentials> {
+    let name = name
+        .map(|name| name.to_string())
+        .or_else(|| std::env::var("AWS_PROFILE").ok())
+        .unwrap_or_else(|| "default".to_string());
+    let path = match path {
+        Some(path) => path,
+        None => match std::env::var_os("AWS_SHARED_CREDENTIALS_FILE") {
+            Some(path) => path.into(),
+            None => std::env::var_os("HOME")
+                .map(|home| PathBuf::from(home).join(".aws/credentials"))
+                .ok_or_else(|| anyhow!("Home directory not found"))?,
+        },
+    };
+
+    let content = std::fs::read_to_string(&path)?;
+    let mut section = None;
+    let mut values = HashMap::new();
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = Some(header.trim());
+        } else if section == Some(name.as_str()) {
+            if let Some((key, value)) = line.split_once('=') {
+                values.insert(key.trim(), value.trim());
+            }
+        }
+    }
+
+    let value = |key| {
+        values
+            .get(key)
+            .ok_or_else(|| anyhow!("{key} not found in profile {name} of {}", path.display()))
+    };
+    let credentials =
+        Credentials::new(value("aws_access_key_id")?, value("aws_secret_access_key")?);
+    Ok(match values.get("aws_session_token") {
+        Some(token) => credentials.with_session_token(token),
+        None => credentials,
+    })
+}
+
+/// Returns the STS endpoint of `AWS_ENDPOINT_URL_STS`, or the regional endpoint of `AWS_REGION`.
+fn sts_endpoint() -> String {
+    if let Ok(endpoint) = std::env::var("AWS_ENDPOINT_URL_STS") {
+        return endpoint;
+    }
+    match std::env::var("AWS_REGION").or_else(|_| std::env::var("AWS_DEFAULT_REGION")) {
+        Ok(region) => format!("https://sts.{region}.amazonaws.com"),
+        Err(_) => "https://sts.amazonaws.com".to_string(),
+    }
+}
+
+/// Exchanges the web identity token of `token_file` for temporary credentials of `role_arn`.
+async fn web_identity_credentials(
+    endpoint: &str,
+    token_file: &Path,
+    role_arn: &str,
+    session_name: &str,
+) -> InnoFileResult<Credentials> {
+    let token = tokio::fs::read_to_string(token_file).await?;
+    let response = reqwest::Client::new()
+        .post(endpoint)
+        .form(&[
+            ("Action", "AssumeRoleWithWebIdentity"),
+            ("Version", "2011-06-15"),
+            ("RoleArn", role_arn),
+            ("RoleSessionName", session_name),
+            ("WebIdentityToken", token.trim()),
+        ])
+        .send()
+        .await
+        .map_err(anyhow::Error::from)?;
+    let status = response.status();
+    let body = response.text().await.map_err(anyhow::Error::from)?;
+    if !status.is_success() {
+        return Err(anyhow!("AssumeRoleWithWebIdentity failed with {status}: {body}").into());
+    }
+
+    let value = |tag| {
+        xml_text(&body, tag).ok_or_else(|| anyhow!("{tag} not found in STS response: {body}"))
+    };
+    let expiry =
+        chrono::DateTime::parse_from_rfc3339(value("Expiration")?).map_err(anyhow::Error::from)?;
+    Ok(
+        Credentials::new(value("AccessKeyId")?, value("SecretAccessKey")?)
+            .with_session_token(value("SessionToken")?)
+            .with_expiry(expiry.into()),
+    )
+}
+
+/// Returns the text of the first `tag` element of `xml`, which STS responses have no markup in.
+fn xml_text<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{tag}>"))? + tag.len() + 2;
+    let len = xml[start..].find(&format!("</{tag}>"))?;
+    Some(xml[start..start + len].trim())
+}
+
+/// Provides the credentials of a [`CredentialProvider`] to S3 stores, caching them until they
+/// are about to expire.
+#[derive(Debug)]
+pub(crate) struct AwsCredentialProvider {
+    provider: CredentialProvider,
+    cached: Mutex<Option<(Credentials, Arc<AwsCredential>)>>,
+}
+
+impl AwsCredentialProvider {
+    pub(crate) fn new(provider: CredentialProvider) -> Self {
+        Self {
+            provider,
+            cached: Mutex::new(None),
+        }
+    }
+}
+
+#[async_trait]
+impl object_store::CredentialProvider for AwsCredentialProvider {
+    type Credential = AwsCredential;
+
+    async fn get_credential(&self) -> object_store::Result<Arc<AwsCredential>> {
+        let mut cached = self.cached.lock().await;
+        if let Some((credentials, credential)) = &*cached {
+            if credentials.is_fresh() {
+                return Ok(Arc::clone(credential));
+            }
+        }
+        let credentials =
+            self.provider
+                .fetch()
+                .await
+                .map_err(|error| object_store::Error::Generic {
+                    store: "S3",
+                    source: Box::new(error),
+                })?;
+        let credential = Arc::new(AwsCredential::from(credentials.clone()));
+        *cached = Some((credentials, Arc::clone(&credential)));
+        Ok(credential)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read as _, Write as _},
+        net::TcpListener,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    use object_store::CredentialProvider as _;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_credential_provider() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("credentials");
+        std::fs::write(
+            &path,
+            "[default]\naws_access_key_id = default\naws_secret_access_key = secret\n\n\
+             # vault mirror\n[tenant-42]\naws_access_key_id=tenant\naws_secret_access_key=secret\n",
+        )?;
+        let provider = CredentialProvider::Profile {
+            name: Some("tenant-42".to_string()),
+            path: Some(path),
+        };
+        assert_eq!(
+            provider.fetch().await?,
+            Credentials::new("tenant", "secret")
+        );
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let callback_calls = Arc::clone(&calls);
+        let provider = AwsCredentialProvider::new(CredentialProvider::callback(move || {
+            let call = callback_calls.fetch_add(1, Ordering::SeqCst);
+            // the first credentials expire within the refresh margin
+            let expiry = SystemTime::now() + Duration::from_secs(60 + call as u64 * 3600);
+            Ok(Credentials::new(format!("key-{call}"), "secret").with_expiry(expiry))
+        }));
+        for key_id in ["key-0", "key-1", "key-1"] {
+            assert_eq!(provider.get_credential().await?.key_id, key_id);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_web_identity() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let token_file = dir.path().join("token");
+        std::fs::write(&token_file, "web-identity-token\n")?;
+
+        // STS stand-in answering a single request
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let endpoint = format!("http://{}", listener.local_addr()?);
+        let server = std::thread::spawn(move || -> std::io::Result<String> {
+            let (mut stream, _) = listener.accept()?;
+            let mut request = String::new();
+            let mut buf = [0; 4096];
+            loop {
+                let len = stream.read(&mut buf)?;
+                request.push_str(&String::from_utf8_lossy(&buf[..len]));
+                let complete = request.split_once("\r\n\r\n").is_some_and(|(head, body)| {
+                    let content_length = head
+                        .lines()
+                        .find_map(|line| line.strip_prefix("content-length: "))
+                        .and_then(|len| len.parse().ok());
+                    content_length == Some(body.len())
+                });
+                if len == 0 || complete {
+                    break;
+                }
+            }
+            let body = "<AssumeRoleWithWebIdentityResponse><AssumeRoleWithWebIdentityResult>\
+                <Credentials><AccessKeyId>ASIAEXAMPLE</AccessKeyId>\
+                <SecretAccessKey>secret</SecretAccessKey><SessionToken>token</SessionToken>\
+                <Expiration>2030-01-01T00:00:00Z</Expiration></Credentials>\
+                </AssumeRoleWithWebIdentityResult></AssumeRoleWithWebIdentityResponse>";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )?;
+            Ok(request)
+        });
+
+        let role_arn = "arn:aws:iam::123456789012:role/reader";
+        let credentials =
+            web_identity_credentials(&endpoint, &token_file, role_arn, "tenant-42").await?;
+        let expiry = SystemTime::UNIX_EPOCH + Duration::from_secs(1_893_456_000);
+        assert_eq!(
+            credentials,
+            Credentials::new("ASIAEXAMPLE", "secret")
+                .with_session_token("token")
+                .with_expiry(expiry)
+        );
+        let request = server.join().unwrap()?;
+        assert!(request.contains("Action=AssumeRoleWithWebIdentity"));
+        assert!(request.contains("RoleSessionName=tenant-42"));
+        assert!(request.ends_with("WebIdentityToken=web-identity-token"));
+
+        Ok(())
+    }
+}
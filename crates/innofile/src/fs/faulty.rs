@@ -0,0 +1,182 @@
+use std::{
+    collections::BTreeSet,
+    io::ErrorKind,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use crate::utils::random_fraction;
+
+/// Failures to inject into the calls of a file system, its files and their readers and writers.
+///
+/// Calls are numbered from 1 in the order they are made, across everything opened from the same
+/// file system. Only reads and writes that reach the underlying reader or writer count as calls,
+/// reads served from a buffer don't.
+#[derive(Debug, Clone)]
+pub struct FaultPolicy {
+    failing_calls: BTreeSet<usize>,
+    failure_interval: Option<usize>,
+    error_rate: f64,
+    error_kind: ErrorKind,
+    max_read_len: Option<usize>,
+    max_write_len: Option<usize>,
+    write_limit: Option<u64>,
+    latency: Option<Duration>,
+}
+
+impl Default for FaultPolicy {
+    fn default() -> Self {
+        Self {
+            failing_calls: BTreeSet::new(),
+            failure_interval: None,
+            error_rate: 0.0,
+            error_kind: ErrorKind::ConnectionReset,
+            max_read_len: None,
+            max_write_len: None,
+            write_limit: None,
+            latency: None,
+        }
+    }
+}
+
+impl FaultPolicy {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Fails the `n`th call.
+    pub fn with_failure_on_call(mut self, n: usize) -> Self {
+        self.failing_calls.insert(n);
+        self
+    }
+
+    /// Fails every `n`th call.
+    pub fn with_failure_every(mut self, n: usize) -> Self {
+        self.failure_interval = Some(n.max(1));
+        self
+    }
+
+    /// Fails each call with probability `error_rate`.
+    pub fn with_error_rate(mut self, error_rate: f64) -> Self {
+        self.error_rate = error_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the kind of injected errors, [`ErrorKind::ConnectionReset`] by default.
+    pub fn with_error_kind(mut self, error_kind: ErrorKind) -> Self {
+        self.error_kind = error_kind;
+        self
+    }
+
+    /// Returns at most `max_read_len` bytes from each read.
+    pub fn with_short_reads(mut self, max_read_len: usize) -> Self {
+        self.max_read_len = Some(max_read_len.max(1));
+        self
+    }
+
+    /// Accepts at most `max_write_len` bytes in each write.
+    pub fn with_short_writes(mut self, max_write_len: usize) -> Self {
+        self.max_write_len = Some(max_write_len.max(1));
+        self
+    }
+
+    /// Fails writes once `write_limit` bytes have been written, leaving the file truncated.
+    pub fn with_truncated_writes(mut self, write_limit: u64) -> Self {
+        self.write_limit = Some(write_limit);
+        self
+    }
+
+    /// Delays each call by `latency`.
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+}
+
+/// State of the faults injected by a [`FaultPolicy`], shared across everything opened from the
+/// same file system.
+#[derive(Debug)]
+pub(crate) struct Faults {
+    policy: FaultPolicy,
+    calls: AtomicUsize,
+}
+
+impl Faults {
+    pub(crate) fn new(policy: FaultPolicy) -> Self {
+        Self {
+            policy,
+            calls: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn calls(&self) -> usize {
+        self.calls.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn latency(&self) -> Option<Duration> {
+        self.policy.latency
+    }
+
+    /// Counts a call, failing it if the policy says so.
+    pub(crate) fn call(&self) -> std::io::Result<()> {
+        let n = self.calls.fetch_add(1, Ordering::Relaxed) + 1;
+        let policy = &self.policy;
+        if policy.failing_calls.contains(&n)
+            || policy.failure_interval.is_some_and(|i| n.is_multiple_of(i))
+            || (policy.error_rate > 0.0 && random_fraction() < policy.error_rate)
+        {
+            return Err(std::io::Error::new(
+                policy.error_kind,
+                format!("Injected failure of call {n}"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Length of the next read into a buffer of `len` bytes.
+    pub(crate) fn read_len(&self, len: usize) -> usize {
+        self.policy.max_read_len.map_or(len, |max| len.min(max))
+    }
+
+    /// Length of the next write of `len` bytes, after `written` bytes, failing it once the write
+    /// limit is reached.
+    pub(crate) fn write_len(&self, len: usize, written: u64) -> std::io::Result<usize> {
+        let len = self.policy.max_write_len.map_or(len, |max| len.min(max));
+        match self.policy.write_limit {
+            Some(limit) if written >= limit && len > 0 => Err(std::io::Error::new(
+                self.policy.error_kind,
+                format!("Injected truncation after {limit} bytes"),
+            )),
+            Some(limit) => Ok(len.min((limit - written) as usize)),
+            None => Ok(len),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_faults() {
+        let faults = Faults::new(
+            FaultPolicy::new()
+                .with_failure_on_call(2)
+                .with_failure_every(5)
+                .with_error_kind(ErrorKind::TimedOut)
+                .with_short_reads(4)
+                .with_truncated_writes(10),
+        );
+        let failed = (1..=10)
+            .filter(|_| faults.call().is_err())
+            .collect::<Vec<_>>();
+        assert_eq!(failed, [2, 5, 10]);
+        assert_eq!(faults.calls(), 10);
+
+        assert_eq!(faults.read_len(8), 4);
+        assert_eq!(faults.write_len(8, 0).unwrap(), 8);
+        assert_eq!(faults.write_len(8, 8).unwrap(), 2);
+        let error = faults.write_len(8, 10).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::TimedOut);
+    }
+}
@@ -0,0 +1,160 @@
+use fluent_uri::UriRef;
+
+use crate::error::{InnoFileError, InnoFileResult};
+
+use super::{prefix::resolve, FileStatus};
+
+/// File system mounted at a path prefix, whose paths are resolved against a root in it.
+#[derive(Debug)]
+pub(crate) struct Mount<F> {
+    prefix: String,
+    root: String,
+    pub(crate) file_system: F,
+}
+
+impl<F> Mount<F> {
+    /// Maps a status listed by the mounted file system to the path it is mounted at.
+    pub(crate) fn mount_status(&self, status: FileStatus) -> FileStatus {
+        let base = self.root.trim_end_matches('/');
+        let relative = status.path.strip_prefix(base).unwrap_or(&status.path);
+        FileStatus {
+            path: format!("{}/{}", self.prefix, relative.trim_start_matches('/')),
+            ..status
+        }
+    }
+}
+
+/// Mounts ordered from the longest prefix, so that nested mounts take precedence.
+#[derive(Debug)]
+pub(crate) struct MountTable<F> {
+    mounts: Vec<Mount<F>>,
+}
+
+impl<F> Default for MountTable<F> {
+    fn default() -> Self {
+        Self { mounts: Vec::new() }
+    }
+}
+
+impl<F> MountTable<F> {
+    /// Mounts `file_system` at `prefix`, replacing any file system mounted there.
+    pub(crate) fn insert(&mut self, prefix: &str, root: impl ToString, file_system: F) {
+        let prefix = prefix.trim_matches('/');
+        let prefix = if prefix.is_empty() {
+            String::new()
+        } else {
+            format!("/{}", prefix)
+        };
+        self.mounts.retain(|mount| mount.prefix != prefix);
+        self.mounts.push(Mount {
+            prefix,
+            root: root.to_string(),
+            file_system,
+        });
+        self.mounts
+            .sort_by_key(|mount| std::cmp::Reverse(mount.prefix.len()));
+    }
+
+    /// Finds the mount of `path` and the path it stands for in the mounted file system.
+    pub(crate) fn route(&self, path: &str) -> InnoFileResult<(&Mount<F>, String)> {
+        let path = resolve("/", path)?;
+        for mount in &self.mounts {
+            if let Some(rest) = path.strip_prefix(&mount.prefix) {
+                if rest.is_empty() || rest.starts_with('/') {
+                    return Ok((mount, resolve(&mount.root, rest)?));
+                }
+            }
+        }
+        Err(InnoFileError::MountNotFound(path))
+    }
+
+    /// Lists the directories leading to the mounts nested under `path`.
+    pub(crate) fn mount_points(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let path = resolve("/", path)?;
+        let base = path.trim_end_matches('/');
+        let mut statuses = Vec::<FileStatus>::new();
+        for mount in &self.mounts {
+            let Some(child) = mount
+                .prefix
+                .strip_prefix(base)
+                .and_then(|rest| rest.strip_prefix('/'))
+                .and_then(|rest| rest.split('/').next())
+            else {
+                continue;
+            };
+            let child_path = format!("{}/{}", base, child);
+            if !statuses.iter().any(|status| status.path == child_path) {
+                statuses.push(FileStatus::dir(child_path));
+            }
+        }
+        Ok(statuses)
+    }
+}
+
+/// Adds the mount points to a listing, unless listed already, and sorts it by path.
+pub(crate) fn merge_statuses(
+    mut statuses: Vec<FileStatus>,
+    mount_points: Vec<FileStatus>,
+) -> Vec<FileStatus> {
+    for mount_point in mount_points {
+        if !statuses
+            .iter()
+            .any(|status| status.path == mount_point.path)
+        {
+            statuses.push(mount_point);
+        }
+    }
+    statuses.sort_by(|a, b| a.path.cmp(&b.path));
+    statuses
+}
+
+/// Returns the root to mount a file system built from `uri` at: its path for local files, or
+/// its path within the bucket for object stores.
+pub(crate) fn uri_root(uri: &str) -> InnoFileResult<String> {
+    let parsed = UriRef::parse(uri)?;
+    Ok(match parsed.scheme() {
+        Some(scheme) if !scheme.as_str().eq_ignore_ascii_case("file") => {
+            parsed.path().as_str().trim_start_matches('/').to_string()
+        }
+        _ => uri.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mount_table() -> InnoFileResult<()> {
+        let mut mounts = MountTable::default();
+        mounts.insert("/", "/data", "local");
+        mounts.insert("/warehouse/", "warehouse", "s3");
+        mounts.insert("/ref/archive", "", "http");
+
+        let (mount, path) = mounts.route("/warehouse/./a.parquet")?;
+        assert_eq!(
+            (mount.file_system, path.as_str()),
+            ("s3", "warehouse/a.parquet")
+        );
+        let (mount, path) = mounts.route("/warehouses/a.parquet")?;
+        assert_eq!(
+            (mount.file_system, path.as_str()),
+            ("local", "/data/warehouses/a.parquet")
+        );
+        let (mount, path) = mounts.route("/ref/archive")?;
+        assert_eq!((mount.file_system, path.as_str()), ("http", ""));
+        assert_eq!(
+            mount.mount_status(FileStatus::dir("a.csv")).path,
+            "/ref/archive/a.csv"
+        );
+
+        let statuses = merge_statuses(Vec::new(), mounts.mount_points("/")?);
+        let paths = statuses.iter().map(|s| s.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["/ref", "/warehouse"]);
+
+        assert_eq!(uri_root("s3://bucket/warehouse/")?, "warehouse/");
+        assert_eq!(uri_root("/tmp")?, "/tmp");
+
+        Ok(())
+    }
+}
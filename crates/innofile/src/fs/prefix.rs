@@ -0,0 +1,99 @@
+use crate::error::{InnoFileError, InnoFileResult};
+
+/// Resolves `path` against `root`, as a path relative to it or as a path under it, normalizing
+/// `.` and `..` segments and rejecting paths that escape it.
+pub(crate) fn resolve(root: &str, path: &str) -> InnoFileResult<String> {
+    let base = root.trim_end_matches('/');
+    let outside_root = || InnoFileError::PathOutsideRoot {
+        path: path.to_string(),
+        root: root.to_string(),
+    };
+
+    let relative = match path.strip_prefix(base) {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => rest,
+        // a URI of another location, rather than a path relative to the root
+        _ if path.contains("://") => return Err(outside_root()),
+        _ => path,
+    };
+
+    let mut segments = Vec::new();
+    for segment in relative.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop().ok_or_else(outside_root)?;
+            }
+            segment => segments.push(segment),
+        }
+    }
+    let relative = segments.join("/");
+    Ok(match base {
+        // the bucket root of an object store has no path
+        "" if root.is_empty() => relative,
+        "" => format!("/{}", relative),
+        _ if relative.is_empty() => base.to_string(),
+        base => format!("{}/{}", base, relative),
+    })
+}
+
+/// Maps `path`, as listed by the inner file system, to a path relative to `root`.
+///
+/// Object stores list keys without the scheme and bucket of the root, which are stripped from
+/// the root as well.
+pub(crate) fn relative(root: &str, path: &str) -> String {
+    let base = root.trim_end_matches('/');
+    let key_prefix = base
+        .split_once("://")
+        .map(|(_, rest)| rest.split_once('/').map_or("", |(_, key)| key))
+        .unwrap_or(base);
+    let relative = [base, key_prefix]
+        .into_iter()
+        .find_map(|prefix| {
+            path.strip_prefix(prefix)
+                .filter(|rest| prefix.is_empty() || rest.is_empty() || rest.starts_with('/'))
+        })
+        .unwrap_or(path);
+    relative.trim_start_matches('/').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() -> InnoFileResult<()> {
+        let root = "s3://bucket/tenant-42/";
+        assert_eq!(resolve(root, "a/./b.csv")?, "s3://bucket/tenant-42/a/b.csv");
+        assert_eq!(resolve(root, "/a/../b.csv")?, "s3://bucket/tenant-42/b.csv");
+        assert_eq!(
+            resolve(root, "s3://bucket/tenant-42/a.csv")?,
+            "s3://bucket/tenant-42/a.csv"
+        );
+        assert_eq!(resolve("/data", "")?, "/data");
+        assert_eq!(resolve("/data", "/etc/passwd")?, "/data/etc/passwd");
+        assert_eq!(resolve("/", "a/../b.csv")?, "/b.csv");
+        assert_eq!(resolve("", "/a/b.csv")?, "a/b.csv");
+
+        for path in [
+            "../tenant-43/a.csv",
+            "a/../../b.csv",
+            "s3://bucket/tenant-420/a.csv",
+        ] {
+            let error = resolve(root, path).unwrap_err();
+            assert!(matches!(error, InnoFileError::PathOutsideRoot { .. }));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_relative() {
+        let root = "s3://bucket/tenant-42/";
+        assert_eq!(relative(root, "tenant-42/a/b.csv"), "a/b.csv");
+        assert_eq!(relative(root, "s3://bucket/tenant-42/a.csv"), "a.csv");
+        assert_eq!(relative("s3://bucket", "a.csv"), "a.csv");
+        assert_eq!(relative("/data/", "/data/a/b.csv"), "a/b.csv");
+        assert_eq!(relative("/", "/a.csv"), "a.csv");
+        assert_eq!(relative("", "a/b.csv"), "a/b.csv");
+    }
+}
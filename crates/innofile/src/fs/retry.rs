@@ -0,0 +1,180 @@
+#[cfg(feature = "tokio")]
+use std::future::Future;
+use std::time::Duration;
+
+use crate::{
+    error::{InnoFileError, InnoFileResult},
+    utils::random_fraction,
+};
+
+/// Policy for retrying file system operations that fail with transient errors.
+///
+/// Failed attempts are retried after an exponentially growing backoff, with random jitter so that
+/// concurrent readers don't retry in lockstep, until `max_attempts` attempts have been made.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    is_retryable: fn(&InnoFileError) -> bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+            is_retryable: InnoFileError::is_retryable,
+        }
+    }
+}
+
+impl RetryPolicy {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// A policy that never retries.
+    pub fn none() -> Self {
+        Self::new().with_max_attempts(1)
+    }
+
+    /// Sets the total number of attempts, including the first one.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the classification of errors worth retrying, [`InnoFileError::is_retryable`] by
+    /// default.
+    pub fn with_retryable(mut self, is_retryable: fn(&InnoFileError) -> bool) -> Self {
+        self.is_retryable = is_retryable;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Whether to retry after `error` failed the given attempt, counted from 1.
+    pub fn should_retry(&self, error: &InnoFileError, attempt: u32) -> bool {
+        attempt < self.max_attempts && (self.is_retryable)(error)
+    }
+
+    /// The delay before the attempt following the given one, counted from 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let backoff = self
+            .initial_backoff
+            .mul_f64(self.multiplier.powi(exponent).min(u32::MAX as f64))
+            .min(self.max_backoff);
+        if self.jitter {
+            // half of the backoff is kept, so retries still slow down as attempts fail
+            backoff / 2 + (backoff / 2).mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+
+    /// Runs `operation` until it succeeds, fails with an error not worth retrying, or runs out of
+    /// attempts, sleeping the current thread between attempts.
+    pub fn retry<T>(&self, mut operation: impl FnMut() -> InnoFileResult<T>) -> InnoFileResult<T> {
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Err(error) if self.should_retry(&error, attempt) => {
+                    std::thread::sleep(self.backoff(attempt));
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Async version of [`RetryPolicy::retry`], sleeping on the tokio timer between attempts.
+    #[cfg(feature = "tokio")]
+    pub async fn retry_async<T, F>(&self, mut operation: impl FnMut() -> F) -> InnoFileResult<T>
+    where
+        F: Future<Output = InnoFileResult<T>>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation().await {
+                Err(error) if self.should_retry(&error, attempt) => {
+                    tokio::time::sleep(self.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::ErrorKind;
+
+    use super::*;
+
+    #[test]
+    fn test_retry_policy() {
+        let policy = RetryPolicy::new()
+            .with_max_attempts(4)
+            .with_initial_backoff(Duration::from_millis(1))
+            .with_max_backoff(Duration::from_millis(3))
+            .with_jitter(false);
+        let backoffs = (1..=4).map(|a| policy.backoff(a)).collect::<Vec<_>>();
+        assert_eq!(backoffs, [1, 2, 3, 3].map(Duration::from_millis));
+
+        let mut attempts = 0;
+        let result = policy.retry(|| {
+            attempts += 1;
+            match attempts {
+                1 | 2 => Err(std::io::Error::from(ErrorKind::ConnectionReset))?,
+                _ => Ok(attempts),
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        let mut attempts = 0;
+        let result = policy.retry(|| -> InnoFileResult<()> {
+            attempts += 1;
+            Err(std::io::Error::from(ErrorKind::NotFound))?
+        });
+        assert!(result.unwrap_err().is_not_found());
+        assert_eq!(attempts, 1);
+
+        let mut attempts = 0;
+        let result = policy.retry(|| -> InnoFileResult<()> {
+            attempts += 1;
+            Err(std::io::Error::from(ErrorKind::TimedOut))?
+        });
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(attempts, 4);
+    }
+}
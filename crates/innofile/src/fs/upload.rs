@@ -0,0 +1,234 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    ops::Range,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    buffered::BufWriter, path::Path, GetOptions, GetResult, ListResult, MultipartUpload,
+    ObjectMeta, ObjectStore, PutMultipartOpts, PutOptions, PutPayload, PutResult, Result,
+    UploadPart,
+};
+
+/// Callback notified with the total number of bytes of an upload the store has confirmed so far.
+pub type ProgressCallback = Arc<dyn Fn(u64) + Send + Sync>;
+
+const DEFAULT_PART_SIZE: usize = 10 * 1024 * 1024;
+const DEFAULT_MAX_CONCURRENCY: usize = 8;
+
+/// Options of the multipart uploads made by object store writers.
+///
+/// Written bytes are buffered up to the part size. Files no larger than one part are uploaded with
+/// a single put on close, larger ones as a multipart upload of parts of that size.
+#[derive(Clone)]
+pub struct UploadOptions {
+    part_size: usize,
+    max_concurrency: usize,
+    progress: Option<ProgressCallback>,
+}
+
+impl Default for UploadOptions {
+    fn default() -> Self {
+        Self {
+            part_size: DEFAULT_PART_SIZE,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            progress: None,
+        }
+    }
+}
+
+impl Debug for UploadOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UploadOptions")
+            .field("part_size", &self.part_size)
+            .field("max_concurrency", &self.max_concurrency)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+impl UploadOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the size of each part, 10 MiB by default. Stores may impose a minimum, 5 MiB for S3.
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        self.part_size = part_size.max(1);
+        self
+    }
+
+    /// Sets the maximum number of parts uploaded concurrently, 8 by default.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Reports the number of bytes of each upload the store has confirmed to `progress`, as each
+    /// part of a multipart upload completes, and once the upload is complete.
+    pub fn with_upload_progress(mut self, progress: impl Fn(u64) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+
+    pub fn part_size(&self) -> usize {
+        self.part_size
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    pub(crate) fn buf_writer(&self, store: Arc<dyn ObjectStore>, path: Path) -> BufWriter {
+        let store = match &self.progress {
+            Some(progress) => Arc::new(ProgressStore::new(store, Arc::clone(progress))),
+            None => store,
+        };
+        BufWriter::with_capacity(store, path, self.part_size)
+            .with_max_concurrency(self.max_concurrency)
+    }
+}
+
+/// Total number of bytes of an upload confirmed so far, notified to its progress callback.
+struct Progress {
+    callback: ProgressCallback,
+    uploaded: Mutex<u64>,
+}
+
+impl Progress {
+    /// Adds `len` confirmed bytes, notifying the callback under the lock so that it observes
+    /// increasing totals even when parts complete concurrently.
+    fn add(&self, len: u64) {
+        let mut uploaded = self.uploaded.lock().unwrap_or_else(|e| e.into_inner());
+        *uploaded += len;
+        (self.callback)(*uploaded);
+    }
+}
+
+impl Debug for Progress {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Progress")
+            .field("uploaded", &self.uploaded)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Store of a single upload, reporting its progress as the inner store confirms the single put or
+/// the parts of the multipart upload of the buffered writer.
+#[derive(Debug)]
+struct ProgressStore {
+    inner: Arc<dyn ObjectStore>,
+    progress: Arc<Progress>,
+}
+
+impl ProgressStore {
+    fn new(inner: Arc<dyn ObjectStore>, callback: ProgressCallback) -> Self {
+        let progress = Progress {
+            callback,
+            uploaded: Mutex::new(0),
+        };
+        Self {
+            inner,
+            progress: Arc::new(progress),
+        }
+    }
+}
+
+impl Display for ProgressStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ProgressStore({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ProgressStore {
+    async fn put_opts(
+        &self,
+        location: &Path,
+        payload: PutPayload,
+        opts: PutOptions,
+    ) -> Result<PutResult> {
+        let len = payload.content_length() as u64;
+        let result = self.inner.put_opts(location, payload, opts).await?;
+        self.progress.add(len);
+        Ok(result)
+    }
+
+    async fn put_multipart_opts(
+        &self,
+        location: &Path,
+        opts: PutMultipartOpts,
+    ) -> Result<Box<dyn MultipartUpload>> {
+        let inner = self.inner.put_multipart_opts(location, opts).await?;
+        Ok(Box::new(ProgressUpload {
+            inner,
+            progress: Arc::clone(&self.progress),
+        }))
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        self.inner.get_opts(location, options).await
+    }
+
+    async fn get_ranges(&self, location: &Path, ranges: &[Range<usize>]) -> Result<Vec<Bytes>> {
+        self.inner.get_ranges(location, ranges).await
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        self.inner.head(location).await
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.inner.delete(location).await
+    }
+
+    fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, Result<ObjectMeta>> {
+        self.inner.list(prefix)
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.inner.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.inner.copy_if_not_exists(from, to).await
+    }
+}
+
+/// Multipart upload reporting the bytes of each part once the store confirmed it.
+#[derive(Debug)]
+struct ProgressUpload {
+    inner: Box<dyn MultipartUpload>,
+    progress: Arc<Progress>,
+}
+
+#[async_trait]
+impl MultipartUpload for ProgressUpload {
+    fn put_part(&mut self, data: PutPayload) -> UploadPart {
+        let len = data.content_length() as u64;
+        let part = self.inner.put_part(data);
+        let progress = Arc::clone(&self.progress);
+        Box::pin(async move {
+            part.await?;
+            progress.add(len);
+            Ok(())
+        })
+    }
+
+    async fn complete(&mut self) -> Result<PutResult> {
+        let result = self.inner.complete().await?;
+        self.progress.add(0);
+        Ok(result)
+    }
+
+    async fn abort(&mut self) -> Result<()> {
+        self.inner.abort().await
+    }
+}
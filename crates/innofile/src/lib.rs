@@ -1,9 +1,13 @@
 #![allow(clippy::len_without_is_empty)]
 
 pub mod arrow;
+#[cfg(all(feature = "sync", feature = "tokio"))]
+pub mod bridge;
 pub mod error;
 pub mod fs;
 pub mod io;
+#[cfg(any(feature = "tokio", feature = "object_store"))]
+pub mod runtime;
 #[cfg(feature = "sync")]
 pub mod sync;
 #[cfg(feature = "tokio")]
@@ -11,14 +15,16 @@ pub mod tokio;
 pub mod utils;
 
 macro_rules! with_field {
-    ($method:ident, $field:ident, String) => {
+    ($(#[$meta:meta])* $method:ident, $field:ident, String) => {
+        $(#[$meta])*
         pub fn $method(mut self, $field: Option<impl ToString>) -> Self {
             self.$field = $field.map(|$field| $field.to_string());
             self
         }
     };
 
-    ($method:ident, $field:ident, $type:ident) => {
+    ($(#[$meta:meta])* $method:ident, $field:ident, $type:ty) => {
+        $(#[$meta])*
         pub fn $method(mut self, $field: Option<$type>) -> Self {
             self.$field = $field;
             self
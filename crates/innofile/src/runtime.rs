@@ -0,0 +1,87 @@
+use std::{future::Future, sync::OnceLock};
+
+use tokio::runtime::{Builder, Handle, Runtime, RuntimeFlavor};
+
+use crate::error::{InnoFileError, InnoFileResult};
+
+static SHARED_RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+/// Returns the [`Handle`] of a lazily created multi-thread runtime shared across the crate.
+pub fn shared_handle() -> Handle {
+    SHARED_RUNTIME
+        .get_or_init(|| {
+            Builder::new_multi_thread()
+                .thread_name("innofile-runtime")
+                .enable_all()
+                .build()
+                .expect("Failed to build shared tokio runtime")
+        })
+        .handle()
+        .clone()
+}
+
+/// Runs `future` to completion on `handle`, also when called from inside another runtime.
+///
+/// Inside a multi-thread runtime the current worker is moved out with
+/// [`tokio::task::block_in_place`], while inside a current-thread runtime the future is driven
+/// from a scoped thread, so the call never panics the way a bare `block_on` does.
+///
+/// Driving a current-thread runtime from a scoped thread while its only thread waits on it would
+/// hang, and handles don't tell which runtime they belong to, so this fails with
+/// [`InnoFileError::BlockingInRuntime`] whenever both the current runtime and `handle` are
+/// current-thread runtimes.
+pub fn block_on<F>(handle: &Handle, future: F) -> InnoFileResult<F::Output>
+where
+    F: Future + Send,
+    F::Output: Send,
+{
+    match Handle::try_current() {
+        Err(_) => Ok(handle.block_on(future)),
+
+        Ok(current) if current.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            Ok(tokio::task::block_in_place(|| handle.block_on(future)))
+        }
+
+        Ok(_) if handle.runtime_flavor() == RuntimeFlavor::CurrentThread => {
+            Err(InnoFileError::BlockingInRuntime)
+        }
+
+        Ok(_) => Ok(std::thread::scope(|scope| {
+            scope
+                .spawn(|| handle.block_on(future))
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+        })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_block_on() -> InnoFileResult<()> {
+        let handle = shared_handle();
+        assert_eq!(block_on(&handle, async { 1 })?, 1);
+
+        let current_thread = Builder::new_current_thread().build()?;
+        assert_eq!(
+            current_thread.block_on(async { block_on(&handle, async { 2 }) })?,
+            2
+        );
+
+        let multi_thread = Builder::new_multi_thread().build()?;
+        assert_eq!(
+            multi_thread.block_on(async { block_on(&handle, async { 3 }) })?,
+            3
+        );
+
+        let current_handle = current_thread.handle().clone();
+        let error = current_thread
+            .block_on(async { block_on(&current_handle, async { 4 }) })
+            .unwrap_err();
+        assert!(matches!(error, InnoFileError::BlockingInRuntime));
+
+        Ok(())
+    }
+}
@@ -1,18 +1,76 @@
+use std::sync::Arc;
+
 use arrow::{array::RecordBatch, datatypes::SchemaRef};
 
 use crate::{error::InnoFileResult, sync::io::Closeable};
 
 #[cfg(feature = "csv")]
 pub mod csv;
+#[cfg(feature = "ipc")]
+pub mod ipc;
 #[cfg(feature = "json")]
 pub mod json;
 #[cfg(feature = "orc")]
 pub mod orc;
 #[cfg(feature = "parquet")]
 pub mod parquet;
+#[cfg(any(feature = "orc", feature = "parquet"))]
+pub mod range;
 
-pub trait ArrowReader: Iterator<Item = InnoFileResult<RecordBatch>> {
+pub trait ArrowReader: Iterator<Item = InnoFileResult<RecordBatch>> + Send {
     fn schema(&self) -> SchemaRef;
+
+    /// Number of rows from the file metadata, if known without reading the file.
+    fn num_rows(&self) -> Option<usize> {
+        None
+    }
+}
+
+impl<R: ArrowReader + ?Sized> ArrowReader for Box<R> {
+    fn schema(&self) -> SchemaRef {
+        (**self).schema()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        (**self).num_rows()
+    }
+}
+
+/// Reads the columns at `indices` of another reader, in the order given.
+pub(crate) struct ProjectedReader<R> {
+    inner: R,
+    indices: Vec<usize>,
+    schema: SchemaRef,
+}
+
+impl<R: ArrowReader> ProjectedReader<R> {
+    pub(crate) fn new(inner: R, indices: Vec<usize>) -> InnoFileResult<Self> {
+        let schema = Arc::new(inner.schema().project(&indices)?);
+        Ok(Self {
+            inner,
+            indices,
+            schema,
+        })
+    }
+}
+
+impl<R: ArrowReader> Iterator for ProjectedReader<R> {
+    type Item = InnoFileResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let batch = self.inner.next()?;
+        Some(batch.and_then(|batch| Ok(batch.project(&self.indices)?)))
+    }
+}
+
+impl<R: ArrowReader> ArrowReader for ProjectedReader<R> {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        self.inner.num_rows()
+    }
 }
 
 pub trait ArrowWriter: Closeable {
@@ -26,6 +84,12 @@ pub trait ArrowWriter: Closeable {
     fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()>;
 }
 
+impl<W: ArrowWriter + ?Sized> ArrowWriter for Box<W> {
+    fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
+        (**self).write(batch)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -81,6 +145,15 @@ mod tests {
             assert_eq!(record_batch, batch);
         }
 
+        let file: FileRef = Arc::from(file_system.open(path)?);
+        let columns = vec!["name".to_string(), "id".to_string()];
+        let mut arrow_reader = ArrowReaderBuilder::new()
+            .with_schema(read_with_schema.then(|| Arc::clone(&schema)))
+            .with_columns(Some(columns))
+            .build_sync(file)?;
+        let record_batch = arrow_reader.next().unwrap()?;
+        assert_eq!(record_batch, batch.project(&[1, 0])?);
+
         Ok(())
     }
 }
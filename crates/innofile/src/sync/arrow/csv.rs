@@ -9,7 +9,10 @@ use arrow_csv::{
     ReaderBuilder, Writer, WriterBuilder,
 };
 
+use regex::Regex;
+
 use crate::{
+    arrow::{CsvOptions, DEFAULT_INFER_RECORDS},
     error::{InnoFileError, InnoFileResult},
     sync::{
         fs::{FileRead, FileRef, FileWrite},
@@ -27,21 +30,47 @@ impl ArrowCsvReader {
         file_format: impl AsRef<str>,
         schema: Option<SchemaRef>,
     ) -> InnoFileResult<Self> {
-        let delimiter = delimiter_from_file_format(file_format)?;
+        Self::with_infer_records(file, file_format, schema, DEFAULT_INFER_RECORDS)
+    }
+
+    /// Creates a reader inferring the schema from `infer_records` records, when no schema is
+    /// given.
+    pub fn with_infer_records(
+        file: FileRef,
+        file_format: impl AsRef<str>,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
+    ) -> InnoFileResult<Self> {
+        Self::with_options(
+            file,
+            file_format,
+            schema,
+            infer_records,
+            &CsvOptions::default(),
+        )
+    }
+
+    /// Creates a reader of files in the CSV dialect of `options`.
+    pub fn with_options(
+        file: FileRef,
+        file_format: impl AsRef<str>,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
+        options: &CsvOptions,
+    ) -> InnoFileResult<Self> {
+        let format = csv_format(file_format, options)?;
         let schema = match schema {
             Some(schema) => schema,
             None => {
-                let (schema, _) = Format::default()
-                    .with_header(true)
-                    .with_delimiter(delimiter)
-                    .infer_schema(file.reader()?, Some(100))?;
+                let (schema, _) = format
+                    .clone()
+                    .infer_schema(file.reader()?, Some(infer_records))?;
                 file.reader()?.rewind()?;
                 Arc::new(schema)
             }
         };
         let inner_reader = ReaderBuilder::new(schema)
-            .with_header(true)
-            .with_delimiter(delimiter)
+            .with_format(format)
             .build_buffered(file.reader()?)?;
         Ok(Self(inner_reader))
     }
@@ -65,18 +94,40 @@ pub struct ArrowCsvWriter(Writer<Box<dyn FileWrite>>);
 
 impl ArrowCsvWriter {
     pub fn new(file: FileRef, file_format: impl AsRef<str>) -> InnoFileResult<Self> {
-        let delimiter = delimiter_from_file_format(file_format)?;
-        Ok(Self(
-            WriterBuilder::new()
-                .with_delimiter(delimiter)
-                .build(file.writer()?),
-        ))
+        Self::with_options(file, file_format, &CsvOptions::default())
+    }
+
+    /// Creates a writer of files in the CSV dialect of `options`.
+    pub fn with_options(
+        file: FileRef,
+        file_format: impl AsRef<str>,
+        options: &CsvOptions,
+    ) -> InnoFileResult<Self> {
+        let builder = csv_writer_builder(file_format, options)?;
+        Ok(Self(builder.build(file.writer()?)))
+    }
+
+    /// Creates a writer appending to `file`, which only writes a header if the file is empty.
+    pub fn new_append(file: FileRef, file_format: impl AsRef<str>) -> InnoFileResult<Self> {
+        Self::new_append_with_options(file, file_format, &CsvOptions::default())
+    }
+
+    /// Creates a writer appending to `file` in the CSV dialect of `options`, which only writes a
+    /// header if the file is empty and `options` have one.
+    pub fn new_append_with_options(
+        file: FileRef,
+        file_format: impl AsRef<str>,
+        options: &CsvOptions,
+    ) -> InnoFileResult<Self> {
+        let header = options.header && file.metadata()?.len() == 0;
+        let builder = csv_writer_builder(file_format, options)?.with_header(header);
+        Ok(Self(builder.build(file.writer()?)))
     }
 }
 
 impl Closeable for ArrowCsvWriter {
     fn close(self) -> InnoFileResult<()> {
-        Ok(())
+        self.0.into_inner().close()
     }
 }
 
@@ -86,7 +137,51 @@ impl ArrowWriter for ArrowCsvWriter {
     }
 }
 
-fn delimiter_from_file_format(file_format: impl AsRef<str>) -> InnoFileResult<u8> {
+fn csv_format(file_format: impl AsRef<str>, options: &CsvOptions) -> InnoFileResult<Format> {
+    let mut format = Format::default()
+        .with_header(options.header)
+        .with_delimiter(delimiter(file_format, options)?);
+    if let Some(quote) = options.quote {
+        format = format.with_quote(quote);
+    }
+    if let Some(escape) = options.escape {
+        format = format.with_escape(escape);
+    }
+    if let Some(comment) = options.comment {
+        format = format.with_comment(comment);
+    }
+    if let Some(null_value) = &options.null_value {
+        let null_regex =
+            Regex::new(&format!("^{}$", regex::escape(null_value))).map_err(anyhow::Error::from)?;
+        format = format.with_null_regex(null_regex);
+    }
+    Ok(format)
+}
+
+fn csv_writer_builder(
+    file_format: impl AsRef<str>,
+    options: &CsvOptions,
+) -> InnoFileResult<WriterBuilder> {
+    let mut builder = WriterBuilder::new()
+        .with_header(options.header)
+        .with_delimiter(delimiter(file_format, options)?);
+    if let Some(quote) = options.quote {
+        builder = builder.with_quote(quote);
+    }
+    if let Some(escape) = options.escape {
+        builder = builder.with_escape(escape);
+    }
+    if let Some(null_value) = &options.null_value {
+        builder = builder.with_null(null_value.clone());
+    }
+    Ok(builder)
+}
+
+/// Delimiter of `options` if set, or else the delimiter of `file_format`.
+fn delimiter(file_format: impl AsRef<str>, options: &CsvOptions) -> InnoFileResult<u8> {
+    if let Some(delimiter) = options.delimiter {
+        return Ok(delimiter);
+    }
     match file_format.as_ref().to_lowercase().as_str() {
         "csv" => Ok(b','),
         "dsv" => Ok(b':'),
@@ -100,6 +195,14 @@ fn delimiter_from_file_format(file_format: impl AsRef<str>) -> InnoFileResult<u8
 
 #[cfg(test)]
 mod tests {
+    use arrow::{
+        array::{Int64Array, StringArray},
+        datatypes::{DataType, Field, Schema},
+    };
+    use tempfile::tempdir;
+
+    use crate::sync::fs::{local::LocalFS, FileSystem as _};
+
     use super::{super::tests::write_then_read, *};
 
     #[test]
@@ -109,4 +212,75 @@ mod tests {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_close_file_writer() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("close_file_writer.csv");
+        let path = path.to_str().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![1, 2]))])?;
+
+        // atomically created files are only published once their writer is closed
+        let mut writer = ArrowCsvWriter::new(Arc::from(LocalFS.create_atomic(path)?), "csv")?;
+        writer.write(&batch)?;
+        assert!(!LocalFS.exists(path)?);
+        writer.close()?;
+        assert_eq!(std::fs::read_to_string(path)?, "a\n1\n2\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_append() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("append.csv");
+        let path = path.to_str().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(schema, vec![Arc::new(Int64Array::from(vec![1, 2]))])?;
+
+        for _ in 0..2 {
+            let mut writer = ArrowCsvWriter::new_append(Arc::from(LocalFS.append(path)?), "csv")?;
+            writer.write(&batch)?;
+            writer.close()?;
+        }
+        assert_eq!(std::fs::read_to_string(path)?, "a\n1\n2\n1\n2\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_csv_options() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("options.csv");
+        let path = path.to_str().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from(vec![Some(1), None])),
+                Arc::new(StringArray::from(vec![Some("x;y"), Some("z")])),
+            ],
+        )?;
+
+        let options = CsvOptions::new()
+            .with_header(false)
+            .with_delimiter(b';')
+            .with_quote(b'\'')
+            .with_null_value("NA");
+        let mut writer =
+            ArrowCsvWriter::with_options(Arc::from(LocalFS.create(path)?), "csv", &options)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        assert_eq!(std::fs::read_to_string(path)?, "1;'x;y'\nNA;z\n");
+
+        let file = Arc::from(LocalFS.open(path)?);
+        let reader = ArrowCsvReader::with_options(file, "csv", Some(schema), 100, &options)?;
+        assert_eq!(reader.collect::<InnoFileResult<Vec<_>>>()?, [batch]);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,104 @@
+use arrow::{
+    array::RecordBatch,
+    datatypes::SchemaRef,
+    ipc::{reader::StreamReader, writer::StreamWriter},
+};
+
+use crate::{
+    error::InnoFileResult,
+    sync::{
+        fs::{FileRead, FileRef, FileWrite},
+        io::Closeable,
+    },
+};
+
+use super::{ArrowReader, ArrowWriter};
+
+/// Reader of the Arrow IPC streaming format, which is read front to back.
+pub struct ArrowIpcReader(StreamReader<Box<dyn FileRead>>);
+
+impl ArrowIpcReader {
+    pub fn new(file: FileRef) -> InnoFileResult<Self> {
+        Ok(Self(StreamReader::try_new(file.reader()?, None)?))
+    }
+}
+
+impl Iterator for ArrowIpcReader {
+    type Item = InnoFileResult<RecordBatch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(self.0.next()?.map_err(|e| e.into()))
+    }
+}
+
+impl ArrowReader for ArrowIpcReader {
+    fn schema(&self) -> SchemaRef {
+        self.0.schema()
+    }
+}
+
+pub struct ArrowIpcWriter(StreamWriter<Box<dyn FileWrite>>);
+
+impl ArrowIpcWriter {
+    pub fn new(file: FileRef, schema: SchemaRef) -> InnoFileResult<Self> {
+        Ok(Self(StreamWriter::try_new(file.writer()?, &schema)?))
+    }
+}
+
+impl Closeable for ArrowIpcWriter {
+    fn close(mut self) -> InnoFileResult<()> {
+        self.0.finish()?;
+        self.0.into_inner()?.close()
+    }
+}
+
+impl ArrowWriter for ArrowIpcWriter {
+    fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
+        Ok(self.0.write(batch)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Cursor, sync::Arc};
+
+    use arrow::{
+        array::Int64Array,
+        datatypes::{DataType, Field, Schema},
+    };
+
+    use crate::sync::fs::stdio::{SharedBuffer, StdinFile, StdoutFile};
+
+    use super::{super::tests::write_then_read, *};
+
+    #[test]
+    fn test_arrow_read_write() -> InnoFileResult<()> {
+        write_then_read("arrows", false)
+    }
+
+    #[test]
+    fn test_stream_through_stdio() -> InnoFileResult<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int64Array::from(vec![1, 2]))],
+        )?;
+
+        let buffer = SharedBuffer::default();
+        let file = Arc::new(StdoutFile::from_writer(buffer.clone()));
+        let mut writer = ArrowIpcWriter::new(file, schema)?;
+        writer.write(&batch)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let content = buffer.0.lock().unwrap().clone();
+        let file = Arc::new(StdinFile::from_reader(Cursor::new(content)));
+        let reader = ArrowIpcReader::new(file)?;
+        assert_eq!(
+            reader.collect::<InnoFileResult<Vec<_>>>()?,
+            [batch.clone(), batch]
+        );
+
+        Ok(())
+    }
+}
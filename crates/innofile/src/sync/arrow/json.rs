@@ -10,6 +10,7 @@ use arrow_json::{
 };
 
 use crate::{
+    arrow::DEFAULT_INFER_RECORDS,
     error::InnoFileResult,
     sync::{
         fs::{FileRead, FileRef, FileWrite},
@@ -23,10 +24,21 @@ pub struct ArrowJsonReader(Reader<Box<dyn FileRead>>);
 
 impl ArrowJsonReader {
     pub fn new(file: FileRef, schema: Option<SchemaRef>) -> InnoFileResult<Self> {
+        Self::with_infer_records(file, schema, DEFAULT_INFER_RECORDS)
+    }
+
+    /// Creates a reader inferring the schema from `infer_records` records, when no schema is
+    /// given.
+    pub fn with_infer_records(
+        file: FileRef,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
+    ) -> InnoFileResult<Self> {
         let schema = match schema {
             Some(schema) => schema,
             None => {
-                let (schema, _) = infer_json_schema_from_seekable(file.reader()?, Some(100))?;
+                let (schema, _) =
+                    infer_json_schema_from_seekable(file.reader()?, Some(infer_records))?;
                 Arc::new(schema)
             }
         };
@@ -74,11 +86,17 @@ impl ArrowJsonWriter {
 
 impl Closeable for ArrowJsonWriter {
     fn close(self) -> InnoFileResult<()> {
-        match self {
-            Self::JsonArray(mut writer) => writer.finish()?,
-            Self::LineDelimited(mut writer) => writer.finish()?,
-        }
-        Ok(())
+        let writer = match self {
+            Self::JsonArray(mut writer) => {
+                writer.finish()?;
+                writer.into_inner()
+            }
+            Self::LineDelimited(mut writer) => {
+                writer.finish()?;
+                writer.into_inner()
+            }
+        };
+        writer.close()
     }
 }
 
@@ -1,46 +1,106 @@
-use std::io::SeekFrom;
+use std::{
+    io::{Read, Write},
+    sync::{Arc, Mutex, PoisonError},
+};
 
 use arrow::{
     array::{RecordBatch, RecordBatchReader as _},
     datatypes::SchemaRef,
+    error::ArrowError,
 };
+use bytes::Bytes;
 use orc_rust::{
-    reader::ChunkReader, ArrowReader as InnerReader, ArrowReaderBuilder as InnerReaderBuilder,
+    projection::ProjectionMask,
+    reader::{metadata::FileMetadata, ChunkReader},
+    ArrowReader as InnerReader, ArrowReaderBuilder as InnerReaderBuilder,
     ArrowWriter as InnerWriter, ArrowWriterBuilder as InnerWriterBuilder,
 };
 
 use crate::{
     error::InnoFileResult,
     sync::{
-        fs::{FileRead, FileRef, FileWrite},
+        fs::{FileRef, FileWrite},
         io::Closeable,
     },
 };
 
-use super::{ArrowReader, ArrowWriter};
+use super::{range::RangeReader, ArrowReader, ArrowWriter};
 
-pub struct ArrowOrcReader(InnerReader<FileReader>);
+pub struct ArrowOrcReader {
+    inner: InnerReader<FileReader>,
+    metadata: FileMetadata,
+}
 
 impl ArrowOrcReader {
     pub fn new(file: FileRef) -> InnoFileResult<Self> {
-        let inner_reader = InnerReaderBuilder::try_new(FileReader(file))?.build();
-        Ok(Self(inner_reader))
+        Self::build(file, None)
+    }
+
+    /// Reads only the columns at `projection`, in the order of the file.
+    pub fn with_projection(file: FileRef, projection: &[usize]) -> InnoFileResult<Self> {
+        Self::build(file, Some(projection))
+    }
+
+    fn build(file: FileRef, projection: Option<&[usize]>) -> InnoFileResult<Self> {
+        let range_reader = Arc::new(RangeReader::new(file));
+        let mut builder = InnerReaderBuilder::try_new(FileReader(Arc::clone(&range_reader)))?;
+        match projection {
+            Some(projection) => {
+                let children = builder.file_metadata().root_data_type().children();
+                let names = projection
+                    .iter()
+                    .map(|i| match children.get(*i) {
+                        Some(column) => Ok(column.name().to_string()),
+                        None => Err(ArrowError::SchemaError(format!(
+                            "Column index {i} out of range"
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let root_data_type = builder.file_metadata().root_data_type();
+                let mask = ProjectionMask::named_roots(root_data_type, &names);
+                builder = builder.with_projection(mask);
+            }
+            // prefetch the streams of each stripe together, only when all of them are read since
+            // the ranges of the streams of each column are only known from stripe footers
+            None => range_reader.set_prefetch_groups(
+                builder
+                    .file_metadata()
+                    .stripe_metadatas()
+                    .iter()
+                    .map(|stripe| {
+                        let end = stripe.footer_offset() + stripe.footer_length();
+                        std::iter::once(stripe.offset()..end).collect()
+                    }),
+            ),
+        }
+        Ok(Self {
+            metadata: builder.file_metadata().clone(),
+            inner: builder.build(),
+        })
+    }
+
+    pub fn metadata(&self) -> &FileMetadata {
+        &self.metadata
     }
 }
 
-pub struct FileReader(FileRef);
+pub struct FileReader(Arc<RangeReader>);
 
 impl ChunkReader for FileReader {
-    type T = Box<dyn FileRead>;
+    type T = Box<dyn Read + Send>;
 
     fn len(&self) -> u64 {
-        self.0.metadata().map(|m| m.len()).unwrap_or_default()
+        self.0.len().unwrap_or_default()
     }
 
     fn get_read(&self, offset_from_start: u64) -> std::io::Result<Self::T> {
-        let mut reader = self.0.reader()?;
-        reader.seek(SeekFrom::Start(offset_from_start))?;
-        Ok(reader)
+        Ok(self.0.get_read(offset_from_start)?)
+    }
+
+    fn get_bytes(&self, offset_from_start: u64, length: u64) -> std::io::Result<Bytes> {
+        Ok(self
+            .0
+            .get_bytes(offset_from_start..offset_from_start + length)?)
     }
 }
 
@@ -48,35 +108,71 @@ impl Iterator for ArrowOrcReader {
     type Item = InnoFileResult<RecordBatch>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(self.0.next()?.map_err(|e| e.into()))
+        Some(self.inner.next()?.map_err(|e| e.into()))
     }
 }
 
 impl ArrowReader for ArrowOrcReader {
     fn schema(&self) -> SchemaRef {
-        self.0.schema()
+        self.inner.schema()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        usize::try_from(self.metadata.number_of_rows()).ok()
     }
 }
 
-pub struct ArrowOrcWriter(InnerWriter<Box<dyn FileWrite>>);
+pub struct ArrowOrcWriter {
+    inner: InnerWriter<SharedWriter>,
+    file_writer: Arc<Mutex<Box<dyn FileWrite>>>,
+}
 
 impl ArrowOrcWriter {
     pub fn new(file: FileRef, schema: SchemaRef) -> InnoFileResult<Self> {
-        Ok(Self(
-            InnerWriterBuilder::new(file.writer()?, schema).try_build()?,
-        ))
+        let file_writer = Arc::new(Mutex::new(file.writer()?));
+        let shared_writer = SharedWriter(Arc::clone(&file_writer));
+        Ok(Self {
+            inner: InnerWriterBuilder::new(shared_writer, schema).try_build()?,
+            file_writer,
+        })
     }
 }
 
 impl Closeable for ArrowOrcWriter {
     fn close(self) -> InnoFileResult<()> {
-        Ok(self.0.close()?)
+        self.inner.close()?;
+        let file_writer = Arc::into_inner(self.file_writer)
+            .ok_or_else(|| std::io::Error::other("File writer still in use"))?;
+        file_writer
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+            .close()
     }
 }
 
 impl ArrowWriter for ArrowOrcWriter {
     fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
-        Ok(self.0.write(batch)?)
+        Ok(self.inner.write(batch)?)
+    }
+}
+
+/// Writer sharing the file writer with [`ArrowOrcWriter`], since the ORC writer doesn't give it
+/// back on close.
+struct SharedWriter(Arc<Mutex<Box<dyn FileWrite>>>);
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .flush()
     }
 }
 
@@ -1,4 +1,4 @@
-use std::io::{Read, SeekFrom};
+use std::{io::Read, sync::Arc};
 
 use arrow::{
     array::{RecordBatch, RecordBatchReader as _},
@@ -7,60 +7,116 @@ use arrow::{
 use bytes::Bytes;
 use parquet::{
     arrow::{
-        arrow_reader::{ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder},
-        ArrowWriter as InnerWriter,
+        arrow_reader::{
+            ArrowReaderMetadata, ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder,
+        },
+        ArrowWriter as InnerWriter, ProjectionMask,
     },
     errors::ParquetError,
-    file::reader::{ChunkReader, Length},
+    file::{
+        metadata::ParquetMetaData,
+        properties::WriterProperties,
+        reader::{ChunkReader, Length},
+    },
 };
 
 use crate::{
     error::InnoFileResult,
     sync::{
-        fs::{FileRead, FileRef, FileWrite},
+        fs::{FileRef, FileWrite},
         io::Closeable,
     },
 };
 
-use super::{ArrowReader, ArrowWriter};
+use super::{range::RangeReader, ArrowReader, ArrowWriter};
 
-pub struct ArrowParquetReader(ParquetRecordBatchReader);
+pub struct ArrowParquetReader {
+    inner: ParquetRecordBatchReader,
+    metadata: Arc<ParquetMetaData>,
+}
 
 impl ArrowParquetReader {
     pub fn new(file: FileRef) -> InnoFileResult<Self> {
-        let inner_reader = ParquetRecordBatchReaderBuilder::try_new(FileReader(file))?.build()?;
-        Ok(Self(inner_reader))
+        Self::build(file, None)
+    }
+
+    /// Reads only the columns at `projection`, in the order of the file.
+    pub fn with_projection(file: FileRef, projection: &[usize]) -> InnoFileResult<Self> {
+        Self::build(file, Some(projection))
+    }
+
+    fn build(file: FileRef, projection: Option<&[usize]>) -> InnoFileResult<Self> {
+        let file_reader = FileReader::new(file);
+        let metadata = ArrowReaderMetadata::load(&file_reader, Default::default())?;
+        let mask = match projection {
+            Some(projection) => ProjectionMask::roots(
+                metadata.metadata().file_metadata().schema_descr(),
+                projection.iter().copied(),
+            ),
+            None => ProjectionMask::all(),
+        };
+        // prefetch the projected column chunks of each row group together
+        file_reader
+            .0
+            .set_prefetch_groups(metadata.metadata().row_groups().iter().map(|row_group| {
+                row_group
+                    .columns()
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| mask.leaf_included(*i))
+                    .map(|(_, column)| {
+                        let (start, length) = column.byte_range();
+                        start..start + length
+                    })
+                    .collect()
+            }));
+        let parquet_metadata = Arc::clone(metadata.metadata());
+        let inner_reader =
+            ParquetRecordBatchReaderBuilder::new_with_metadata(file_reader, metadata)
+                .with_projection(mask)
+                .build()?;
+        Ok(Self {
+            inner: inner_reader,
+            metadata: parquet_metadata,
+        })
+    }
+
+    pub fn metadata(&self) -> &Arc<ParquetMetaData> {
+        &self.metadata
     }
 }
 
-pub struct FileReader(FileRef);
+pub struct FileReader(Arc<RangeReader>);
+
+impl FileReader {
+    pub fn new(file: FileRef) -> Self {
+        Self(Arc::new(RangeReader::new(file)))
+    }
+}
 
 impl Length for FileReader {
     fn len(&self) -> u64 {
-        self.0.metadata().map(|m| m.len()).unwrap_or_default()
+        self.0.len().unwrap_or_default()
     }
 }
 
 impl ChunkReader for FileReader {
-    type T = Box<dyn FileRead>;
+    type T = Box<dyn Read + Send>;
 
     fn get_read(&self, start: u64) -> parquet::errors::Result<Self::T> {
-        let mut reader = self.0.reader()?;
-        reader.seek(SeekFrom::Start(start))?;
-        Ok(reader)
+        Ok(self.0.get_read(start)?)
     }
 
     fn get_bytes(&self, start: u64, length: usize) -> parquet::errors::Result<Bytes> {
-        let reader = self.get_read(start)?;
-        let mut buf = Vec::with_capacity(length);
-        let read = reader.take(length as _).read_to_end(&mut buf)?;
-        if read != length {
+        let bytes = self.0.get_bytes(start..start + length as u64)?;
+        if bytes.len() != length {
             Err(ParquetError::EOF(format!(
                 "Expected to read {} bytes, read only {}",
-                length, read
+                length,
+                bytes.len()
             )))?
         }
-        Ok(buf.into())
+        Ok(bytes)
     }
 }
 
@@ -68,13 +124,17 @@ impl Iterator for ArrowParquetReader {
     type Item = InnoFileResult<RecordBatch>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        Some(self.0.next()?.map_err(|e| e.into()))
+        Some(self.inner.next()?.map_err(|e| e.into()))
     }
 }
 
 impl ArrowReader for ArrowParquetReader {
     fn schema(&self) -> SchemaRef {
-        self.0.schema()
+        self.inner.schema()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        usize::try_from(self.metadata.file_metadata().num_rows()).ok()
     }
 }
 
@@ -82,15 +142,23 @@ pub struct ArrowParquetWriter(InnerWriter<Box<dyn FileWrite>>);
 
 impl ArrowParquetWriter {
     pub fn new(file: FileRef, schema: SchemaRef) -> InnoFileResult<Self> {
-        let inner_writer = InnerWriter::try_new(file.writer()?, schema, None)?;
+        Self::with_properties(file, schema, WriterProperties::default())
+    }
+
+    /// Creates a writer of row groups and column chunks configured by `properties`.
+    pub fn with_properties(
+        file: FileRef,
+        schema: SchemaRef,
+        properties: WriterProperties,
+    ) -> InnoFileResult<Self> {
+        let inner_writer = InnerWriter::try_new(file.writer()?, schema, Some(properties))?;
         Ok(Self(inner_writer))
     }
 }
 
 impl Closeable for ArrowParquetWriter {
     fn close(self) -> InnoFileResult<()> {
-        self.0.close()?;
-        Ok(())
+        self.0.into_inner()?.close()
     }
 }
 
@@ -102,10 +170,112 @@ impl ArrowWriter for ArrowParquetWriter {
 
 #[cfg(test)]
 mod tests {
+    use arrow::{
+        array::Int64Array,
+        datatypes::{DataType, Field, Schema},
+    };
+    use tempfile::tempdir;
+
+    use parquet::basic::Compression;
+
+    use crate::{
+        arrow::ArrowWriterBuilder,
+        error::InnoFileError,
+        sync::fs::{local::LocalFS, FileSystem as _},
+    };
+
     use super::{super::tests::write_then_read, *};
 
     #[test]
     fn test_arrow_read_write() -> InnoFileResult<()> {
         write_then_read("parquet", false)
     }
+
+    #[test]
+    fn test_read_row_groups() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("row_groups.parquet");
+        let path = path.to_str().unwrap();
+        let file_system = LocalFS;
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new("b", DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![
+                Arc::new(Int64Array::from_iter_values(0..10)),
+                Arc::new(Int64Array::from_iter_values(10..20)),
+            ],
+        )?;
+        let properties = WriterProperties::builder()
+            .set_max_row_group_size(3)
+            .build();
+        let file = Arc::from(file_system.create_new(path)?);
+        let mut writer = ArrowParquetWriter::with_properties(file, schema, properties)?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let arrow_reader = ArrowParquetReader::new(Arc::from(file_system.open(path)?))?;
+        assert_eq!(arrow_reader.num_rows(), Some(10));
+        assert_eq!(arrow_reader.metadata().num_row_groups(), 4);
+        let batches = arrow_reader.collect::<InnoFileResult<Vec<_>>>()?;
+        assert_eq!(
+            arrow::compute::concat_batches(&batch.schema(), &batches)?,
+            batch
+        );
+
+        // only the projected column chunks are read
+        let file = Arc::from(file_system.open(path)?);
+        let arrow_reader = ArrowParquetReader::with_projection(file, &[1])?;
+        let batches = arrow_reader.collect::<InnoFileResult<Vec<_>>>()?;
+        assert_eq!(
+            arrow::compute::concat_batches(&batches[0].schema(), &batches)?,
+            batch.project(&[1])?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_writer_options() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("options.parquet");
+        let path = path.to_str().unwrap();
+        let file_system = LocalFS;
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int64Array::from_iter_values(0..10))],
+        )?;
+        let mut writer = ArrowWriterBuilder::new(Arc::clone(&schema))
+            .with_compression(Some("zstd"))
+            .with_row_group_size(Some(4))
+            .build_sync(Arc::from(file_system.create(path)?))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        let arrow_reader = ArrowParquetReader::new(Arc::from(file_system.open(path)?))?;
+        let metadata = arrow_reader.metadata();
+        assert_eq!(metadata.num_row_groups(), 3);
+        assert_eq!(
+            metadata.row_group(0).column(0).compression(),
+            Compression::ZSTD(Default::default())
+        );
+
+        // compression is only supported by Parquet
+        let path = dir.path().join("options.csv");
+        let file = Arc::from(file_system.create(path.to_str().unwrap())?);
+        let result = ArrowWriterBuilder::new(schema)
+            .with_compression(Some("zstd"))
+            .build_sync(file);
+        let error = result.err().unwrap();
+        assert!(matches!(
+            error.root(),
+            InnoFileError::OptionNotSupported { .. }
+        ));
+
+        Ok(())
+    }
 }
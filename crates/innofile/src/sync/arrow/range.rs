@@ -0,0 +1,232 @@
+use std::{
+    io::{Read, SeekFrom},
+    ops::Range,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use bytes::{Buf as _, Bytes};
+
+use crate::{
+    error::InnoFileResult,
+    sync::fs::{FileRead, FileRef},
+    utils::range::{coalesce_ranges, Chunk, FOOTER_SIZE},
+};
+
+/// Reader of byte ranges of a [`FileRef`] for columnar formats.
+///
+/// It caches the file length and footer, merges nearby ranges into a single fetch, and prefetches
+/// groups of ranges read together, such as the column chunks of a Parquet row group or an ORC
+/// stripe, with one concurrent [`read_ranges`](crate::sync::fs::File::read_ranges) call. Only the
+/// most recently prefetched group is kept in memory.
+#[derive(Debug)]
+pub struct RangeReader {
+    file: FileRef,
+    len: OnceLock<u64>,
+    footer: OnceLock<Chunk>,
+    prefetch_groups: OnceLock<Vec<Vec<Range<u64>>>>,
+    cache: Mutex<Vec<Chunk>>,
+}
+
+impl RangeReader {
+    pub fn new(file: FileRef) -> Self {
+        Self {
+            file,
+            len: OnceLock::new(),
+            footer: OnceLock::new(),
+            prefetch_groups: OnceLock::new(),
+            cache: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn file(&self) -> &FileRef {
+        &self.file
+    }
+
+    pub fn len(&self) -> InnoFileResult<u64> {
+        if let Some(len) = self.len.get() {
+            return Ok(*len);
+        }
+        let len = self.file.metadata()?.len();
+        Ok(*self.len.get_or_init(|| len))
+    }
+
+    /// Sets the groups of ranges to fetch together once any of their ranges is read.
+    pub fn set_prefetch_groups(&self, groups: impl IntoIterator<Item = Vec<Range<u64>>>) {
+        _ = self.prefetch_groups.set(groups.into_iter().collect());
+    }
+
+    /// Reads the bytes in `range`, from the cache where possible.
+    pub fn get_bytes(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        if range.is_empty() {
+            return Ok(Bytes::new());
+        }
+        if let Some(bytes) = self.cached(range.start, Some(range.end))? {
+            return Ok(bytes);
+        }
+        self.file.read_range(range)
+    }
+
+    /// Reads the bytes in each of `ranges`, merging nearby ranges and fetching them concurrently.
+    pub fn get_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let chunks = self.fetch(ranges)?;
+        ranges
+            .iter()
+            .map(|range| {
+                let bytes = chunks
+                    .iter()
+                    .find_map(|chunk| chunk.slice(range.start, Some(range.end)));
+                bytes.ok_or_else(|| unread(range).into())
+            })
+            .collect()
+    }
+
+    /// Returns a reader starting at `offset`, over cached bytes where possible and then over the
+    /// file past them.
+    pub fn get_read(&self, offset: u64) -> InnoFileResult<Box<dyn Read + Send>> {
+        let tail = |offset| TailReader {
+            file: Arc::clone(&self.file),
+            offset,
+            reader: None,
+        };
+        match self.cached(offset, None)? {
+            Some(bytes) => {
+                let end = offset + bytes.len() as u64;
+                Ok(Box::new(bytes.reader().chain(tail(end))))
+            }
+            None => Ok(Box::new(tail(offset))),
+        }
+    }
+
+    /// Looks up the bytes from `start` to `end`, or to the end of the cached chunk if `end` is
+    /// `None`, fetching the footer or a prefetch group first if they cover `start`.
+    fn cached(&self, start: u64, end: Option<u64>) -> InnoFileResult<Option<Bytes>> {
+        if let Some(bytes) = self.footer.get().and_then(|f| f.slice(start, end)) {
+            return Ok(Some(bytes));
+        }
+        if let Some(bytes) = self.cached_in_group(start, end) {
+            return Ok(Some(bytes));
+        }
+
+        let len = self.len()?;
+        if len.saturating_sub(start) <= FOOTER_SIZE && end.is_none_or(|end| end <= len) {
+            let footer_start = len.saturating_sub(FOOTER_SIZE);
+            let data = self.file.read_range(footer_start..len)?;
+            let footer = self.footer.get_or_init(|| Chunk::new(footer_start, data));
+            return Ok(footer.slice(start, end));
+        }
+
+        let group = self.prefetch_groups.get().and_then(|groups| {
+            groups
+                .iter()
+                .find(|group| group.iter().any(|r| r.contains(&start)))
+        });
+        if let Some(group) = group {
+            let chunks = self.fetch(group)?;
+            let bytes = chunks.iter().find_map(|chunk| chunk.slice(start, end));
+            *self.cache.lock().unwrap_or_else(|e| e.into_inner()) = chunks;
+            return Ok(bytes);
+        }
+
+        Ok(None)
+    }
+
+    fn cached_in_group(&self, start: u64, end: Option<u64>) -> Option<Bytes> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find_map(|chunk| chunk.slice(start, end))
+    }
+
+    fn fetch(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Chunk>> {
+        let ranges = coalesce_ranges(ranges);
+        let data = self.file.read_ranges(&ranges)?;
+        Ok(ranges
+            .into_iter()
+            .zip(data)
+            .map(|(range, data)| Chunk::new(range.start, data))
+            .collect())
+    }
+}
+
+/// Reader of a file from `offset`, opened on the first read.
+struct TailReader {
+    file: FileRef,
+    offset: u64,
+    reader: Option<Box<dyn FileRead>>,
+}
+
+impl Read for TailReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let reader = match &mut self.reader {
+            Some(reader) => reader,
+            None => {
+                let mut reader = self.file.reader().map_err(std::io::Error::other)?;
+                reader.seek(SeekFrom::Start(self.offset))?;
+                self.reader.insert(reader)
+            }
+        };
+        reader.read(buf)
+    }
+}
+
+/// Error of a range past the end of the file, which reads fewer bytes than requested.
+fn unread(range: &Range<u64>) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        format!(
+            "Range {}..{} past the end of the file",
+            range.start, range.end
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use tempfile::tempdir;
+
+    use crate::sync::fs::{local::LocalFS, FileSystem as _};
+
+    use super::*;
+
+    #[test]
+    fn test_range_reader() -> InnoFileResult<()> {
+        let content = (0..=u8::MAX).cycle().take(3 * FOOTER_SIZE as usize);
+        let content = content.collect::<Vec<_>>();
+
+        let path = tempdir()?.path().join("range_reader.bin");
+        let path = path.to_str().unwrap();
+        let file_system = LocalFS;
+        file_system
+            .create_new(path)?
+            .writer()?
+            .write_all(&content)?;
+
+        let range_reader = RangeReader::new(Arc::from(file_system.open(path)?));
+        assert_eq!(range_reader.len()?, content.len() as u64);
+        range_reader.set_prefetch_groups([vec![100..200, 300..400]]);
+
+        let len = content.len() as u64;
+        for range in [len - 8..len, 150..160, 350..400, 1000..1010] {
+            let expected = &content[range.start as usize..range.end as usize];
+            assert_eq!(range_reader.get_bytes(range)?, expected);
+        }
+
+        // reads continue in the file past the cached chunk
+        let mut buf = Vec::new();
+        range_reader.get_read(390)?.read_to_end(&mut buf)?;
+        assert_eq!(buf, &content[390..]);
+
+        let ranges = [500..600, 0..10];
+        let bytes = range_reader.get_ranges(&ranges)?;
+        assert_eq!(bytes[0], &content[500..600]);
+        assert_eq!(bytes[1], &content[0..10]);
+        assert!(range_reader
+            .get_ranges(std::slice::from_ref(&(len - 8..len + 8)))
+            .is_err());
+
+        Ok(())
+    }
+}
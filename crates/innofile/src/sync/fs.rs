@@ -1,14 +1,31 @@
 use std::{
     fmt::Debug,
-    io::{BufRead, Seek, Write},
+    io::{BufRead, Read as _, Seek, SeekFrom, Write},
+    ops::Range,
     sync::Arc,
+    time::SystemTime,
 };
 
-use crate::error::InnoFileResult;
+use bytes::Bytes;
 
+use crate::{
+    error::{InnoFileError, InnoFileResult, Operation},
+    fs::FileStatus,
+};
+
+use super::io::{Closeable, CloseableBoxed};
+
+pub mod cache;
+pub mod faulty;
 pub mod local;
+pub mod mount;
 #[cfg(feature = "object_store")]
 pub mod object_store;
+pub mod parent_check;
+pub mod prefix;
+pub mod read_only;
+pub mod retry;
+pub mod stdio;
 
 pub type FileSystemRef = Arc<dyn FileSystem>;
 pub type FileRef = Arc<dyn File>;
@@ -24,9 +41,109 @@ pub trait FileSystem: Debug + Send + Sync {
 
     fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>>;
 
+    /// Creates a file at `path` whose content is only published there, replacing any existing
+    /// file, once its writer is closed. Dropping the writer without closing it discards the
+    /// written content.
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>>;
+
+    /// Opens the file at `path` for appending, creating it if it doesn't exist. Its writers write
+    /// after the existing content. Not supported by default.
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        _ = path;
+        Err(InnoFileError::AppendNotSupported(self.scheme().to_string()))
+    }
+
+    /// Creates the directory at `path`, whose parent must exist.
+    fn create_dir(&self, path: &str) -> InnoFileResult<()>;
+
+    /// Creates the directory at `path` and its missing parents, succeeding if it already exists.
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()>;
+
+    /// Removes the directory at `path`, which must be empty.
     fn remove_dir(&self, path: &str) -> InnoFileResult<()>;
 
+    /// Removes the directory at `path` and all of its content.
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()>;
+
     fn remove_file(&self, path: &str) -> InnoFileResult<()>;
+
+    /// Lists the files and directories directly under the directory at `path`, in no particular
+    /// order. Not supported by default.
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        _ = path;
+        Err(InnoFileError::OperationNotSupported {
+            operation: Operation::List,
+            scheme: self.scheme().to_string(),
+        })
+    }
+
+    /// Returns the status of the file or directory at `path`, with `path` as its path. Not
+    /// supported by default.
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        _ = path;
+        Err(InnoFileError::OperationNotSupported {
+            operation: Operation::Stat,
+            scheme: self.scheme().to_string(),
+        })
+    }
+}
+
+impl<F: FileSystem + ?Sized> FileSystem for Box<F> {
+    fn scheme(&self) -> &str {
+        (**self).scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        (**self).exists(path)
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).open(path)
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).create(path)
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).create_new(path)
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).create_atomic(path)
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).append(path)
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        (**self).create_dir(path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        (**self).create_dir_all(path)
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        (**self).remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        (**self).remove_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        (**self).remove_file(path)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        (**self).list(path)
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        (**self).status(path)
+    }
 }
 
 pub trait File: Debug + Send + Sync {
@@ -37,19 +154,56 @@ pub trait File: Debug + Send + Sync {
     fn reader(&self) -> InnoFileResult<Box<dyn FileRead>>;
 
     fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>>;
+
+    /// Reads the bytes in `range` of the file.
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        let mut reader = self.reader()?;
+        reader.seek(SeekFrom::Start(range.start))?;
+        let mut buf = vec![0; (range.end - range.start) as usize];
+        reader.read_exact(&mut buf)?;
+        Ok(buf.into())
+    }
+
+    /// Reads the bytes in each of `ranges`, concurrently where the file system supports it.
+    fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        ranges
+            .iter()
+            .map(|range| self.read_range(range.clone()))
+            .collect()
+    }
 }
 
 pub trait Metadata: Debug + Send + Sync {
     fn len(&self) -> u64;
+
+    /// Time the file was last modified, if known.
+    fn last_modified(&self) -> Option<SystemTime> {
+        None
+    }
+
+    /// Version of the file content, such as an object store ETag, if known.
+    fn version(&self) -> Option<String> {
+        None
+    }
 }
 
 pub trait FileRead: Debug + BufRead + Seek + Send {}
 
 impl<R: Debug + BufRead + Seek + Send> FileRead for R {}
 
-pub trait FileWrite: Debug + Write + Send {}
+/// A writer of a file, closed to flush and finish the written content.
+pub trait FileWrite: Debug + Write + Send + CloseableBoxed {}
+
+impl<W: Debug + Write + Send + CloseableBoxed> FileWrite for W {}
 
-impl<W: Debug + Write + Send> FileWrite for W {}
+impl Closeable for Box<dyn FileWrite> {
+    fn close(self) -> InnoFileResult<()> {
+        self.close_boxed()
+    }
+    fn abort(self) -> InnoFileResult<()> {
+        self.abort_boxed()
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,454 @@
+use std::{
+    fmt::{Debug, Formatter},
+    fs::{self, File as FsFile},
+    io::{BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    path::PathBuf,
+    sync::Arc,
+    time::SystemTime,
+};
+
+use bytes::Bytes;
+
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        cache::{Cache, CacheKey, CacheOptions, CacheStats, CachedMetadata},
+        FileStatus,
+    },
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+/// Caches the content read from the files of a [`FileSystem`] on local disk, according to
+/// [`CacheOptions`].
+///
+/// Readers cache whole files as they read them through, while range reads cache each range on its
+/// own. Content is keyed by the version of the file when it was opened, so changed files are read
+/// again.
+#[derive(Debug)]
+pub struct CachingFS<F> {
+    inner: F,
+    cache: Arc<Cache>,
+}
+
+impl<F: FileSystem> CachingFS<F> {
+    pub fn new(inner: F, options: CacheOptions) -> InnoFileResult<Self> {
+        Ok(Self {
+            inner,
+            cache: Arc::new(Cache::new(options)?),
+        })
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+}
+
+impl<F: FileSystem> FileSystem for CachingFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(path)
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.inner.open(path)?;
+        let metadata = CachedMetadata::from(file.metadata()?);
+        Ok(Box::new(CachingFile {
+            inner: file,
+            cache: Arc::clone(&self.cache),
+            key: metadata.key(path),
+            metadata,
+        }))
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.create(path)
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.create_new(path)
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.create_atomic(path)
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.append(path)
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir_all(path)
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.cache.invalidate(path);
+        self.inner.remove_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.cache.invalidate(path);
+        self.inner.remove_file(path)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.inner.list(path)
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.inner.status(path)
+    }
+}
+
+impl From<Box<dyn Metadata>> for CachedMetadata {
+    fn from(value: Box<dyn Metadata>) -> Self {
+        Self {
+            len: value.len(),
+            last_modified: value.last_modified(),
+            version: value.version(),
+        }
+    }
+}
+
+impl Metadata for CachedMetadata {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+    }
+
+    fn version(&self) -> Option<String> {
+        self.version.clone()
+    }
+}
+
+#[derive(Debug)]
+pub struct CachingFile {
+    inner: Box<dyn File>,
+    cache: Arc<Cache>,
+    key: CacheKey,
+    metadata: CachedMetadata,
+}
+
+impl CachingFile {
+    /// Reads the cached content of `range`, returning `None` on a miss or if it is not cached at
+    /// all.
+    fn get_range(&self, range: &Range<u64>) -> InnoFileResult<Option<Bytes>> {
+        if !self.cache.admits(range.end - range.start) {
+            return Ok(None);
+        }
+        let Some(mut file) = self.cache.get(&self.key.with_range(range.clone())) else {
+            return Ok(None);
+        };
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)?;
+        Ok(Some(buf.into()))
+    }
+
+    fn insert_range(&self, range: &Range<u64>, content: &Bytes) -> InnoFileResult<()> {
+        if self.cache.admits(range.end - range.start) {
+            self.cache
+                .insert(self.key.with_range(range.clone()), content)?;
+        }
+        Ok(())
+    }
+}
+
+impl File for CachingFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        Ok(Box::new(self.metadata.clone()))
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        if !self.cache.admits(self.metadata.len) {
+            return self.inner.reader();
+        }
+        if let Some(file) = self.cache.get(&self.key) {
+            return Ok(Box::new(BufReader::new(file)));
+        }
+        let file_path = self.cache.file_path();
+        let filling = Filling {
+            writer: BufWriter::new(FsFile::create_new(&file_path)?),
+            file_path,
+        };
+        Ok(Box::new(BufReader::new(CachingReader {
+            inner: self.inner.reader()?,
+            cache: Arc::clone(&self.cache),
+            key: self.key.clone(),
+            position: 0,
+            filling: Some(filling),
+        })))
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        self.inner.writer()
+    }
+
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        if let Some(content) = self.get_range(&range)? {
+            return Ok(content);
+        }
+        let content = self.inner.read_range(range.clone())?;
+        self.insert_range(&range, &content)?;
+        Ok(content)
+    }
+
+    /// Reads the ranges not cached yet with a single read of the inner file, which may read them
+    /// concurrently.
+    fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let mut contents = ranges
+            .iter()
+            .map(|range| self.get_range(range))
+            .collect::<InnoFileResult<Vec<_>>>()?;
+        let misses = ranges
+            .iter()
+            .zip(&contents)
+            .filter(|(_, content)| content.is_none())
+            .map(|(range, _)| range.clone())
+            .collect::<Vec<_>>();
+        if misses.is_empty() {
+            return Ok(contents.into_iter().flatten().collect());
+        }
+        let mut fetched = self.inner.read_ranges(&misses)?.into_iter();
+        for (range, content) in ranges.iter().zip(&mut contents) {
+            if content.is_none() {
+                let bytes = fetched.next().ok_or_else(|| {
+                    std::io::Error::new(ErrorKind::UnexpectedEof, "Missing range read")
+                })?;
+                self.insert_range(range, &bytes)?;
+                *content = Some(bytes);
+            }
+        }
+        Ok(contents.into_iter().flatten().collect())
+    }
+}
+
+/// Cache file being filled with the content read through a [`CachingReader`].
+struct Filling {
+    writer: BufWriter<FsFile>,
+    file_path: PathBuf,
+}
+
+/// Reader of a file not cached yet, filling the cache with the content it reads through.
+///
+/// The content is only cached once read sequentially from the start to the end. Seeking anywhere
+/// else stops filling the cache, and the reader then only reads from the inner reader.
+struct CachingReader {
+    inner: Box<dyn FileRead>,
+    cache: Arc<Cache>,
+    key: CacheKey,
+    position: u64,
+    filling: Option<Filling>,
+}
+
+impl CachingReader {
+    /// Caches the content filled so far, once the end of the inner reader is reached.
+    fn finish(&mut self) -> std::io::Result<()> {
+        let Some(filling) = self.filling.take() else {
+            return Ok(());
+        };
+        let file = filling.writer.into_inner().map_err(|e| e.into_error())?;
+        drop(file);
+        let key = self.key.clone();
+        self.cache
+            .insert_file(key, filling.file_path, self.position)?;
+        Ok(())
+    }
+
+    /// Stops filling the cache, discarding the content filled so far.
+    fn abandon(&mut self) {
+        if let Some(filling) = self.filling.take() {
+            drop(filling.writer);
+            _ = fs::remove_file(&filling.file_path);
+        }
+    }
+}
+
+impl Debug for CachingReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingReader")
+            .field("inner", &self.inner)
+            .field("key", &self.key)
+            .field("position", &self.position)
+            .field("filling", &self.filling.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for CachingReader {
+    fn drop(&mut self) {
+        self.abandon();
+    }
+}
+
+impl Read for CachingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.position += len as u64;
+        if let Some(filling) = &mut self.filling {
+            if len == 0 && !buf.is_empty() {
+                // failing to cache the content does not fail reading it
+                if let Err(error) = self.finish() {
+                    log::warn!("Failed to cache the content of a file: {error}");
+                }
+            } else if let Err(error) = filling.writer.write_all(&buf[..len]) {
+                log::warn!("Failed to cache the content of a file: {error}");
+                self.abandon();
+            }
+        }
+        Ok(len)
+    }
+}
+
+impl Seek for CachingReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        if position != self.position {
+            self.abandon();
+        }
+        self.position = position;
+        Ok(position)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use tempfile::tempdir;
+
+    use crate::{
+        fs::faulty::FaultPolicy,
+        sync::fs::{faulty::FaultyFS, local::LocalFS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_caching_fs() -> InnoFileResult<()> {
+        let content = "Hello, CachingFS!\n";
+
+        let dir = tempdir()?;
+        let path = dir.path().join("caching_fs.txt");
+        let path = path.to_str().unwrap();
+        LocalFS
+            .create_new(path)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let file_system = CachingFS::new(LocalFS, CacheOptions::new(dir.path().join("cache")))?;
+        for _ in 0..2 {
+            let mut buf = String::new();
+            file_system.open(path)?.reader()?.read_to_string(&mut buf)?;
+            assert_eq!(buf, content);
+            assert_eq!(file_system.open(path)?.read_range(7..16)?, "CachingFS");
+        }
+        let stats = file_system.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 2, 2));
+
+        let mut writer = LocalFS.append(path)?.writer()?;
+        writer.write_all(content.as_bytes())?;
+        drop(writer);
+        let mut buf = String::new();
+        file_system.open(path)?.reader()?.read_to_string(&mut buf)?;
+        assert_eq!(buf, content.repeat(2));
+        assert_eq!(file_system.stats().misses, 3);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_ranges() -> InnoFileResult<()> {
+        let content = "Hello, CachingFS!\n";
+
+        let dir = tempdir()?;
+        let path = dir.path().join("read_ranges.txt");
+        let path = path.to_str().unwrap();
+        LocalFS
+            .create_new(path)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let inner = FaultyFS::new(LocalFS, FaultPolicy::new());
+        let file_system = CachingFS::new(inner, CacheOptions::new(dir.path().join("cache")))?;
+        let file = file_system.open(path)?;
+        assert_eq!(file.read_range(7..16)?, "CachingFS");
+
+        // the ranges not cached yet are read with a single call, and the metadata from opening
+        // the file is reused
+        let contents = file.read_ranges(&[0..5, 7..16, 17..18])?;
+        assert_eq!(contents, ["Hello", "CachingFS", "\n"]);
+        assert_eq!(file.read_ranges(&[0..5, 17..18])?, ["Hello", "\n"]);
+        assert_eq!(file.metadata()?.len(), content.len() as u64);
+        drop(file);
+        assert_eq!(file_system.into_inner().calls(), 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_caching_reader() -> InnoFileResult<()> {
+        let content = "Hello, CachingFS!\n";
+
+        let dir = tempdir()?;
+        let path = dir.path().join("caching_reader.txt");
+        let path = path.to_str().unwrap();
+        LocalFS
+            .create_new(path)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let cache_dir = dir.path().join("cache");
+        let file_system = CachingFS::new(LocalFS, CacheOptions::new(&cache_dir))?;
+
+        // readers stopping before the end, or seeking elsewhere, don't cache the file
+        let mut buf = [0; 5];
+        file_system.open(path)?.reader()?.read_exact(&mut buf)?;
+        assert_eq!(&buf, b"Hello");
+        let mut reader = file_system.open(path)?.reader()?;
+        reader.seek(SeekFrom::Start(7))?;
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+        assert_eq!(buf, "CachingFS!\n");
+        drop(reader);
+        assert_eq!(file_system.stats().entries, 0);
+        assert_eq!(std::fs::read_dir(&cache_dir)?.count(), 0);
+
+        // readers reading through to the end cache the file
+        for _ in 0..2 {
+            let mut buf = String::new();
+            file_system.open(path)?.reader()?.read_to_string(&mut buf)?;
+            assert_eq!(buf, content);
+        }
+        let stats = file_system.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 3, 1));
+
+        Ok(())
+    }
+}
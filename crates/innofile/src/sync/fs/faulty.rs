@@ -0,0 +1,317 @@
+use std::{
+    io::{BufRead, Read, Seek, SeekFrom, Write},
+    ops::Range,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        faulty::{FaultPolicy, Faults},
+        FileStatus,
+    },
+    sync::io::Closeable,
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Injects the failures of a [`FaultPolicy`] into a [`FileSystem`], its files and their readers
+/// and writers, to test how callers cope with an unreliable store.
+#[derive(Debug)]
+pub struct FaultyFS<F> {
+    inner: F,
+    faults: Arc<Faults>,
+}
+
+impl<F: FileSystem> FaultyFS<F> {
+    pub fn new(inner: F, policy: FaultPolicy) -> Self {
+        Self {
+            inner,
+            faults: Arc::new(Faults::new(policy)),
+        }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    /// Number of calls made so far, including failed ones.
+    pub fn calls(&self) -> usize {
+        self.faults.calls()
+    }
+
+    fn make_file(&self, file: Box<dyn File>) -> Box<dyn File> {
+        Box::new(FaultyFile {
+            inner: file,
+            faults: Arc::clone(&self.faults),
+        })
+    }
+}
+
+impl<F: FileSystem> FileSystem for FaultyFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        inject(&self.faults)?;
+        self.inner.exists(path)
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults)?;
+        Ok(self.make_file(self.inner.open(path)?))
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults)?;
+        Ok(self.make_file(self.inner.create(path)?))
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults)?;
+        Ok(self.make_file(self.inner.create_new(path)?))
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults)?;
+        Ok(self.make_file(self.inner.create_atomic(path)?))
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults)?;
+        Ok(self.make_file(self.inner.append(path)?))
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults)?;
+        self.inner.create_dir(path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults)?;
+        self.inner.create_dir_all(path)
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults)?;
+        self.inner.remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults)?;
+        self.inner.remove_dir_all(path)
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults)?;
+        self.inner.remove_file(path)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        inject(&self.faults)?;
+        self.inner.list(path)
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        inject(&self.faults)?;
+        self.inner.status(path)
+    }
+}
+
+#[derive(Debug)]
+pub struct FaultyFile {
+    inner: Box<dyn File>,
+    faults: Arc<Faults>,
+}
+
+impl File for FaultyFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        inject(&self.faults)?;
+        self.inner.metadata()
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        inject(&self.faults)?;
+        Ok(Box::new(FaultyReader {
+            inner: self.inner.reader()?,
+            faults: Arc::clone(&self.faults),
+            buf: vec![0; READ_BUFFER_SIZE].into(),
+            start: 0,
+            end: 0,
+        }))
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        inject(&self.faults)?;
+        Ok(Box::new(FaultyWriter {
+            inner: self.inner.writer()?,
+            faults: Arc::clone(&self.faults),
+            written: 0,
+        }))
+    }
+
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        inject(&self.faults)?;
+        self.inner.read_range(range)
+    }
+
+    fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        inject(&self.faults)?;
+        self.inner.read_ranges(ranges)
+    }
+}
+
+/// Reader buffering on its own, so that only reads reaching the inner reader are calls.
+#[derive(Debug)]
+struct FaultyReader {
+    inner: Box<dyn FileRead>,
+    faults: Arc<Faults>,
+    buf: Box<[u8]>,
+    start: usize,
+    end: usize,
+}
+
+impl FaultyReader {
+    fn read_inner(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        inject(&self.faults)?;
+        let len = self.faults.read_len(buf.len());
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+impl Read for FaultyReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.start == self.end {
+            return self.read_inner(buf);
+        }
+        let len = buf.len().min(self.end - self.start);
+        buf[..len].copy_from_slice(&self.buf[self.start..self.start + len]);
+        self.start += len;
+        Ok(len)
+    }
+}
+
+impl BufRead for FaultyReader {
+    fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+        if self.start == self.end {
+            let mut buf = std::mem::take(&mut self.buf);
+            let result = self.read_inner(&mut buf);
+            self.buf = buf;
+            (self.start, self.end) = (0, result?);
+        }
+        Ok(&self.buf[self.start..self.end])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.start = (self.start + amt).min(self.end);
+    }
+}
+
+impl Seek for FaultyReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Current(offset) => SeekFrom::Current(offset - (self.end - self.start) as i64),
+            pos => pos,
+        };
+        (self.start, self.end) = (0, 0);
+        self.inner.seek(pos)
+    }
+}
+
+#[derive(Debug)]
+struct FaultyWriter {
+    inner: Box<dyn FileWrite>,
+    faults: Arc<Faults>,
+    written: u64,
+}
+
+impl Write for FaultyWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        inject(&self.faults)?;
+        let len = self.faults.write_len(buf.len(), self.written)?;
+        let len = self.inner.write(&buf[..len])?;
+        self.written += len as u64;
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        inject(&self.faults)?;
+        self.inner.flush()
+    }
+}
+
+impl Closeable for FaultyWriter {
+    fn close(self) -> InnoFileResult<()> {
+        inject(&self.faults)?;
+        self.inner.close()
+    }
+
+    fn abort(self) -> InnoFileResult<()> {
+        self.inner.abort()
+    }
+}
+
+/// Delays and counts a call, failing it if the policy says so.
+fn inject(faults: &Faults) -> std::io::Result<()> {
+    if let Some(latency) = faults.latency() {
+        std::thread::sleep(latency);
+    }
+    faults.call()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::ErrorKind, time::Duration};
+
+    use tempfile::tempdir;
+
+    use crate::sync::fs::local::LocalFS;
+
+    use super::*;
+
+    #[test]
+    fn test_faulty_fs() -> InnoFileResult<()> {
+        let content = "Hello, FaultyFS!\n";
+
+        let path = tempdir()?.path().join("faulty_fs.txt");
+        let path = path.to_str().unwrap();
+
+        let policy = FaultPolicy::new()
+            .with_failure_on_call(1)
+            .with_truncated_writes(5)
+            .with_latency(Duration::from_millis(1));
+        let file_system = FaultyFS::new(LocalFS, policy);
+        let error = file_system.create_new(path).unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::ConnectionReset));
+
+        let mut writer = file_system.create_new(path)?.writer()?;
+        let error = writer.write_all(content.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+        writer.flush()?;
+        assert_eq!(LocalFS.open(path)?.metadata()?.len(), 5);
+        LocalFS.remove_file(path)?;
+        LocalFS
+            .create_new(path)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let file_system = FaultyFS::new(LocalFS, FaultPolicy::new().with_short_reads(3));
+        let mut reader = file_system.open(path)?.reader()?;
+        assert_eq!(reader.fill_buf()?, b"Hel");
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+        assert!(file_system.calls() > content.len() / 3);
+
+        Ok(())
+    }
+}
@@ -1,10 +1,22 @@
 use std::{
-    fs::{self, File as FsFile, Metadata as FsMetadata},
-    io::{BufReader, BufWriter},
+    fs::{self, File as FsFile, Metadata as FsMetadata, OpenOptions},
+    io::{BufReader, BufWriter, Write},
     path::Path,
+    sync::Arc,
+    time::SystemTime,
 };
+#[cfg(unix)]
+use std::{ops::Range, os::unix::fs::FileExt as _};
 
-use crate::error::InnoFileResult;
+#[cfg(unix)]
+use bytes::Bytes;
+
+use crate::{
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+    sync::io::Closeable,
+    utils::staging::Staging,
+};
 
 use super::{File, FileRead, FileSystem, FileWrite, Metadata};
 
@@ -20,6 +32,12 @@ impl LocalFS {
             .unwrap_or_else(|| path.to_string())
     }
 
+    fn context(operation: Operation, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_path(path)
+            .with_scheme(SCHEME)
+    }
+
     fn ensure_parent_path(path: impl AsRef<Path>) -> InnoFileResult<()> {
         if let Some(parent) = path.as_ref().parent() {
             if !fs::exists(parent)? {
@@ -36,32 +54,111 @@ impl FileSystem for LocalFS {
     }
 
     fn exists(&self, path: &str) -> InnoFileResult<bool> {
-        Ok(fs::exists(Self::strip_scheme(path))?)
+        fs::exists(Self::strip_scheme(path)).with_context(|| Self::context(Operation::Stat, path))
     }
 
     fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        let path = Self::strip_scheme(path);
-        Ok(Box::new(LocalFile::new(&path, FsFile::open(&path)?)))
+        let local_path = Self::strip_scheme(path);
+        let file =
+            FsFile::open(&local_path).with_context(|| Self::context(Operation::Open, path))?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
     }
 
     fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        let path = Self::strip_scheme(path);
-        Self::ensure_parent_path(&path)?;
-        Ok(Box::new(LocalFile::new(&path, FsFile::create(&path)?)))
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Create, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let file = FsFile::create(&local_path).with_context(context)?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
     }
 
     fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        let path = Self::strip_scheme(path);
-        Self::ensure_parent_path(&path)?;
-        Ok(Box::new(LocalFile::new(&path, FsFile::create_new(&path)?)))
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Create, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let file = FsFile::create_new(&local_path).with_context(context)?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Create, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let staging = Staging::new(&local_path);
+        let file = FsFile::create_new(staging.temp_path()).with_context(context)?;
+        Ok(Box::new(StagedFile {
+            inner: LocalFile::new(&local_path, file),
+            staging: Arc::new(staging),
+        }))
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Append, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&local_path)
+            .with_context(context)?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        fs::create_dir(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Create, path))
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        fs::create_dir_all(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Create, path))
     }
 
     fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
-        Ok(fs::remove_dir(Self::strip_scheme(path))?)
+        fs::remove_dir(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Remove, path))
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        fs::remove_dir_all(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Remove, path))
     }
 
     fn remove_file(&self, path: &str) -> InnoFileResult<()> {
-        Ok(fs::remove_file(Self::strip_scheme(path))?)
+        fs::remove_file(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Remove, path))
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let context = || Self::context(Operation::List, path);
+        let mut statuses = Vec::new();
+        for entry in fs::read_dir(Self::strip_scheme(path)).with_context(context)? {
+            let entry = entry.with_context(context)?;
+            let metadata = entry.metadata().with_context(context)?;
+            statuses.push(FileStatus {
+                path: format!(
+                    "{}/{}",
+                    path.trim_end_matches('/'),
+                    entry.file_name().to_string_lossy()
+                ),
+                len: metadata.len(),
+                is_dir: metadata.is_dir(),
+                last_modified: metadata.modified().ok(),
+            });
+        }
+        Ok(statuses)
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let metadata = fs::metadata(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Stat, path))?;
+        Ok(FileStatus {
+            path: path.to_string(),
+            len: metadata.len(),
+            is_dir: metadata.is_dir(),
+            last_modified: metadata.modified().ok(),
+        })
     }
 }
 
@@ -96,16 +193,96 @@ impl File for LocalFile {
     fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
         Ok(Box::new(BufWriter::new(self.inner.try_clone()?)))
     }
+
+    // Positional reads leave the cursor shared by cloned handles untouched.
+    #[cfg(unix)]
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        let mut buf = vec![0; (range.end - range.start) as usize];
+        self.inner.read_exact_at(&mut buf, range.start)?;
+        Ok(buf.into())
+    }
+}
+
+// Syncs the file, so that write errors are reported on close rather than lost.
+impl Closeable for BufWriter<FsFile> {
+    fn close(self) -> InnoFileResult<()> {
+        let file = self.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        Ok(())
+    }
+}
+
+/// A file written to a staging path and published at its own path when its writer is closed.
+#[derive(Debug)]
+pub struct StagedFile {
+    inner: LocalFile,
+    staging: Arc<Staging>,
+}
+
+impl File for StagedFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        self.inner.metadata()
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        self.inner.reader()
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        Ok(Box::new(StagedWriter {
+            inner: BufWriter::new(self.inner.inner.try_clone()?),
+            staging: Arc::clone(&self.staging),
+        }))
+    }
+
+    #[cfg(unix)]
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        self.inner.read_range(range)
+    }
+}
+
+#[derive(Debug)]
+struct StagedWriter {
+    inner: BufWriter<FsFile>,
+    staging: Arc<Staging>,
+}
+
+impl Write for StagedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Closeable for StagedWriter {
+    fn close(self) -> InnoFileResult<()> {
+        self.inner.close()?;
+        self.staging.publish()?;
+        Ok(())
+    }
 }
 
 impl Metadata for FsMetadata {
     fn len(&self) -> u64 {
         self.len()
     }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.modified().ok()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::ErrorKind;
+
     use tempfile::tempdir;
 
     use crate::fs::FileSystemBuilder;
@@ -150,4 +327,99 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_error_context() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("error_context.txt");
+        let path = path.to_str().unwrap();
+
+        let error = LocalFS.open(path).unwrap_err();
+        assert!(error.is_not_found());
+        let context = error.context().unwrap();
+        assert_eq!(context.operation, Operation::Open);
+        assert_eq!(context.path.as_deref(), Some(path));
+        assert_eq!(context.scheme.as_deref(), Some(SCHEME));
+
+        LocalFS.create_new(path)?;
+        assert!(LocalFS.create_new(path).unwrap_err().is_already_exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_atomic() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("create_atomic.txt");
+        let path = path.to_str().unwrap();
+
+        let mut writer = LocalFS.create_atomic(path)?.writer()?;
+        writer.write_all(b"discarded")?;
+        writer.flush()?;
+        assert!(!LocalFS.exists(path)?);
+        drop(writer);
+        assert!(!LocalFS.exists(path)?);
+        assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+
+        let mut writer = LocalFS.create_atomic(path)?.writer()?;
+        writer.write_all(b"published")?;
+        assert!(!LocalFS.exists(path)?);
+        writer.close()?;
+        assert_eq!(fs::read_to_string(path)?, "published");
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dirs() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let parent = dir.path().join("parent");
+        let child = parent.join("child");
+        let (parent, child) = (parent.to_str().unwrap(), child.to_str().unwrap());
+        let file_path = format!("{}/file.txt", child);
+
+        let file_system = LocalFS;
+        assert!(file_system.create_dir(child).unwrap_err().is_not_found());
+        file_system.create_dir_all(child)?;
+        file_system.create_dir_all(child)?;
+        assert!(file_system
+            .create_dir(child)
+            .unwrap_err()
+            .is_already_exists());
+        file_system.create(&file_path)?;
+        let statuses = file_system.list(parent)?;
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(
+            (statuses[0].path.as_str(), statuses[0].is_dir),
+            (child, true)
+        );
+        assert!(file_system.status(child)?.is_dir);
+        let status = file_system.status(&file_path)?;
+        assert_eq!(
+            (status.path.as_str(), status.is_dir),
+            (file_path.as_str(), false)
+        );
+
+        let error = file_system.remove_dir(parent).unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::DirectoryNotEmpty));
+        file_system.remove_dir_all(parent)?;
+        assert!(!file_system.exists(parent)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_append() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("append.txt");
+        let path = path.to_str().unwrap();
+
+        for line in ["first\n", "second\n"] {
+            let mut writer = LocalFS.append(path)?.writer()?;
+            writer.write_all(line.as_bytes())?;
+            writer.close()?;
+        }
+        assert_eq!(fs::read_to_string(path)?, "first\nsecond\n");
+
+        Ok(())
+    }
 }
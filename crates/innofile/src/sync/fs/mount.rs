@@ -0,0 +1,212 @@
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        mount::{merge_statuses, uri_root, MountTable},
+        FileStatus, FileSystemBuilder,
+    },
+};
+
+use super::{File, FileSystem, FileSystemRef};
+
+/// Routes paths to the file systems mounted at their longest matching prefix, such as
+/// `/warehouse/` to S3 and `/tmp/` to local files.
+///
+/// Listing a directory merges the listing of its mount with the directories leading to the
+/// mounts nested under it.
+#[derive(Debug, Default)]
+pub struct MountFS {
+    mounts: MountTable<FileSystemRef>,
+}
+
+impl MountFS {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Builds a file system for each mount of `config`, from its prefix to the URI of its root,
+    /// such as `/warehouse/` to `s3://bucket/warehouse/`.
+    pub fn from_config(
+        config: impl IntoIterator<Item = (impl ToString, impl ToString)>,
+    ) -> InnoFileResult<Self> {
+        let mut file_system = Self::new();
+        for (prefix, uri) in config {
+            let uri = uri.to_string();
+            let mounted = FileSystemBuilder::from_path(&uri)?.build_sync()?;
+            file_system =
+                file_system.with_mount(prefix.to_string(), mounted.into(), uri_root(&uri)?);
+        }
+        Ok(file_system)
+    }
+
+    /// Mounts `file_system` at `prefix`, resolving the paths under it against `root`, a
+    /// directory or object key prefix of the file system.
+    pub fn with_mount(
+        mut self,
+        prefix: impl AsRef<str>,
+        file_system: FileSystemRef,
+        root: impl ToString,
+    ) -> Self {
+        self.mounts.insert(prefix.as_ref(), root, file_system);
+        self
+    }
+}
+
+impl FileSystem for MountFS {
+    fn scheme(&self) -> &str {
+        "mount"
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        if !self.mounts.mount_points(path)?.is_empty() {
+            return Ok(true);
+        }
+        match self.mounts.route(path) {
+            Ok((mount, path)) => mount.file_system.exists(&path),
+            Err(_) => Ok(false),
+        }
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.open(&path)
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create(&path)
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_new(&path)
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_atomic(&path)
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.append(&path)
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_dir(&path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_dir_all(&path)
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.remove_dir(&path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.remove_dir_all(&path)
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.remove_file(&path)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let mount_points = self.mounts.mount_points(path)?;
+        let statuses = match self.mounts.route(path) {
+            Ok((mount, path)) => match mount.file_system.list(&path) {
+                Ok(statuses) => statuses
+                    .into_iter()
+                    .map(|status| mount.mount_status(status))
+                    .collect(),
+                Err(error) if error.is_not_found() && !mount_points.is_empty() => Vec::new(),
+                Err(error) => return Err(error),
+            },
+            Err(_) if !mount_points.is_empty() => Vec::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(merge_statuses(statuses, mount_points))
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let mount_points = self.mounts.mount_points(path)?;
+        let status = match self.mounts.route(path) {
+            Ok((mount, path)) => match mount.file_system.status(&path) {
+                Ok(status) => status,
+                Err(error) if error.is_not_found() && !mount_points.is_empty() => {
+                    FileStatus::dir("")
+                }
+                Err(error) => return Err(error),
+            },
+            // directories containing mount points exist even if nothing is mounted on them
+            Err(_) if !mount_points.is_empty() => FileStatus::dir(""),
+            Err(error) => return Err(error),
+        };
+        Ok(FileStatus {
+            path: path.to_string(),
+            ..status
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{Read as _, Write as _},
+        sync::Arc,
+    };
+
+    use tempfile::tempdir;
+
+    use crate::{error::InnoFileError, sync::fs::local::LocalFS};
+
+    use super::*;
+
+    #[test]
+    fn test_mount_fs() -> InnoFileResult<()> {
+        let content = "Hello, MountFS!\n";
+
+        let dir = tempdir()?;
+        let (warehouse, tmp) = (dir.path().join("warehouse"), dir.path().join("tmp"));
+        let config = [
+            ("/warehouse/", warehouse.to_str().unwrap()),
+            ("/tmp", tmp.to_str().unwrap()),
+        ];
+        let file_system: FileSystemRef = Arc::new(MountFS::from_config(config)?);
+
+        file_system
+            .create_new("/warehouse/sales/mount_fs.txt")?
+            .writer()?
+            .write_all(content.as_bytes())?;
+        let mut buf = String::new();
+        let path = warehouse.join("sales/mount_fs.txt");
+        LocalFS
+            .open(path.to_str().unwrap())?
+            .reader()?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+
+        let paths = |statuses: Vec<FileStatus>| {
+            statuses
+                .into_iter()
+                .map(|status| status.path)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(file_system.list("/")?), ["/tmp", "/warehouse"]);
+        assert_eq!(paths(file_system.list("/warehouse")?), ["/warehouse/sales"]);
+        let statuses = file_system.list("/warehouse/sales/")?;
+        assert_eq!(paths(statuses), ["/warehouse/sales/mount_fs.txt"]);
+
+        assert!(file_system.exists("/")?);
+        assert!(!file_system.exists("/tmp/mount_fs.txt")?);
+        let error = file_system.open("/ref/mount_fs.txt").unwrap_err();
+        assert!(matches!(error, InnoFileError::MountNotFound(_)));
+
+        Ok(())
+    }
+}
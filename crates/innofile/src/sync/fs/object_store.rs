@@ -1,23 +1,37 @@
 use std::{
     collections::HashMap,
-    io::{BufRead, Read, Seek, SeekFrom, Write},
+    fmt::{Debug, Formatter},
+    io::{BufRead, ErrorKind, Read, Seek, SeekFrom, Write},
+    ops::Range,
     sync::Arc,
+    time::SystemTime,
 };
 
+#[cfg(feature = "s3")]
+use anyhow::anyhow;
+use bytes::Bytes;
 use futures::{StreamExt as _, TryStreamExt as _};
 #[cfg(feature = "s3")]
-use object_store::aws::AmazonS3Builder;
+use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
 use object_store::{
     buffered::{BufReader, BufWriter},
-    path::Path,
-    Error, ObjectMeta, ObjectStore, PutMode, PutPayload, PutResult,
+    Error, ListResult, ObjectMeta, ObjectStore, PutMode, PutPayload, PutResult,
 };
 use tokio::{
     io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncSeekExt as _, AsyncWriteExt as _},
-    runtime::Runtime,
+    runtime::Handle,
+};
+
+use crate::{
+    error::{ErrorContext, InnoFileError, InnoFileResult, Operation, ResultExt as _},
+    fs::{upload::UploadOptions, FileStatus},
+    runtime::{block_on, shared_handle},
+    sync::io::Closeable,
+    utils::{bucket_object_path, object_path},
 };
 
-use crate::error::InnoFileResult;
+#[cfg(feature = "s3")]
+use crate::fs::credential::{AwsCredentialProvider, CredentialProvider};
 
 use super::{File, FileRead, FileSystem, FileWrite, Metadata};
 
@@ -25,22 +39,46 @@ use super::{File, FileRead, FileSystem, FileWrite, Metadata};
 pub struct ObjectFS {
     scheme: String,
     store: Arc<dyn ObjectStore>,
-    rt: Arc<Runtime>,
+    handle: Handle,
+    upload_options: UploadOptions,
+    /// Bucket of the store, which URIs of other buckets are rejected for.
+    bucket: Option<String>,
+    /// Builder of the store of S3 file systems, to build it again with other credentials.
+    #[cfg(feature = "s3")]
+    s3_builder: Option<AmazonS3Builder>,
 }
 
 impl ObjectFS {
+    /// Creates a file system driven on the runtime shared across the crate.
     pub fn from_store(scheme: impl ToString, store: Arc<dyn ObjectStore>) -> InnoFileResult<Self> {
         Ok(Self {
             scheme: scheme.to_string(),
             store,
-            rt: Arc::new(
-                tokio::runtime::Builder::new_current_thread()
-                    .enable_all()
-                    .build()?,
-            ),
+            handle: shared_handle(),
+            upload_options: UploadOptions::default(),
+            bucket: None,
+            #[cfg(feature = "s3")]
+            s3_builder: None,
         })
     }
 
+    /// Drives all I/O on the runtime behind `handle`, which should be a multi-thread runtime.
+    pub fn with_handle(mut self, handle: Handle) -> Self {
+        self.handle = handle;
+        self
+    }
+
+    pub fn with_upload_options(mut self, upload_options: UploadOptions) -> Self {
+        self.upload_options = upload_options;
+        self
+    }
+
+    /// Rejects URIs of other buckets than `bucket`, which the store is of.
+    pub fn with_bucket(mut self, bucket: impl ToString) -> Self {
+        self.bucket = Some(bucket.to_string());
+        self
+    }
+
     #[cfg(feature = "s3")]
     pub fn new_s3(
         scheme: impl ToString,
@@ -54,31 +92,71 @@ impl ObjectFS {
         for (key, value) in properties {
             builder = builder.with_config(key.to_string().parse()?, value.to_string());
         }
-        let store = Arc::new(builder.build()?);
-        Self::from_store(scheme, store)
+        let store = Arc::new(builder.clone().build()?);
+        Ok(Self {
+            bucket: builder.get_config_value(&AmazonS3ConfigKey::Bucket),
+            s3_builder: Some(builder),
+            ..Self::from_store(scheme, store)?
+        })
+    }
+
+    /// Fetches the credentials of an S3 file system from `provider`, instead of resolving them
+    /// from the environment.
+    #[cfg(feature = "s3")]
+    pub fn with_credential_provider(
+        mut self,
+        provider: CredentialProvider,
+    ) -> InnoFileResult<Self> {
+        let builder = self
+            .s3_builder
+            .take()
+            .ok_or_else(|| anyhow!("Credential providers only supported by S3 file systems"))?
+            .with_credentials(Arc::new(AwsCredentialProvider::new(provider)));
+        self.store = Arc::new(builder.clone().build()?);
+        self.s3_builder = Some(builder);
+        Ok(self)
     }
 
-    fn head(&self, path: impl AsRef<str>) -> InnoFileResult<ObjectMeta, Error> {
-        self.rt.block_on(self.store.head(&Path::parse(path)?))
+    fn head(&self, path: impl AsRef<str>) -> InnoFileResult<ObjectMeta> {
+        Ok(block_on(
+            &self.handle,
+            self.store.head(&self.object_path(path.as_ref())?),
+        )??)
     }
 
     fn put_empty_file(
         &self,
         path: impl AsRef<str>,
         put_mode: PutMode,
-    ) -> InnoFileResult<PutResult, Error> {
-        self.rt.block_on(self.store.put_opts(
-            &Path::parse(path)?,
-            PutPayload::new(),
-            put_mode.into(),
-        ))
+    ) -> InnoFileResult<PutResult> {
+        Ok(block_on(
+            &self.handle,
+            self.store.put_opts(
+                &self.object_path(path.as_ref())?,
+                PutPayload::new(),
+                put_mode.into(),
+            ),
+        )??)
+    }
+
+    fn object_path(&self, path: &str) -> InnoFileResult<object_store::path::Path> {
+        bucket_object_path(path, self.bucket.as_deref())
+    }
+
+    fn context(&self, operation: Operation, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_path(path)
+            .with_scheme(&self.scheme)
     }
 
     fn make_file(&self, path: impl ToString) -> ObjectFile {
-        ObjectFile::new(path, Arc::clone(&self.store), Arc::clone(&self.rt))
+        ObjectFile::new(path, Arc::clone(&self.store), self.handle.clone())
+            .with_upload_options(self.upload_options.clone())
     }
 }
 
+// Objects can't be appended to, and emulating it by uploading the existing content again costs
+// as much as the whole object, so appending is left unsupported.
 impl FileSystem for ObjectFS {
     fn scheme(&self) -> &str {
         &self.scheme
@@ -87,39 +165,114 @@ impl FileSystem for ObjectFS {
     fn exists(&self, path: &str) -> InnoFileResult<bool> {
         match self.head(path) {
             Ok(_) => Ok(true),
-            Err(Error::NotFound { path: _, source: _ }) => Ok(false),
-            Err(error) => Err(error)?,
+            Err(InnoFileError::ObjectStore(Error::NotFound { .. })) => Ok(false),
+            Err(error) => Err(error).with_context(|| self.context(Operation::Stat, path)),
         }
     }
 
     fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        self.head(path)?;
-        Ok(Box::new(self.make_file(path)))
+        let meta = self
+            .head(path)
+            .with_context(|| self.context(Operation::Open, path))?;
+        Ok(Box::new(self.make_file(path).with_object_meta(meta)))
     }
 
     fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        self.put_empty_file(path, PutMode::Overwrite)?;
+        self.put_empty_file(path, PutMode::Overwrite)
+            .with_context(|| self.context(Operation::Create, path))?;
         Ok(Box::new(self.make_file(path)))
     }
 
     fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        self.put_empty_file(path, PutMode::Create)?;
+        self.put_empty_file(path, PutMode::Create)
+            .with_context(|| self.context(Operation::Create, path))?;
         Ok(Box::new(self.make_file(path)))
     }
 
+    // Multipart uploads only become visible once completed, so nothing is put upfront.
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.object_path(path)
+            .with_context(|| self.context(Operation::Create, path))?;
+        Ok(Box::new(ObjectFile {
+            atomic: true,
+            ..self.make_file(path)
+        }))
+    }
+
+    /// Does nothing but validate `path`, since directories are implicit in object stores,
+    /// existing as long as objects are stored under them. The directory doesn't exist afterwards
+    /// until an object is stored under it.
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.object_path(path)
+            .with_context(|| self.context(Operation::Create, path))?;
+        Ok(())
+    }
+
+    /// Does nothing but validate `path`, like [`Self::create_dir`].
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.object_path(path)
+            .with_context(|| self.context(Operation::Create, path))?;
+        Ok(())
+    }
+
     fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        let context = || self.context(Operation::Remove, path);
+        let prefix = self.object_path(path).with_context(context)?;
+        let first = block_on(&self.handle, self.store.list(Some(&prefix)).next())?;
+        if first.transpose().with_context(context)?.is_some() {
+            Err(std::io::Error::from(ErrorKind::DirectoryNotEmpty)).with_context(context)?;
+        }
+        Ok(())
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let context = || self.context(Operation::Remove, path);
+        let prefix = self.object_path(path).with_context(context)?;
         let locations = self
             .store
-            .list(Some(&Path::parse(path)?))
+            .list(Some(&prefix))
             .map_ok(|m| m.location)
             .boxed();
-        self.rt
-            .block_on(self.store.delete_stream(locations).try_collect::<Vec<_>>())?;
+        block_on(
+            &self.handle,
+            self.store.delete_stream(locations).try_collect::<Vec<_>>(),
+        )?
+        .with_context(context)?;
         Ok(())
     }
 
     fn remove_file(&self, path: &str) -> InnoFileResult<()> {
-        Ok(self.rt.block_on(self.store.delete(&Path::parse(path)?))?)
+        let context = || self.context(Operation::Remove, path);
+        let location = self.object_path(path).with_context(context)?;
+        block_on(&self.handle, self.store.delete(&location))?.with_context(context)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let context = || self.context(Operation::List, path);
+        let prefix = self.object_path(path).with_context(context)?;
+        let result = block_on(&self.handle, self.store.list_with_delimiter(Some(&prefix)))?
+            .with_context(context)?;
+        Ok(file_statuses(result))
+    }
+
+    // Directories are implicit, existing as long as objects are stored under them.
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let context = || self.context(Operation::Stat, path);
+        match self.head(path) {
+            Ok(meta) => Ok(FileStatus {
+                path: path.to_string(),
+                ..meta.into()
+            }),
+            Err(InnoFileError::ObjectStore(Error::NotFound { .. })) => {
+                let prefix = self.object_path(path).with_context(context)?;
+                let first = block_on(&self.handle, self.store.list(Some(&prefix)).next())?;
+                match first.transpose().with_context(context)? {
+                    Some(_) => Ok(FileStatus::dir(path)),
+                    None => Err(std::io::Error::from(ErrorKind::NotFound)).with_context(context),
+                }
+            }
+            Err(error) => Err(error).with_context(context),
+        }
     }
 }
 
@@ -127,22 +280,58 @@ impl FileSystem for ObjectFS {
 pub struct ObjectFile {
     path: String,
     store: Arc<dyn ObjectStore>,
-    rt: Arc<Runtime>,
+    handle: Handle,
+    upload_options: UploadOptions,
+    atomic: bool,
+    /// Metadata of the object when the file was opened, which reading reuses.
+    meta: Option<ObjectMeta>,
 }
 
 impl ObjectFile {
-    pub fn new(path: impl ToString, store: Arc<dyn ObjectStore>, rt: Arc<Runtime>) -> Self {
+    pub fn new(path: impl ToString, store: Arc<dyn ObjectStore>, handle: Handle) -> Self {
         Self {
             path: path.to_string(),
             store,
-            rt,
+            handle,
+            upload_options: UploadOptions::default(),
+            atomic: false,
+            meta: None,
         }
     }
 
+    pub fn with_upload_options(mut self, upload_options: UploadOptions) -> Self {
+        self.upload_options = upload_options;
+        self
+    }
+
+    fn with_object_meta(mut self, meta: ObjectMeta) -> Self {
+        self.meta = Some(meta);
+        self
+    }
+
+    /// Metadata of the object, as of opening the file if it was opened.
     pub fn object_meta(&self) -> InnoFileResult<ObjectMeta> {
-        Ok(self
-            .rt
-            .block_on(self.store.head(&Path::parse(&self.path)?))?)
+        if let Some(meta) = &self.meta {
+            return Ok(meta.clone());
+        }
+        Ok(block_on(
+            &self.handle,
+            self.store.head(&object_path(&self.path)?),
+        )??)
+    }
+
+    /// Opens a writer uploading to the object.
+    pub fn object_writer(&self) -> InnoFileResult<ObjectWriter> {
+        let location = object_path(&self.path)?;
+        let buf_writer = self
+            .upload_options
+            .buf_writer(Arc::clone(&self.store), location);
+        Ok(ObjectWriter {
+            buf_writer,
+            handle: self.handle.clone(),
+            closed: false,
+            abort_on_drop: self.atomic,
+        })
     }
 }
 
@@ -158,15 +347,31 @@ impl File for ObjectFile {
     fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
         Ok(Box::new(ObjectReader {
             buf_reader: BufReader::new(Arc::clone(&self.store), &self.object_meta()?),
-            rt: Arc::clone(&self.rt),
+            handle: self.handle.clone(),
         }))
     }
 
     fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
-        Ok(Box::new(ObjectWriter {
-            buf_writer: BufWriter::new(Arc::clone(&self.store), Path::parse(&self.path)?),
-            rt: Arc::clone(&self.rt),
-        }))
+        Ok(Box::new(self.object_writer()?))
+    }
+
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        let range = range.start as usize..range.end as usize;
+        Ok(block_on(
+            &self.handle,
+            self.store.get_range(&object_path(&self.path)?, range),
+        )??)
+    }
+
+    fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let ranges = ranges
+            .iter()
+            .map(|range| range.start as usize..range.end as usize)
+            .collect::<Vec<_>>();
+        Ok(block_on(
+            &self.handle,
+            self.store.get_ranges(&object_path(&self.path)?, &ranges),
+        )??)
     }
 }
 
@@ -174,23 +379,31 @@ impl Metadata for ObjectMeta {
     fn len(&self) -> u64 {
         self.size as _
     }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        Some(self.last_modified.into())
+    }
+
+    fn version(&self) -> Option<String> {
+        self.e_tag.clone().or_else(|| self.version.clone())
+    }
 }
 
 #[derive(Debug)]
 pub struct ObjectReader {
     buf_reader: BufReader,
-    rt: Arc<Runtime>,
+    handle: Handle,
 }
 
 impl Read for ObjectReader {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
-        self.rt.block_on(self.buf_reader.read(buf))
+        block_on(&self.handle, self.buf_reader.read(buf))?
     }
 }
 
 impl BufRead for ObjectReader {
     fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
-        self.rt.block_on(self.buf_reader.fill_buf())
+        block_on(&self.handle, self.buf_reader.fill_buf())?
     }
 
     fn consume(&mut self, amt: usize) {
@@ -200,34 +413,86 @@ impl BufRead for ObjectReader {
 
 impl Seek for ObjectReader {
     fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
-        self.rt.block_on(self.buf_reader.seek(pos))
+        block_on(&self.handle, self.buf_reader.seek(pos))?
     }
 }
 
-#[derive(Debug)]
+/// Writer uploading to an object, completing the upload when closed.
+///
+/// Writers dropped without closing complete the upload on a best effort basis, or abort it for
+/// atomically created files. Close them to observe upload failures.
 pub struct ObjectWriter {
     buf_writer: BufWriter,
-    rt: Arc<Runtime>,
+    handle: Handle,
+    closed: bool,
+    abort_on_drop: bool,
+}
+
+impl Debug for ObjectWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectWriter")
+            .field("buf_writer", &self.buf_writer)
+            .field("closed", &self.closed)
+            .field("abort_on_drop", &self.abort_on_drop)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Drop for ObjectWriter {
     fn drop(&mut self) {
-        self.rt.block_on(self.buf_writer.shutdown()).unwrap();
+        if self.closed {
+            return;
+        }
+        let result = if self.abort_on_drop {
+            block_on(&self.handle, self.buf_writer.abort())
+                .and_then(|result| result.map_err(Into::into))
+        } else {
+            block_on(&self.handle, self.buf_writer.shutdown())
+                .and_then(|result| result.map_err(Into::into))
+        };
+        if let Err(error) = result {
+            log::warn!("Failed to finish the upload of a writer dropped without closing: {error}");
+        }
     }
 }
 
 impl Write for ObjectWriter {
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
-        self.rt.block_on(self.buf_writer.write(buf))
+        block_on(&self.handle, self.buf_writer.write(buf))?
     }
 
     fn flush(&mut self) -> std::io::Result<()> {
-        self.rt.block_on(self.buf_writer.flush())
+        block_on(&self.handle, self.buf_writer.flush())?
+    }
+}
+
+impl Closeable for ObjectWriter {
+    fn close(mut self) -> InnoFileResult<()> {
+        // a failed shutdown must not be attempted again on drop, which panics
+        self.closed = true;
+        block_on(&self.handle, self.buf_writer.shutdown())??;
+        Ok(())
+    }
+
+    /// Aborts the upload, discarding the parts uploaded so far.
+    fn abort(mut self) -> InnoFileResult<()> {
+        self.closed = true;
+        block_on(&self.handle, self.buf_writer.abort())??;
+        Ok(())
     }
 }
 
+/// Converts a listing of objects and common prefixes, as directories, to file statuses.
+fn file_statuses(result: ListResult) -> Vec<FileStatus> {
+    let dirs = result.common_prefixes.into_iter().map(FileStatus::dir);
+    dirs.chain(result.objects.into_iter().map(FileStatus::from))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
     use object_store::local::LocalFileSystem;
     use tempfile::tempdir;
 
@@ -260,4 +525,212 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_url_paths() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let file_name = "url_paths.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?.with_bucket("bucket");
+
+        file_system.create_new(&format!("s3://bucket/{}", file_name))?;
+        assert!(file_system.exists(file_name)?);
+        assert!(file_system.exists(&format!("s3://bucket/{}", file_name))?);
+
+        // URIs of other buckets don't resolve to the same key in this one
+        let other = format!("s3://other-bucket/{}", file_name);
+        let error = file_system.remove_file(&other).unwrap_err();
+        assert!(matches!(
+            error.root(),
+            InnoFileError::PathOutsideRoot { .. }
+        ));
+        assert!(file_system.exists(file_name)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_error_context() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        let error = file_system.open("missing.txt").unwrap_err();
+        assert!(error.is_not_found());
+        assert!(!error.is_retryable());
+        assert_eq!(error.context().unwrap().operation, Operation::Open);
+
+        file_system.create_new("existing.txt")?;
+        let error = file_system.create_new("existing.txt").unwrap_err();
+        assert!(error.is_already_exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_atomic() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let file_name = "create_atomic.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        let mut writer = file_system.create_atomic(file_name)?.writer()?;
+        writer.write_all(b"discarded")?;
+        assert!(!file_system.exists(file_name)?);
+        drop(writer);
+        assert!(!file_system.exists(file_name)?);
+
+        let mut writer = file_system.create_atomic(file_name)?.writer()?;
+        writer.write_all(b"published")?;
+        assert!(!file_system.exists(file_name)?);
+        writer.close()?;
+        assert_eq!(file_system.open(file_name)?.metadata()?.len(), 9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_upload_options() -> InnoFileResult<()> {
+        let content = "Hello, multipart ObjectFS!\n".repeat(8);
+
+        let dir = tempdir()?;
+        let file_name = "upload_options.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let progress = Arc::new(AtomicU64::new(0));
+        let upload_options = UploadOptions::new()
+            .with_part_size(16)
+            .with_max_concurrency(2)
+            .with_upload_progress({
+                let progress = Arc::clone(&progress);
+                move |uploaded| progress.store(uploaded, Ordering::Relaxed)
+            });
+        let file_system = ObjectFS::from_store("file", store)?.with_upload_options(upload_options);
+
+        let mut writer = file_system.create(file_name)?.writer()?;
+        writer.write_all(content.as_bytes())?;
+        // the last part is still buffered, and only counted once uploaded
+        assert!(progress.load(Ordering::Relaxed) < content.len() as u64);
+        writer.close()?;
+        assert_eq!(progress.load(Ordering::Relaxed), content.len() as u64);
+        let mut buf = String::new();
+        file_system
+            .open(file_name)?
+            .reader()?
+            .read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+
+        // writers of files not created atomically are aborted through the trait, and not completed
+        let mut writer = file_system.make_file("aborted.txt").writer()?;
+        writer.write_all(content.as_bytes())?;
+        writer.abort()?;
+        assert!(!file_system.exists("aborted.txt")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_close_failed_upload() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+        // uploads below a file fail, since its parent directory cannot be created
+        file_system.create_new("blocker")?;
+
+        let mut writer = file_system.make_file("blocker/plain.txt").writer()?;
+        writer.write_all(b"failed")?;
+        assert!(writer.close().is_err());
+
+        let mut writer = file_system.create_atomic("blocker/atomic.txt")?.writer()?;
+        writer.write_all(b"failed")?;
+        assert!(writer.close().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dirs() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        file_system.create_dir("parent/child")?;
+        file_system.create("parent/child/file.txt")?;
+        file_system.create("parent/file.txt")?;
+        let statuses = file_system.list("parent")?;
+        let statuses = statuses
+            .iter()
+            .map(|s| (s.path.as_str(), s.is_dir))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            statuses,
+            [("parent/child", true), ("parent/file.txt", false)]
+        );
+        assert!(file_system.status("parent")?.is_dir);
+        assert!(!file_system.status("parent/file.txt")?.is_dir);
+        assert!(file_system.status("missing").unwrap_err().is_not_found());
+        let error = file_system.remove_dir("parent").unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::DirectoryNotEmpty));
+        file_system.remove_dir_all("parent")?;
+        assert!(!file_system.exists("parent/child/file.txt")?);
+        file_system.remove_dir("parent")?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_append() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        let error = file_system.append("append.txt").unwrap_err();
+        assert!(matches!(error.root(), InnoFileError::AppendNotSupported(_)));
+        assert!(!file_system.exists("append.txt")?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_concurrent_readers() -> InnoFileResult<()> {
+        let content = "Hello, concurrent ObjectFS!\n";
+
+        let dir = tempdir()?;
+        let file_name = "concurrent_object_fs.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+        file_system
+            .create_new(file_name)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        std::thread::scope(|scope| {
+            let handles = (0..8)
+                .map(|_| {
+                    scope.spawn(|| -> InnoFileResult<String> {
+                        let mut buf = String::new();
+                        file_system
+                            .open(file_name)?
+                            .reader()?
+                            .read_to_string(&mut buf)?;
+                        Ok(buf)
+                    })
+                })
+                .collect::<Vec<_>>();
+            for handle in handles {
+                assert_eq!(handle.join().unwrap()?, content);
+            }
+            Ok(())
+        })
+    }
+
+    #[tokio::test]
+    async fn test_inside_current_thread_runtime() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let file_name = "inside_runtime.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+        assert!(!file_system.exists(file_name)?);
+
+        Ok(())
+    }
 }
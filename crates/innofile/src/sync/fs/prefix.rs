@@ -0,0 +1,152 @@
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        prefix::{relative, resolve},
+        FileStatus,
+    },
+};
+
+use super::{File, FileSystem};
+
+/// Roots a [`FileSystem`] at a directory or URI prefix, such as `s3://bucket/tenant-42/`.
+///
+/// Paths are resolved relative to the root, or may be given in full under it. Paths escaping
+/// the root with `..` segments are rejected.
+#[derive(Debug)]
+pub struct PrefixFS<F> {
+    inner: F,
+    root: String,
+}
+
+impl<F: FileSystem> PrefixFS<F> {
+    pub fn new(inner: F, root: impl ToString) -> Self {
+        Self {
+            inner,
+            root: root.to_string(),
+        }
+    }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    /// Resolves `path` to the path it stands for in the inner file system.
+    pub fn resolve(&self, path: &str) -> InnoFileResult<String> {
+        resolve(&self.root, path)
+    }
+}
+
+impl<F: FileSystem> FileSystem for PrefixFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(&self.resolve(path)?)
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.open(&self.resolve(path)?)
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.create(&self.resolve(path)?)
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.create_new(&self.resolve(path)?)
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.create_atomic(&self.resolve(path)?)
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.append(&self.resolve(path)?)
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(&self.resolve(path)?)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir_all(&self.resolve(path)?)
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(&self.resolve(path)?)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir_all(&self.resolve(path)?)
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_file(&self.resolve(path)?)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let statuses = self.inner.list(&self.resolve(path)?)?;
+        Ok(statuses
+            .into_iter()
+            .map(|status| FileStatus {
+                path: relative(&self.root, &status.path),
+                ..status
+            })
+            .collect())
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let status = self.inner.status(&self.resolve(path)?)?;
+        Ok(FileStatus {
+            path: path.to_string(),
+            ..status
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use tempfile::tempdir;
+
+    use crate::{error::InnoFileError, sync::fs::local::LocalFS};
+
+    use super::*;
+
+    #[test]
+    fn test_prefix_fs() -> InnoFileResult<()> {
+        let content = "Hello, PrefixFS!\n";
+
+        let dir = tempdir()?;
+        let root = dir.path().join("tenant-42");
+        let file_system = PrefixFS::new(LocalFS, root.to_str().unwrap());
+        file_system
+            .create_new("data/../prefix_fs.txt")?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let mut buf = String::new();
+        let path = root.join("prefix_fs.txt");
+        let path = path.to_str().unwrap();
+        LocalFS.open(path)?.reader()?.read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+        assert!(file_system.exists(path)?);
+        assert!(file_system.exists("/prefix_fs.txt")?);
+
+        let statuses = file_system.list("/")?;
+        let paths = statuses.iter().map(|s| s.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["prefix_fs.txt"]);
+        assert!(file_system.exists(paths[0])?);
+
+        let error = file_system.open("../tenant-43/prefix_fs.txt").unwrap_err();
+        assert!(matches!(error, InnoFileError::PathOutsideRoot { .. }));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use std::ops::Range;
+
+use bytes::Bytes;
+
+use crate::{
+    error::{ErrorContext, InnoFileError, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+/// Exposes a [`FileSystem`] for reading only, failing any operation that would modify it with
+/// [`InnoFileError::ReadOnlyFileSystem`].
+#[derive(Debug)]
+pub struct ReadOnlyFS<F> {
+    inner: F,
+}
+
+impl<F: FileSystem> ReadOnlyFS<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    fn reject<T>(&self, operation: Operation, path: &str) -> InnoFileResult<T> {
+        reject(operation, path, self.scheme())
+    }
+}
+
+impl<F: FileSystem> FileSystem for ReadOnlyFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(path)
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        Ok(Box::new(ReadOnlyFile {
+            inner: self.inner.open(path)?,
+            scheme: self.scheme().to_string(),
+        }))
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Create, path)
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Create, path)
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Create, path)
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Append, path)
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Create, path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Create, path)
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Remove, path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Remove, path)
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Remove, path)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.inner.list(path)
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.inner.status(path)
+    }
+}
+
+#[derive(Debug)]
+pub struct ReadOnlyFile {
+    inner: Box<dyn File>,
+    scheme: String,
+}
+
+impl File for ReadOnlyFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        self.inner.metadata()
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        self.inner.reader()
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        reject(Operation::Write, self.path(), &self.scheme)
+    }
+
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        self.inner.read_range(range)
+    }
+
+    fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        self.inner.read_ranges(ranges)
+    }
+}
+
+fn reject<T>(operation: Operation, path: &str, scheme: &str) -> InnoFileResult<T> {
+    Err(InnoFileError::ReadOnlyFileSystem).with_context(|| {
+        ErrorContext::new(operation)
+            .with_path(path)
+            .with_scheme(scheme)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Read as _, Write as _};
+
+    use tempfile::tempdir;
+
+    use crate::sync::fs::local::LocalFS;
+
+    use super::*;
+
+    #[test]
+    fn test_read_only_fs() -> InnoFileResult<()> {
+        let content = "Hello, ReadOnlyFS!\n";
+
+        let path = tempdir()?.path().join("read_only_fs.txt");
+        let path = path.to_str().unwrap();
+        LocalFS
+            .create_new(path)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let file_system = ReadOnlyFS::new(LocalFS);
+        let file = file_system.open(path)?;
+        let mut buf = String::new();
+        file.reader()?.read_to_string(&mut buf)?;
+        assert_eq!(buf, content);
+
+        let errors = [
+            file.writer().map(|_| ()),
+            file_system.create(path).map(|_| ()),
+            file_system.append(path).map(|_| ()),
+            file_system.remove_file(path),
+        ];
+        for error in errors {
+            let error = error.unwrap_err();
+            assert!(matches!(error.root(), InnoFileError::ReadOnlyFileSystem));
+            assert!(error.is_permission_denied());
+        }
+        assert!(file_system.exists(path)?);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    io::{BufReader, Read, Seek, SeekFrom},
+    ops::Range,
+    sync::Arc,
+};
+
+use bytes::Bytes;
+
+use crate::{
+    error::{InnoFileError, InnoFileResult},
+    fs::{retry::RetryPolicy, FileStatus},
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+/// Retries the operations of a [`FileSystem`] and its files that fail with transient errors,
+/// according to a [`RetryPolicy`].
+///
+/// Reads are resumed at the current offset on a reopened reader. Writes are not retried, since a
+/// partially written stream can't be safely repeated. Neither are operations that fail when
+/// repeated after an attempt which took effect but whose response was lost, such as
+/// `create_new` or `remove_file`.
+#[derive(Debug)]
+pub struct RetryFS<F> {
+    inner: F,
+    policy: RetryPolicy,
+}
+
+impl<F: FileSystem> RetryFS<F> {
+    pub fn new(inner: F, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    fn make_file(&self, file: Box<dyn File>) -> Box<dyn File> {
+        Box::new(RetryFile::new(file, self.policy.clone()))
+    }
+}
+
+impl<F: FileSystem> FileSystem for RetryFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.policy.retry(|| self.inner.exists(path))
+    }
+
+    fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry(|| self.inner.open(path))?;
+        Ok(self.make_file(file))
+    }
+
+    fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry(|| self.inner.create(path))?;
+        Ok(self.make_file(file))
+    }
+
+    fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.inner.create_new(path)?;
+        Ok(self.make_file(file))
+    }
+
+    fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry(|| self.inner.create_atomic(path))?;
+        Ok(self.make_file(file))
+    }
+
+    fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry(|| self.inner.append(path))?;
+        Ok(self.make_file(file))
+    }
+
+    fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(path)
+    }
+
+    fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.policy.retry(|| self.inner.create_dir_all(path))
+    }
+
+    fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(path)
+    }
+
+    fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.policy.retry(|| self.inner.remove_dir_all(path))
+    }
+
+    fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_file(path)
+    }
+
+    fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.policy.retry(|| self.inner.list(path))
+    }
+
+    fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.policy.retry(|| self.inner.status(path))
+    }
+}
+
+#[derive(Debug)]
+pub struct RetryFile {
+    inner: Arc<dyn File>,
+    policy: RetryPolicy,
+}
+
+impl RetryFile {
+    pub fn new(inner: impl Into<Arc<dyn File>>, policy: RetryPolicy) -> Self {
+        Self {
+            inner: inner.into(),
+            policy,
+        }
+    }
+}
+
+impl File for RetryFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        self.policy.retry(|| self.inner.metadata())
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        let reader = self.policy.retry(|| self.inner.reader())?;
+        Ok(Box::new(BufReader::new(RetryReader {
+            file: Arc::clone(&self.inner),
+            inner: reader,
+            pos: 0,
+            policy: self.policy.clone(),
+        })))
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        self.policy.retry(|| self.inner.writer())
+    }
+
+    fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        self.policy.retry(|| self.inner.read_range(range.clone()))
+    }
+
+    fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        self.policy.retry(|| self.inner.read_ranges(ranges))
+    }
+}
+
+/// Reader that reopens its file and seeks back to the current offset when a read fails with a
+/// transient error.
+///
+/// Buffered by a [`BufReader`] rather than by the inner reader, so that a buffer is only filled by
+/// a read that succeeded.
+#[derive(Debug)]
+struct RetryReader {
+    file: Arc<dyn File>,
+    inner: Box<dyn FileRead>,
+    pos: u64,
+    policy: RetryPolicy,
+}
+
+impl RetryReader {
+    /// Replaces the inner reader after `error` failed the given attempt, if the policy allows
+    /// another one.
+    fn resume(&mut self, error: std::io::Error, attempt: &mut u32) -> std::io::Result<()> {
+        let mut error = InnoFileError::from(error);
+        loop {
+            if !self.policy.should_retry(&error, *attempt) {
+                return Err(error.into());
+            }
+            std::thread::sleep(self.policy.backoff(*attempt));
+            *attempt += 1;
+            match self.reopen() {
+                Ok(inner) => {
+                    self.inner = inner;
+                    return Ok(());
+                }
+                Err(reopen_error) => error = reopen_error,
+            }
+        }
+    }
+
+    fn reopen(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        let mut inner = self.file.reader()?;
+        inner.seek(SeekFrom::Start(self.pos))?;
+        Ok(inner)
+    }
+}
+
+impl Read for RetryReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut attempt = 1;
+        loop {
+            match self.inner.read(buf) {
+                Ok(len) => {
+                    self.pos += len as u64;
+                    return Ok(len);
+                }
+                Err(error) => self.resume(error, &mut attempt)?,
+            }
+        }
+    }
+}
+
+impl Seek for RetryReader {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        self.pos = self.inner.seek(pos)?;
+        Ok(self.pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{ErrorKind, Write as _},
+        time::Duration,
+    };
+
+    use tempfile::tempdir;
+
+    use crate::{
+        fs::faulty::FaultPolicy,
+        sync::fs::{faulty::FaultyFS, local::LocalFS},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_retry_fs() -> InnoFileResult<()> {
+        let content = "Hello, RetryFS! Reads resume where they failed.\n";
+
+        let path = tempdir()?.path().join("retry_fs.txt");
+        let path = path.to_str().unwrap();
+        LocalFS
+            .create_new(path)?
+            .writer()?
+            .write_all(content.as_bytes())?;
+
+        let faults = FaultPolicy::new().with_failure_every(3).with_short_reads(4);
+        let policy = RetryPolicy::new().with_initial_backoff(Duration::from_millis(1));
+        let file_system = RetryFS::new(FaultyFS::new(LocalFS, faults.clone()), policy);
+        for _ in 0..3 {
+            let mut buf = String::new();
+            file_system.open(path)?.reader()?.read_to_string(&mut buf)?;
+            assert_eq!(buf, content);
+        }
+
+        let file_system = RetryFS::new(FaultyFS::new(LocalFS, faults), RetryPolicy::none());
+        let mut buf = String::new();
+        let error = file_system.open(path)?.reader()?.read_to_string(&mut buf);
+        assert_eq!(error.unwrap_err().kind(), ErrorKind::ConnectionReset);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_retry_fs_non_idempotent() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("retry_fs.txt");
+        let path = path.to_str().unwrap();
+
+        let faults = FaultPolicy::new().with_failure_every(1);
+        let policy = RetryPolicy::new().with_initial_backoff(Duration::from_millis(1));
+        let file_system = RetryFS::new(FaultyFS::new(LocalFS, faults), policy);
+        let error = file_system.create_new(path).unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::ConnectionReset));
+        let error = file_system.remove_file(path).unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::ConnectionReset));
+        assert_eq!(file_system.into_inner().calls(), 2);
+
+        Ok(())
+    }
+}
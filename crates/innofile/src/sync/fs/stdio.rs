@@ -0,0 +1,321 @@
+use std::{
+    fmt::{Debug, Formatter},
+    io::{self, BufReader, BufWriter, ErrorKind, Read, Seek, SeekFrom, Write},
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{error::InnoFileResult, sync::io::Closeable};
+
+use super::{File, FileRead, FileWrite, Metadata};
+
+/// Path standing for standard input or output.
+pub const STDIO_PATH: &str = "-";
+
+/// Length of the content kept in memory to read again, enough to infer schemas from.
+const MAX_RECORDED_LEN: usize = 16 << 20;
+
+/// Standard input, or another stream, as a file read front to back.
+///
+/// Its readers share the stream. Content read before the first seek back is kept in memory, up to
+/// [`MAX_RECORDED_LEN`] bytes, so that readers can rewind once to read it again, such as after
+/// inferring a schema from it.
+#[derive(Debug, Clone)]
+pub struct StdinFile(Arc<Mutex<Replay>>);
+
+impl Default for StdinFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StdinFile {
+    pub fn new() -> Self {
+        Self::from_reader(io::stdin())
+    }
+
+    pub fn from_reader(reader: impl Read + Send + 'static) -> Self {
+        Self::with_recorded_len(reader, MAX_RECORDED_LEN)
+    }
+
+    fn with_recorded_len(reader: impl Read + Send + 'static, max_recorded_len: usize) -> Self {
+        Self(Arc::new(Mutex::new(Replay {
+            source: Box::new(reader),
+            buffer: vec![],
+            max_recorded_len,
+            position: 0,
+            mode: Mode::Recording,
+        })))
+    }
+}
+
+impl File for StdinFile {
+    fn path(&self) -> &str {
+        STDIO_PATH
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        Err(io::Error::new(
+            ErrorKind::Unsupported,
+            "Metadata of standard streams not supported",
+        ))?
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        Ok(Box::new(BufReader::new(StdinReader(Arc::clone(&self.0)))))
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        Err(io::Error::new(
+            ErrorKind::Unsupported,
+            "Writing to standard input not supported",
+        ))?
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    /// Content read from the source is kept in the buffer, until it grows too long.
+    Recording,
+    /// Content is read from the buffer until the end of it.
+    Replaying,
+    /// Content is read from the source only, without seeking back.
+    Streaming,
+}
+
+struct Replay {
+    source: Box<dyn Read + Send>,
+    buffer: Vec<u8>,
+    max_recorded_len: usize,
+    position: u64,
+    mode: Mode,
+}
+
+impl Debug for Replay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Replay")
+            .field("buffer", &self.buffer.len())
+            .field("position", &self.position)
+            .field("mode", &self.mode)
+            .finish()
+    }
+}
+
+impl Read for Replay {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.mode == Mode::Replaying {
+            let start = self.position as usize;
+            if start < self.buffer.len() {
+                let len = buf.len().min(self.buffer.len() - start);
+                buf[..len].copy_from_slice(&self.buffer[start..start + len]);
+                self.position += len as u64;
+                return Ok(len);
+            }
+            self.mode = Mode::Streaming;
+            self.buffer = vec![];
+        }
+        let len = self.source.read(buf)?;
+        if self.mode == Mode::Recording {
+            match self.buffer.len() + len > self.max_recorded_len {
+                true => {
+                    self.mode = Mode::Streaming;
+                    self.buffer = vec![];
+                }
+                false => self.buffer.extend_from_slice(&buf[..len]),
+            }
+        }
+        self.position += len as u64;
+        Ok(len)
+    }
+}
+
+impl Seek for Replay {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+            SeekFrom::End(_) => None,
+        };
+        match position {
+            Some(position) if position == self.position => Ok(position),
+            Some(position)
+                if self.mode != Mode::Streaming && position <= self.buffer.len() as u64 =>
+            {
+                self.position = position;
+                self.mode = Mode::Replaying;
+                Ok(position)
+            }
+            _ => Err(io::Error::new(
+                ErrorKind::Unsupported,
+                "Seeking standard input only supported back to content read before",
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct StdinReader(Arc<Mutex<Replay>>);
+
+impl StdinReader {
+    fn lock(&self) -> MutexGuard<'_, Replay> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Read for StdinReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.lock().read(buf)
+    }
+}
+
+impl Seek for StdinReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.lock().seek(pos)
+    }
+}
+
+/// Standard output, or another stream, as a file written front to back.
+#[derive(Clone)]
+pub struct StdoutFile(Arc<Mutex<Box<dyn Write + Send>>>);
+
+impl Debug for StdoutFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("StdoutFile").finish()
+    }
+}
+
+impl Default for StdoutFile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StdoutFile {
+    pub fn new() -> Self {
+        Self::from_writer(io::stdout())
+    }
+
+    pub fn from_writer(writer: impl Write + Send + 'static) -> Self {
+        Self(Arc::new(Mutex::new(Box::new(writer))))
+    }
+}
+
+impl File for StdoutFile {
+    fn path(&self) -> &str {
+        STDIO_PATH
+    }
+
+    fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        Err(io::Error::new(
+            ErrorKind::Unsupported,
+            "Metadata of standard streams not supported",
+        ))?
+    }
+
+    fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        Err(io::Error::new(
+            ErrorKind::Unsupported,
+            "Reading from standard output not supported",
+        ))?
+    }
+
+    fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        Ok(Box::new(BufWriter::new(StdoutWriter(Arc::clone(&self.0)))))
+    }
+}
+
+struct StdoutWriter(Arc<Mutex<Box<dyn Write + Send>>>);
+
+impl Debug for StdoutWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("StdoutWriter").finish()
+    }
+}
+
+impl Write for StdoutWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .flush()
+    }
+}
+
+// Flushes the stream, which stays open for other writers.
+impl Closeable for BufWriter<StdoutWriter> {
+    fn close(self) -> InnoFileResult<()> {
+        let mut writer = self.into_inner().map_err(|e| e.into_error())?;
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Stream shared by its clones, to check what is written to standard output in tests.
+#[cfg(test)]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SharedBuffer(pub(crate) Arc<Mutex<Vec<u8>>>);
+
+#[cfg(test)]
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufRead as _, Cursor};
+
+    use super::*;
+
+    #[test]
+    fn test_stdin_file() -> InnoFileResult<()> {
+        let file = StdinFile::from_reader(Cursor::new("first\nsecond\nthird\n"));
+
+        // content read before rewinding is replayed to the next reader
+        let mut line = String::new();
+        file.reader()?.read_line(&mut line)?;
+        assert_eq!(line, "first\n");
+        file.reader()?.rewind()?;
+        let mut content = String::new();
+        file.reader()?.read_to_string(&mut content)?;
+        assert_eq!(content, "first\nsecond\nthird\n");
+
+        let error = file.reader()?.rewind().unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Unsupported);
+        assert!(file.writer().is_err());
+        assert!(file.metadata().is_err());
+
+        // content is no longer kept once longer than the recorded length
+        let file = StdinFile::with_recorded_len(Cursor::new("first\nsecond\nthird\n"), 8);
+        let mut content = String::new();
+        file.reader()?.read_to_string(&mut content)?;
+        assert_eq!(content, "first\nsecond\nthird\n");
+        assert!(file.reader()?.rewind().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_stdout_file() -> InnoFileResult<()> {
+        let buffer = SharedBuffer::default();
+        let file = StdoutFile::from_writer(buffer.clone());
+        let mut writer = file.writer()?;
+        writer.write_all(b"content\n")?;
+        writer.close()?;
+        assert_eq!(buffer.0.lock().unwrap().as_slice(), b"content\n");
+        assert!(file.reader().is_err());
+
+        Ok(())
+    }
+}
@@ -3,11 +3,23 @@ use crate::error::InnoFileResult;
 /// The supertrait of [`Closeable`] to close on `Box<Self>`.
 pub trait CloseableBoxed {
     fn close_boxed(self: Box<Self>) -> InnoFileResult<()>;
+
+    fn abort_boxed(self: Box<Self>) -> InnoFileResult<()>;
 }
 
 /// A trait for objects to close on `Self`.
 pub trait Closeable: CloseableBoxed {
     fn close(self) -> InnoFileResult<()>;
+
+    /// Discards what was written instead of completing it, where supported. Only drops `self` by
+    /// default.
+    fn abort(self) -> InnoFileResult<()>
+    where
+        Self: Sized,
+    {
+        drop(self);
+        Ok(())
+    }
 }
 
 // Default implementation of [`CloseableBoxed`] trait for [`Closeable`] objects.
@@ -16,6 +28,10 @@ impl<T: Closeable> CloseableBoxed for T {
         // Dereferencing `Box<Self>` with `*self` to close on `Self`.
         (*self).close()
     }
+
+    fn abort_boxed(self: Box<Self>) -> InnoFileResult<()> {
+        (*self).abort()
+    }
 }
 
 // Implement [`Closeable`] trait for its `Box`ed types.
@@ -23,6 +39,10 @@ impl<T: Closeable + ?Sized> Closeable for Box<T> {
     fn close(self) -> InnoFileResult<()> {
         self.close_boxed()
     }
+
+    fn abort(self) -> InnoFileResult<()> {
+        self.abort_boxed()
+    }
 }
 
 #[cfg(test)]
@@ -45,6 +65,9 @@ mod tests {
         let boxed_closer = Box::new(Closer) as Box<dyn Closeable>;
         assert!(boxed_closer.close().is_ok());
 
+        let boxed_closer = Box::new(Closer) as Box<dyn Closeable>;
+        assert!(boxed_closer.abort().is_ok());
+
         Ok(())
     }
 }
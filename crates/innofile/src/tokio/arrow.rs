@@ -1,6 +1,12 @@
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+
 use arrow::{array::RecordBatch, datatypes::SchemaRef};
 use async_trait::async_trait;
-use futures::Stream;
+use futures::{Stream, StreamExt as _};
 
 use crate::{error::InnoFileResult, tokio::io::Closeable};
 
@@ -12,10 +18,66 @@ pub mod json;
 pub mod orc;
 #[cfg(feature = "parquet")]
 pub mod parquet;
+#[cfg(any(feature = "orc", feature = "parquet"))]
+pub mod range;
 
 #[async_trait]
-pub trait ArrowReader: Stream<Item = InnoFileResult<RecordBatch>> + Unpin {
+pub trait ArrowReader: Stream<Item = InnoFileResult<RecordBatch>> + Unpin + Send {
     fn schema(&self) -> SchemaRef;
+
+    /// Number of rows from the file metadata, if known without reading the file.
+    fn num_rows(&self) -> Option<usize> {
+        None
+    }
+}
+
+#[async_trait]
+impl<R: ArrowReader + ?Sized> ArrowReader for Box<R> {
+    fn schema(&self) -> SchemaRef {
+        (**self).schema()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        (**self).num_rows()
+    }
+}
+
+/// Reads the columns at `indices` of another reader, in the order given.
+pub(crate) struct ProjectedReader<R> {
+    inner: R,
+    indices: Vec<usize>,
+    schema: SchemaRef,
+}
+
+impl<R: ArrowReader> ProjectedReader<R> {
+    pub(crate) fn new(inner: R, indices: Vec<usize>) -> InnoFileResult<Self> {
+        let schema = Arc::new(inner.schema().project(&indices)?);
+        Ok(Self {
+            inner,
+            indices,
+            schema,
+        })
+    }
+}
+
+impl<R: ArrowReader> Stream for ProjectedReader<R> {
+    type Item = InnoFileResult<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let batch = ready!(self.inner.poll_next_unpin(cx));
+        Poll::Ready(batch.map(|batch| Ok(batch?.project(&self.indices)?)))
+    }
+}
+
+#[async_trait]
+impl<R: ArrowReader> ArrowReader for ProjectedReader<R> {
+    fn schema(&self) -> SchemaRef {
+        Arc::clone(&self.schema)
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        self.inner.num_rows()
+    }
 }
 
 #[async_trait]
@@ -30,6 +92,13 @@ pub trait ArrowWriter: Closeable + Unpin {
     async fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()>;
 }
 
+#[async_trait]
+impl<W: ArrowWriter + ?Sized> ArrowWriter for Box<W> {
+    async fn write(&mut self, batch: &RecordBatch) -> InnoFileResult<()> {
+        (**self).write(batch).await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -87,6 +156,16 @@ mod tests {
             assert_eq!(record_batch, batch);
         }
 
+        let file: FileRef = Arc::from(file_system.open(path).await?);
+        let columns = vec!["name".to_string(), "id".to_string()];
+        let mut arrow_reader = ArrowReaderBuilder::new()
+            .with_schema(read_with_schema.then(|| Arc::clone(&schema)))
+            .with_columns(Some(columns))
+            .build_async(file)
+            .await?;
+        let record_batch = arrow_reader.next().await.unwrap()?;
+        assert_eq!(record_batch, batch.project(&[1, 0])?);
+
         Ok(())
     }
 }
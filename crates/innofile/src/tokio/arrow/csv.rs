@@ -8,7 +8,8 @@ use async_trait::async_trait;
 use futures::Stream;
 
 use crate::{
-    error::InnoFileResult,
+    arrow::DEFAULT_INFER_RECORDS,
+    error::{InnoFileError, InnoFileResult},
     tokio::{fs::FileRef, io::Closeable},
 };
 
@@ -21,11 +22,23 @@ impl ArrowCsvReader {
         file: FileRef,
         file_format: impl AsRef<str>,
         schema: Option<SchemaRef>,
+    ) -> InnoFileResult<Self> {
+        Self::with_infer_records(file, file_format, schema, DEFAULT_INFER_RECORDS).await
+    }
+
+    /// Creates a reader inferring the schema from `infer_records` records, when no schema is
+    /// given.
+    pub async fn with_infer_records(
+        file: FileRef,
+        file_format: impl AsRef<str>,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
     ) -> InnoFileResult<Self> {
         _ = file;
         _ = file_format;
         _ = schema;
-        todo!("ArrowCsvReader::new")
+        _ = infer_records;
+        todo!("ArrowCsvReader::with_infer_records")
     }
 }
 
@@ -53,6 +66,13 @@ impl ArrowCsvWriter {
         _ = file_format;
         todo!("ArrowCsvWriter::new")
     }
+
+    pub async fn new_append(file: FileRef, file_format: impl AsRef<str>) -> InnoFileResult<Self> {
+        _ = file;
+        Err(InnoFileError::AppendNotSupported(
+            file_format.as_ref().to_string(),
+        ))
+    }
 }
 
 #[async_trait]
@@ -72,10 +92,41 @@ impl ArrowWriter for ArrowCsvWriter {
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::{DataType, Field, Schema};
+    use tempfile::tempdir;
+
+    use crate::{
+        arrow::{ArrowWriterBuilder, CsvOptions},
+        tokio::fs::{local::LocalFS, FileSystem as _},
+    };
+
     use super::*;
 
     #[tokio::test]
     async fn test_arrow_read_write() -> InnoFileResult<()> {
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_unsupported_options() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("options.csv");
+        let file = Arc::from(LocalFS.create(path.to_str().unwrap()).await?);
+
+        // other dialects than the default one are not silently ignored
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let result = ArrowWriterBuilder::new(schema)
+            .with_csv_options(Some(CsvOptions::new().with_delimiter(b';')))
+            .build_async(file)
+            .await;
+        let error = result.err().unwrap();
+        assert!(matches!(
+            error.root(),
+            InnoFileError::OptionNotSupported { .. }
+        ));
+
+        Ok(())
+    }
 }
@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use futures::Stream;
 
 use crate::{
+    arrow::DEFAULT_INFER_RECORDS,
     error::InnoFileResult,
     tokio::{fs::FileRef, io::Closeable},
 };
@@ -18,9 +19,20 @@ pub struct ArrowJsonReader;
 
 impl ArrowJsonReader {
     pub async fn new(file: FileRef, schema: Option<SchemaRef>) -> InnoFileResult<Self> {
+        Self::with_infer_records(file, schema, DEFAULT_INFER_RECORDS).await
+    }
+
+    /// Creates a reader inferring the schema from `infer_records` records, when no schema is
+    /// given.
+    pub async fn with_infer_records(
+        file: FileRef,
+        schema: Option<SchemaRef>,
+        infer_records: usize,
+    ) -> InnoFileResult<Self> {
         _ = file;
         _ = schema;
-        todo!("ArrowJsonReader::new")
+        _ = infer_records;
+        todo!("ArrowJsonReader::with_infer_records")
     }
 }
 
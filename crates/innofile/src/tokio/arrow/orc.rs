@@ -1,31 +1,102 @@
 use std::{
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
 };
 
-use arrow::{array::RecordBatch, datatypes::SchemaRef};
+use arrow::{array::RecordBatch, datatypes::SchemaRef, error::ArrowError};
 use async_trait::async_trait;
-use futures::{Stream, StreamExt as _};
-use orc_rust::{ArrowReaderBuilder as InnerReaderBuilder, ArrowStreamReader as InnerReader};
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt as _, Stream, StreamExt as _};
+use orc_rust::{
+    projection::ProjectionMask,
+    reader::{metadata::FileMetadata, AsyncChunkReader},
+    ArrowReaderBuilder as InnerReaderBuilder, ArrowStreamReader as InnerReader,
+};
 
 use crate::{
     error::InnoFileResult,
-    tokio::{
-        fs::{FileRead, FileRef},
-        io::Closeable,
-    },
+    tokio::{fs::FileRef, io::Closeable},
 };
 
-use super::{ArrowReader, ArrowWriter};
+use super::{range::RangeReader, ArrowReader, ArrowWriter};
 
-pub struct ArrowOrcReader(InnerReader<Box<dyn FileRead>>);
+pub struct ArrowOrcReader {
+    inner: InnerReader<FileReader>,
+    metadata: FileMetadata,
+}
 
 impl ArrowOrcReader {
     pub async fn new(file: FileRef) -> InnoFileResult<Self> {
-        let inner_reader = InnerReaderBuilder::try_new_async(file.reader().await?)
-            .await?
-            .build_async();
-        Ok(Self(inner_reader))
+        Self::build(file, None).await
+    }
+
+    /// Reads only the columns at `projection`, in the order of the file.
+    pub async fn with_projection(file: FileRef, projection: &[usize]) -> InnoFileResult<Self> {
+        Self::build(file, Some(projection)).await
+    }
+
+    async fn build(file: FileRef, projection: Option<&[usize]>) -> InnoFileResult<Self> {
+        let range_reader = Arc::new(RangeReader::new(file));
+        let mut builder =
+            InnerReaderBuilder::try_new_async(FileReader(Arc::clone(&range_reader))).await?;
+        match projection {
+            Some(projection) => {
+                let children = builder.file_metadata().root_data_type().children();
+                let names = projection
+                    .iter()
+                    .map(|i| match children.get(*i) {
+                        Some(column) => Ok(column.name().to_string()),
+                        None => Err(ArrowError::SchemaError(format!(
+                            "Column index {i} out of range"
+                        ))),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let root_data_type = builder.file_metadata().root_data_type();
+                let mask = ProjectionMask::named_roots(root_data_type, &names);
+                builder = builder.with_projection(mask);
+            }
+            // prefetch the streams of each stripe together, only when all of them are read since
+            // the ranges of the streams of each column are only known from stripe footers
+            None => range_reader.set_prefetch_groups(
+                builder
+                    .file_metadata()
+                    .stripe_metadatas()
+                    .iter()
+                    .map(|stripe| {
+                        let end = stripe.footer_offset() + stripe.footer_length();
+                        std::iter::once(stripe.offset()..end).collect()
+                    }),
+            ),
+        }
+        Ok(Self {
+            metadata: builder.file_metadata().clone(),
+            inner: builder.build_async(),
+        })
+    }
+
+    pub fn metadata(&self) -> &FileMetadata {
+        &self.metadata
+    }
+}
+
+pub struct FileReader(Arc<RangeReader>);
+
+impl AsyncChunkReader for FileReader {
+    fn len(&mut self) -> BoxFuture<'_, std::io::Result<u64>> {
+        async move { Ok(self.0.len().await?) }.boxed()
+    }
+
+    fn get_bytes(
+        &mut self,
+        offset_from_start: u64,
+        length: u64,
+    ) -> BoxFuture<'_, std::io::Result<Bytes>> {
+        async move {
+            let range = offset_from_start..offset_from_start + length;
+            Ok(self.0.get_bytes(range).await?)
+        }
+        .boxed()
     }
 }
 
@@ -33,14 +104,18 @@ impl Stream for ArrowOrcReader {
     type Item = InnoFileResult<RecordBatch>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.0.poll_next_unpin(cx).map_err(|e| e.into())
+        self.inner.poll_next_unpin(cx).map_err(|e| e.into())
     }
 }
 
 #[async_trait]
 impl ArrowReader for ArrowOrcReader {
     fn schema(&self) -> SchemaRef {
-        self.0.schema()
+        self.inner.schema()
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        usize::try_from(self.metadata.number_of_rows()).ok()
     }
 }
 
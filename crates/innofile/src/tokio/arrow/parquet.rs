@@ -1,4 +1,5 @@
 use std::{
+    ops::Range,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
@@ -6,30 +7,118 @@ use std::{
 
 use arrow::{array::RecordBatch, datatypes::SchemaRef};
 use async_trait::async_trait;
-use futures::{Stream, StreamExt as _};
-use parquet::arrow::{
-    async_reader::ParquetRecordBatchStream, AsyncArrowWriter as InnerWriter,
-    ParquetRecordBatchStreamBuilder,
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt as _, Stream, StreamExt as _};
+use parquet::{
+    arrow::{
+        async_reader::{AsyncFileReader, ParquetRecordBatchStream},
+        async_writer::AsyncFileWriter,
+        AsyncArrowWriter as InnerWriter, ParquetRecordBatchStreamBuilder, ProjectionMask,
+    },
+    errors::ParquetError,
+    file::{
+        metadata::{ParquetMetaData, ParquetMetaDataReader},
+        properties::WriterProperties,
+    },
 };
+use tokio::io::AsyncWriteExt as _;
 
 use crate::{
     error::InnoFileResult,
     tokio::{
-        fs::{FileRead, FileRef, FileWrite},
+        fs::{FileRef, FileWrite},
         io::Closeable,
     },
+    utils::range::FOOTER_SIZE,
 };
 
-use super::{ArrowReader, ArrowWriter};
+use super::{range::RangeReader, ArrowReader, ArrowWriter};
 
-pub struct ArrowParquetReader(ParquetRecordBatchStream<Box<dyn FileRead>>);
+pub struct ArrowParquetReader {
+    inner: ParquetRecordBatchStream<FileReader>,
+    metadata: Arc<ParquetMetaData>,
+}
 
 impl ArrowParquetReader {
     pub async fn new(file: FileRef) -> InnoFileResult<Self> {
-        let inner_reader = ParquetRecordBatchStreamBuilder::new(file.reader().await?)
-            .await?
-            .build()?;
-        Ok(Self(inner_reader))
+        Self::build(file, None).await
+    }
+
+    /// Reads only the columns at `projection`, in the order of the file.
+    pub async fn with_projection(file: FileRef, projection: &[usize]) -> InnoFileResult<Self> {
+        Self::build(file, Some(projection)).await
+    }
+
+    async fn build(file: FileRef, projection: Option<&[usize]>) -> InnoFileResult<Self> {
+        let mut builder = ParquetRecordBatchStreamBuilder::new(FileReader::new(file)).await?;
+        if let Some(projection) = projection {
+            let mask = ProjectionMask::roots(builder.parquet_schema(), projection.iter().copied());
+            builder = builder.with_projection(mask);
+        }
+        let metadata = Arc::clone(builder.metadata());
+        Ok(Self {
+            inner: builder.build()?,
+            metadata,
+        })
+    }
+
+    pub fn metadata(&self) -> &Arc<ParquetMetaData> {
+        &self.metadata
+    }
+}
+
+pub struct FileReader {
+    inner: Arc<RangeReader>,
+    metadata: Option<Arc<ParquetMetaData>>,
+}
+
+impl FileReader {
+    pub fn new(file: FileRef) -> Self {
+        Self {
+            inner: Arc::new(RangeReader::new(file)),
+            metadata: None,
+        }
+    }
+}
+
+impl AsyncFileReader for FileReader {
+    fn get_bytes(&mut self, range: Range<usize>) -> BoxFuture<'_, parquet::errors::Result<Bytes>> {
+        async move {
+            let range = range.start as u64..range.end as u64;
+            Ok(self.inner.get_bytes(range).await?)
+        }
+        .boxed()
+    }
+
+    // The column chunks of a row group are requested together, so they are coalesced and
+    // fetched concurrently.
+    fn get_byte_ranges(
+        &mut self,
+        ranges: Vec<Range<usize>>,
+    ) -> BoxFuture<'_, parquet::errors::Result<Vec<Bytes>>> {
+        async move {
+            let ranges = ranges
+                .into_iter()
+                .map(|range| range.start as u64..range.end as u64)
+                .collect::<Vec<_>>();
+            Ok(self.inner.get_ranges(&ranges).await?)
+        }
+        .boxed()
+    }
+
+    fn get_metadata(&mut self) -> BoxFuture<'_, parquet::errors::Result<Arc<ParquetMetaData>>> {
+        async move {
+            if let Some(metadata) = &self.metadata {
+                return Ok(Arc::clone(metadata));
+            }
+            let len = self.inner.len().await?;
+            let metadata = ParquetMetaDataReader::new()
+                .with_prefetch_hint(Some(FOOTER_SIZE as usize))
+                .load_and_finish(&mut *self, len as usize)
+                .await?;
+            Ok(Arc::clone(self.metadata.insert(Arc::new(metadata))))
+        }
+        .boxed()
     }
 }
 
@@ -37,26 +126,74 @@ impl Stream for ArrowParquetReader {
     type Item = InnoFileResult<RecordBatch>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.0.poll_next_unpin(cx).map_err(|e| e.into())
+        self.inner.poll_next_unpin(cx).map_err(|e| e.into())
     }
 }
 
 #[async_trait]
 impl ArrowReader for ArrowParquetReader {
     fn schema(&self) -> SchemaRef {
-        Arc::clone(self.0.schema())
+        Arc::clone(self.inner.schema())
+    }
+
+    fn num_rows(&self) -> Option<usize> {
+        usize::try_from(self.metadata.file_metadata().num_rows()).ok()
     }
 }
 
-pub struct ArrowParquetWriter(InnerWriter<Box<dyn FileWrite>>);
+pub struct ArrowParquetWriter(InnerWriter<FileWriter>);
 
 impl ArrowParquetWriter {
     pub async fn new(file: FileRef, schema: SchemaRef) -> InnoFileResult<Self> {
-        let inner_writer = InnerWriter::try_new(file.writer().await?, schema, None)?;
+        Self::with_properties(file, schema, WriterProperties::default()).await
+    }
+
+    /// Creates a writer of row groups and column chunks configured by `properties`.
+    pub async fn with_properties(
+        file: FileRef,
+        schema: SchemaRef,
+        properties: WriterProperties,
+    ) -> InnoFileResult<Self> {
+        let file_writer = FileWriter(Some(file.writer().await?));
+        let inner_writer = InnerWriter::try_new(file_writer, schema, Some(properties))?;
         Ok(Self(inner_writer))
     }
 }
 
+/// Closes the file writer on completion, instead of merely shutting it down.
+pub struct FileWriter(Option<Box<dyn FileWrite>>);
+
+impl FileWriter {
+    fn inner(&mut self) -> std::io::Result<&mut Box<dyn FileWrite>> {
+        self.0
+            .as_mut()
+            .ok_or_else(|| std::io::Error::other("Writer already closed"))
+    }
+}
+
+impl AsyncFileWriter for FileWriter {
+    fn write(&mut self, bs: Bytes) -> BoxFuture<'_, parquet::errors::Result<()>> {
+        async move {
+            self.inner()?.write_all(&bs).await?;
+            Ok(())
+        }
+        .boxed()
+    }
+
+    fn complete(&mut self) -> BoxFuture<'_, parquet::errors::Result<()>> {
+        async move {
+            if let Some(inner) = self.0.take() {
+                inner
+                    .close()
+                    .await
+                    .map_err(|e| ParquetError::External(Box::new(e)))?;
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
+
 #[async_trait]
 impl Closeable for ArrowParquetWriter {
     async fn close(self) -> InnoFileResult<()> {
@@ -74,10 +211,40 @@ impl ArrowWriter for ArrowParquetWriter {
 
 #[cfg(test)]
 mod tests {
+    use arrow::{
+        array::Int64Array,
+        datatypes::{DataType, Field, Schema},
+    };
+    use tempfile::tempdir;
+
+    use crate::tokio::fs::{local::LocalFS, FileSystem as _};
+
     use super::{super::tests::write_then_read, *};
 
     #[tokio::test]
     async fn test_arrow_read_write() -> InnoFileResult<()> {
         write_then_read("parquet", false).await
     }
+
+    #[tokio::test]
+    async fn test_close_file_writer() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("close_file_writer.parquet");
+        let path = path.to_str().unwrap();
+
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int64, false)]));
+        let batch = RecordBatch::try_new(
+            Arc::clone(&schema),
+            vec![Arc::new(Int64Array::from(vec![1, 2]))],
+        )?;
+
+        // atomically created files are only published once their writer is closed
+        let file = Arc::from(LocalFS.create_atomic(path).await?);
+        let mut writer = ArrowParquetWriter::new(file, schema).await?;
+        writer.write(&batch).await?;
+        assert!(!LocalFS.exists(path).await?);
+        writer.close().await?;
+        assert!(LocalFS.exists(path).await?);
+
+        Ok(())
+    }
 }
@@ -0,0 +1,190 @@
+use std::{
+    ops::Range,
+    sync::{Mutex, OnceLock},
+};
+
+use bytes::Bytes;
+
+use crate::{
+    error::InnoFileResult,
+    tokio::fs::FileRef,
+    utils::range::{coalesce_ranges, Chunk, FOOTER_SIZE},
+};
+
+/// Reader of byte ranges of a [`FileRef`] for columnar formats.
+///
+/// It caches the file length and footer, merges nearby ranges into a single fetch, and prefetches
+/// groups of ranges read together, such as the streams of an ORC stripe, with one concurrent
+/// [`read_ranges`](crate::tokio::fs::File::read_ranges) call. Only the most recently prefetched
+/// group is kept in memory.
+#[derive(Debug)]
+pub struct RangeReader {
+    file: FileRef,
+    len: OnceLock<u64>,
+    footer: OnceLock<Chunk>,
+    prefetch_groups: OnceLock<Vec<Vec<Range<u64>>>>,
+    cache: Mutex<Vec<Chunk>>,
+}
+
+impl RangeReader {
+    pub fn new(file: FileRef) -> Self {
+        Self {
+            file,
+            len: OnceLock::new(),
+            footer: OnceLock::new(),
+            prefetch_groups: OnceLock::new(),
+            cache: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn file(&self) -> &FileRef {
+        &self.file
+    }
+
+    pub async fn len(&self) -> InnoFileResult<u64> {
+        if let Some(len) = self.len.get() {
+            return Ok(*len);
+        }
+        let len = self.file.metadata().await?.len();
+        Ok(*self.len.get_or_init(|| len))
+    }
+
+    /// Sets the groups of ranges to fetch together once any of their ranges is read.
+    pub fn set_prefetch_groups(&self, groups: impl IntoIterator<Item = Vec<Range<u64>>>) {
+        _ = self.prefetch_groups.set(groups.into_iter().collect());
+    }
+
+    /// Reads the bytes in `range`, from the cache where possible.
+    pub async fn get_bytes(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        if range.is_empty() {
+            return Ok(Bytes::new());
+        }
+        if let Some(bytes) = self.cached(range.clone()).await? {
+            return Ok(bytes);
+        }
+        self.file.read_range(range).await
+    }
+
+    /// Reads the bytes in each of `ranges`, merging nearby ranges and fetching them concurrently.
+    pub async fn get_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let chunks = self.fetch(ranges).await?;
+        ranges
+            .iter()
+            .map(|range| {
+                let bytes = chunks
+                    .iter()
+                    .find_map(|chunk| chunk.slice(range.start, Some(range.end)));
+                bytes.ok_or_else(|| unread(range).into())
+            })
+            .collect()
+    }
+
+    /// Looks up the bytes in `range`, fetching the footer or a prefetch group first if they
+    /// cover it.
+    async fn cached(&self, range: Range<u64>) -> InnoFileResult<Option<Bytes>> {
+        let (start, end) = (range.start, Some(range.end));
+        if let Some(bytes) = self.footer.get().and_then(|f| f.slice(start, end)) {
+            return Ok(Some(bytes));
+        }
+        if let Some(bytes) = self.cached_in_group(start, end) {
+            return Ok(Some(bytes));
+        }
+
+        let len = self.len().await?;
+        if len.saturating_sub(start) <= FOOTER_SIZE && range.end <= len {
+            let footer_start = len.saturating_sub(FOOTER_SIZE);
+            let data = self.file.read_range(footer_start..len).await?;
+            let footer = self.footer.get_or_init(|| Chunk::new(footer_start, data));
+            return Ok(footer.slice(start, end));
+        }
+
+        let group = self.prefetch_groups.get().and_then(|groups| {
+            groups
+                .iter()
+                .find(|group| group.iter().any(|r| r.contains(&start)))
+        });
+        if let Some(group) = group {
+            let chunks = self.fetch(group).await?;
+            let bytes = chunks.iter().find_map(|chunk| chunk.slice(start, end));
+            *self.cache.lock().unwrap_or_else(|e| e.into_inner()) = chunks;
+            return Ok(bytes);
+        }
+
+        Ok(None)
+    }
+
+    fn cached_in_group(&self, start: u64, end: Option<u64>) -> Option<Bytes> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .find_map(|chunk| chunk.slice(start, end))
+    }
+
+    async fn fetch(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Chunk>> {
+        let ranges = coalesce_ranges(ranges);
+        let data = self.file.read_ranges(&ranges).await?;
+        Ok(ranges
+            .into_iter()
+            .zip(data)
+            .map(|(range, data)| Chunk::new(range.start, data))
+            .collect())
+    }
+}
+
+/// Error of a range past the end of the file, which reads fewer bytes than requested.
+fn unread(range: &Range<u64>) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::UnexpectedEof,
+        format!(
+            "Range {}..{} past the end of the file",
+            range.start, range.end
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tempfile::tempdir;
+    use tokio::io::AsyncWriteExt as _;
+
+    use crate::tokio::fs::{local::LocalFS, FileSystem as _};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_range_reader() -> InnoFileResult<()> {
+        let content = (0..=u8::MAX).cycle().take(3 * FOOTER_SIZE as usize);
+        let content = content.collect::<Vec<_>>();
+
+        let path = tempdir()?.path().join("range_reader.bin");
+        let path = path.to_str().unwrap();
+        let file_system = LocalFS;
+        let mut writer = file_system.create_new(path).await?.writer().await?;
+        writer.write_all(&content).await?;
+        writer.shutdown().await?;
+
+        let range_reader = RangeReader::new(Arc::from(file_system.open(path).await?));
+        assert_eq!(range_reader.len().await?, content.len() as u64);
+        range_reader.set_prefetch_groups([vec![100..200, 300..400]]);
+
+        let len = content.len() as u64;
+        for range in [len - 8..len, 150..160, 350..400, 1000..1010] {
+            let expected = &content[range.start as usize..range.end as usize];
+            assert_eq!(range_reader.get_bytes(range).await?, expected);
+        }
+
+        let ranges = [500..600, 0..10];
+        let bytes = range_reader.get_ranges(&ranges).await?;
+        assert_eq!(bytes[0], &content[500..600]);
+        assert_eq!(bytes[1], &content[0..10]);
+        assert!(range_reader
+            .get_ranges(std::slice::from_ref(&(len - 8..len + 8)))
+            .await
+            .is_err());
+
+        Ok(())
+    }
+}
@@ -1,13 +1,26 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, io::SeekFrom, ops::Range, sync::Arc, time::SystemTime};
 
 use async_trait::async_trait;
-use tokio::io::{AsyncBufRead, AsyncSeek, AsyncWrite};
+use bytes::Bytes;
+use tokio::io::{AsyncBufRead, AsyncReadExt as _, AsyncSeek, AsyncSeekExt as _, AsyncWrite};
 
-use crate::error::InnoFileResult;
+use crate::{
+    error::{InnoFileError, InnoFileResult, Operation},
+    fs::FileStatus,
+};
 
+use super::io::{Closeable, CloseableBoxed};
+
+pub mod cache;
+pub mod faulty;
 pub mod local;
+pub mod mount;
 #[cfg(feature = "object_store")]
 pub mod object_store;
+pub mod parent_check;
+pub mod prefix;
+pub mod read_only;
+pub mod retry;
 
 pub type FileSystemRef = Arc<dyn FileSystem>;
 pub type FileRef = Arc<dyn File>;
@@ -24,9 +37,110 @@ pub trait FileSystem: Debug + Unpin + Send + Sync {
 
     async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>>;
 
+    /// Creates a file at `path` whose content is only published there, replacing any existing
+    /// file, once its writer is closed. Dropping the writer without closing it discards the
+    /// written content.
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>>;
+
+    /// Opens the file at `path` for appending, creating it if it doesn't exist. Its writers write
+    /// after the existing content. Not supported by default.
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        _ = path;
+        Err(InnoFileError::AppendNotSupported(self.scheme().to_string()))
+    }
+
+    /// Creates the directory at `path`, whose parent must exist.
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()>;
+
+    /// Creates the directory at `path` and its missing parents, succeeding if it already exists.
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()>;
+
+    /// Removes the directory at `path`, which must be empty.
     async fn remove_dir(&self, path: &str) -> InnoFileResult<()>;
 
+    /// Removes the directory at `path` and all of its content.
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()>;
+
     async fn remove_file(&self, path: &str) -> InnoFileResult<()>;
+
+    /// Lists the files and directories directly under the directory at `path`, in no particular
+    /// order. Not supported by default.
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        _ = path;
+        Err(InnoFileError::OperationNotSupported {
+            operation: Operation::List,
+            scheme: self.scheme().to_string(),
+        })
+    }
+
+    /// Returns the status of the file or directory at `path`, with `path` as its path. Not
+    /// supported by default.
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        _ = path;
+        Err(InnoFileError::OperationNotSupported {
+            operation: Operation::Stat,
+            scheme: self.scheme().to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem + ?Sized> FileSystem for Box<F> {
+    fn scheme(&self) -> &str {
+        (**self).scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        (**self).exists(path).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).open(path).await
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).create(path).await
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).create_new(path).await
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).create_atomic(path).await
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        (**self).append(path).await
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        (**self).create_dir(path).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        (**self).create_dir_all(path).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        (**self).remove_dir(path).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        (**self).remove_dir_all(path).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        (**self).remove_file(path).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        (**self).list(path).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        (**self).status(path).await
+    }
 }
 
 #[async_trait]
@@ -38,11 +152,39 @@ pub trait File: Debug + Unpin + Send + Sync {
     async fn reader(&self) -> InnoFileResult<Box<dyn FileRead>>;
 
     async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>>;
+
+    /// Reads the bytes in `range` of the file.
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        let mut reader = self.reader().await?;
+        reader.seek(SeekFrom::Start(range.start)).await?;
+        let mut buf = vec![0; (range.end - range.start) as usize];
+        reader.read_exact(&mut buf).await?;
+        Ok(buf.into())
+    }
+
+    /// Reads the bytes in each of `ranges`, concurrently where the file system supports it.
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let mut result = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            result.push(self.read_range(range.clone()).await?);
+        }
+        Ok(result)
+    }
 }
 
 #[async_trait]
 pub trait Metadata: Debug + Unpin + Send + Sync {
     fn len(&self) -> u64;
+
+    /// Time the file was last modified, if known.
+    fn last_modified(&self) -> Option<SystemTime> {
+        None
+    }
+
+    /// Version of the file content, such as an object store ETag, if known.
+    fn version(&self) -> Option<String> {
+        None
+    }
 }
 
 #[async_trait]
@@ -51,11 +193,22 @@ pub trait FileRead: Debug + AsyncBufRead + AsyncSeek + Unpin + Send {}
 #[async_trait]
 impl<R: Debug + AsyncBufRead + AsyncSeek + Unpin + Send> FileRead for R {}
 
+/// A writer of a file, closed to flush and finish the written content.
+#[async_trait]
+pub trait FileWrite: Debug + AsyncWrite + Unpin + Send + CloseableBoxed {}
+
 #[async_trait]
-pub trait FileWrite: Debug + AsyncWrite + Unpin + Send {}
+impl<W: Debug + AsyncWrite + Unpin + Send + CloseableBoxed> FileWrite for W {}
 
 #[async_trait]
-impl<W: Debug + AsyncWrite + Unpin + Send> FileWrite for W {}
+impl Closeable for Box<dyn FileWrite> {
+    async fn close(self) -> InnoFileResult<()> {
+        self.close_boxed().await
+    }
+    async fn abort(self) -> InnoFileResult<()> {
+        self.abort_boxed().await
+    }
+}
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,533 @@
+use std::{
+    fmt::{Debug, Formatter},
+    fs,
+    future::Future,
+    io::{ErrorKind, SeekFrom},
+    ops::Range,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::SystemTime,
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::{
+    fs::File as FsFile,
+    io::{AsyncRead, AsyncReadExt as _, AsyncSeek, AsyncWrite, BufReader, BufWriter, ReadBuf},
+    task::JoinHandle,
+};
+
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        cache::{Cache, CacheKey, CacheOptions, CacheStats, CachedMetadata},
+        FileStatus,
+    },
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+/// Caches the content read from the files of a [`FileSystem`] on local disk, according to
+/// [`CacheOptions`].
+///
+/// Readers cache whole files, while range reads cache each range on its own. Content is keyed by
+/// the version of the file when it was opened, so changed files are read again.
+#[derive(Debug)]
+pub struct CachingFS<F> {
+    inner: F,
+    cache: Arc<Cache>,
+}
+
+impl<F: FileSystem> CachingFS<F> {
+    pub fn new(inner: F, options: CacheOptions) -> InnoFileResult<Self> {
+        Ok(Self {
+            inner,
+            cache: Arc::new(Cache::new(options)?),
+        })
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem> FileSystem for CachingFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(path).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.inner.open(path).await?;
+        let metadata = CachedMetadata::from(file.metadata().await?);
+        Ok(Box::new(CachingFile {
+            inner: file,
+            cache: Arc::clone(&self.cache),
+            key: metadata.key(path),
+            metadata,
+        }))
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.create(path).await
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.create_new(path).await
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.create_atomic(path).await
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.cache.invalidate(path);
+        self.inner.append(path).await
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(path).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir_all(path).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(path).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.cache.invalidate(path);
+        self.inner.remove_dir_all(path).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.cache.invalidate(path);
+        self.inner.remove_file(path).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.inner.list(path).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.inner.status(path).await
+    }
+}
+
+impl From<Box<dyn Metadata>> for CachedMetadata {
+    fn from(value: Box<dyn Metadata>) -> Self {
+        Self {
+            len: value.len(),
+            last_modified: value.last_modified(),
+            version: value.version(),
+        }
+    }
+}
+
+impl Metadata for CachedMetadata {
+    fn len(&self) -> u64 {
+        self.len
+    }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.last_modified
+    }
+
+    fn version(&self) -> Option<String> {
+        self.version.clone()
+    }
+}
+
+#[derive(Debug)]
+pub struct CachingFile {
+    inner: Box<dyn File>,
+    cache: Arc<Cache>,
+    key: CacheKey,
+    metadata: CachedMetadata,
+}
+
+impl CachingFile {
+    /// Opens the cached content of `key`, returning `None` on a miss.
+    async fn get(&self, key: &CacheKey) -> InnoFileResult<Option<FsFile>> {
+        let (cache, key) = (Arc::clone(&self.cache), key.clone());
+        let file = blocking(move || Ok(cache.get(&key))).await?;
+        Ok(file.map(FsFile::from_std))
+    }
+
+    /// Caches `content` as the content of `key` and opens it.
+    async fn insert(&self, key: &CacheKey, content: Vec<u8>) -> InnoFileResult<FsFile> {
+        let (cache, key) = (Arc::clone(&self.cache), key.clone());
+        let file = blocking(move || cache.insert(key, &content)).await?;
+        Ok(FsFile::from_std(file))
+    }
+
+    /// Reads the cached content of `range`, returning `None` on a miss or if it is not cached at
+    /// all.
+    async fn get_range(&self, range: &Range<u64>) -> InnoFileResult<Option<Bytes>> {
+        if !self.cache.admits(range.end - range.start) {
+            return Ok(None);
+        }
+        let Some(mut file) = self.get(&self.key.with_range(range.clone())).await? else {
+            return Ok(None);
+        };
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf).await?;
+        Ok(Some(buf.into()))
+    }
+
+    async fn insert_range(&self, range: &Range<u64>, content: &Bytes) -> InnoFileResult<()> {
+        if self.cache.admits(range.end - range.start) {
+            let key = self.key.with_range(range.clone());
+            self.insert(&key, content.to_vec()).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl File for CachingFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    async fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        Ok(Box::new(self.metadata.clone()))
+    }
+
+    async fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        if !self.cache.admits(self.metadata.len) {
+            return self.inner.reader().await;
+        }
+        if let Some(file) = self.get(&self.key).await? {
+            return Ok(Box::new(BufReader::new(file)));
+        }
+        let file_path = self.cache.file_path();
+        let filling = Filling {
+            writer: BufWriter::new(FsFile::create_new(&file_path).await?),
+            file_path,
+            unwritten: Vec::new(),
+        };
+        Ok(Box::new(BufReader::new(CachingReader {
+            inner: self.inner.reader().await?,
+            cache: Arc::clone(&self.cache),
+            key: self.key.clone(),
+            position: 0,
+            filling: Some(filling),
+            committing: None,
+        })))
+    }
+
+    async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        self.inner.writer().await
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        if let Some(content) = self.get_range(&range).await? {
+            return Ok(content);
+        }
+        let content = self.inner.read_range(range.clone()).await?;
+        self.insert_range(&range, &content).await?;
+        Ok(content)
+    }
+
+    /// Reads the ranges not cached yet with a single read of the inner file, which may read them
+    /// concurrently.
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let mut contents = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            contents.push(self.get_range(range).await?);
+        }
+        let misses = ranges
+            .iter()
+            .zip(&contents)
+            .filter(|(_, content)| content.is_none())
+            .map(|(range, _)| range.clone())
+            .collect::<Vec<_>>();
+        if misses.is_empty() {
+            return Ok(contents.into_iter().flatten().collect());
+        }
+        let mut fetched = self.inner.read_ranges(&misses).await?.into_iter();
+        for (range, content) in ranges.iter().zip(&mut contents) {
+            if content.is_none() {
+                let bytes = fetched.next().ok_or_else(|| {
+                    std::io::Error::new(ErrorKind::UnexpectedEof, "Missing range read")
+                })?;
+                self.insert_range(range, &bytes).await?;
+                *content = Some(bytes);
+            }
+        }
+        Ok(contents.into_iter().flatten().collect())
+    }
+}
+
+/// Cache file being filled with the content read through a [`CachingReader`].
+struct Filling {
+    writer: BufWriter<FsFile>,
+    file_path: PathBuf,
+    /// Content read but not written to the cache file yet.
+    unwritten: Vec<u8>,
+}
+
+impl Filling {
+    fn poll_write_unwritten(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while !self.unwritten.is_empty() {
+            let len = ready!(Pin::new(&mut self.writer).poll_write(cx, &self.unwritten))?;
+            if len == 0 {
+                return Poll::Ready(Err(ErrorKind::WriteZero.into()));
+            }
+            self.unwritten.drain(..len);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+/// Reader of a file not cached yet, filling the cache with the content it reads through.
+///
+/// The content is only cached once read sequentially from the start to the end. Seeking anywhere
+/// else stops filling the cache, and the reader then only reads from the inner reader.
+struct CachingReader {
+    inner: Box<dyn FileRead>,
+    cache: Arc<Cache>,
+    key: CacheKey,
+    position: u64,
+    filling: Option<Filling>,
+    committing: Option<JoinHandle<std::io::Result<fs::File>>>,
+}
+
+impl CachingReader {
+    /// Caches the content filled so far, once the end of the inner reader is reached.
+    fn poll_finish(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if let Some(filling) = &mut self.filling {
+            ready!(filling.poll_write_unwritten(cx))?;
+            ready!(Pin::new(&mut filling.writer).poll_flush(cx))?;
+        }
+        if let Some(filling) = self.filling.take() {
+            drop(filling.writer);
+            let (cache, key, len) = (Arc::clone(&self.cache), self.key.clone(), self.position);
+            self.committing = Some(tokio::task::spawn_blocking(move || {
+                cache.insert_file(key, filling.file_path, len)
+            }));
+        }
+        if let Some(committing) = &mut self.committing {
+            let result = ready!(Pin::new(committing).poll(cx));
+            self.committing = None;
+            result.map_err(std::io::Error::from)??;
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Stops filling the cache, discarding the content filled so far.
+    fn abandon(&mut self) {
+        if let Some(filling) = self.filling.take() {
+            drop(filling.writer);
+            _ = fs::remove_file(&filling.file_path);
+        }
+    }
+}
+
+impl Debug for CachingReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachingReader")
+            .field("inner", &self.inner)
+            .field("key", &self.key)
+            .field("position", &self.position)
+            .field("filling", &self.filling.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for CachingReader {
+    fn drop(&mut self) {
+        self.abandon();
+    }
+}
+
+impl AsyncRead for CachingReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        // the content read before is written to the cache file before reading on
+        if let Some(filling) = &mut this.filling {
+            if let Err(error) = ready!(filling.poll_write_unwritten(cx)) {
+                log::warn!("Failed to cache the content of a file: {error}");
+                this.abandon();
+            }
+        }
+
+        let filled = buf.filled().len();
+        ready!(Pin::new(&mut this.inner).poll_read(cx, buf))?;
+        let read = &buf.filled()[filled..];
+        this.position += read.len() as u64;
+        if this.filling.is_none() && this.committing.is_none() {
+            return Poll::Ready(Ok(()));
+        }
+        if read.is_empty() && buf.remaining() > 0 {
+            // failing to cache the content does not fail reading it
+            if let Err(error) = ready!(this.poll_finish(cx)) {
+                log::warn!("Failed to cache the content of a file: {error}");
+                this.abandon();
+            }
+        } else if let Some(filling) = &mut this.filling {
+            filling.unwritten.extend_from_slice(read);
+            if let Poll::Ready(Err(error)) = filling.poll_write_unwritten(cx) {
+                log::warn!("Failed to cache the content of a file: {error}");
+                this.abandon();
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncSeek for CachingReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        Pin::new(&mut self.get_mut().inner).start_seek(position)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        let position = ready!(Pin::new(&mut this.inner).poll_complete(cx))?;
+        if position != this.position {
+            this.abandon();
+        }
+        this.position = position;
+        Poll::Ready(Ok(position))
+    }
+}
+
+/// Runs a blocking cache operation on the blocking thread pool.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> std::io::Result<T> + Send + 'static,
+) -> std::io::Result<T> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(std::io::Error::from)?
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use tokio::io::{AsyncSeekExt as _, AsyncWriteExt as _};
+
+    use crate::{
+        fs::faulty::FaultPolicy,
+        tokio::{
+            fs::{faulty::FaultyFS, local::LocalFS},
+            io::Closeable as _,
+        },
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_caching_fs() -> InnoFileResult<()> {
+        let content = "Hello, CachingFS!\n";
+
+        let dir = tempdir()?;
+        let path = dir.path().join("caching_fs.txt");
+        let path = path.to_str().unwrap();
+        let mut writer = LocalFS.create_new(path).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.close().await?;
+
+        let cache_options = CacheOptions::new(dir.path().join("cache"));
+        let file_system = CachingFS::new(LocalFS, cache_options)?;
+        for _ in 0..2 {
+            let file = file_system.open(path).await?;
+            let mut buf = String::new();
+            file.reader().await?.read_to_string(&mut buf).await?;
+            assert_eq!(buf, content);
+            assert_eq!(file.read_range(7..16).await?, "CachingFS");
+        }
+        let stats = file_system.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (2, 2, 2));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_read_ranges() -> InnoFileResult<()> {
+        let content = "Hello, CachingFS!\n";
+
+        let dir = tempdir()?;
+        let path = dir.path().join("read_ranges.txt");
+        let path = path.to_str().unwrap();
+        let mut writer = LocalFS.create_new(path).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.close().await?;
+
+        let inner = FaultyFS::new(LocalFS, FaultPolicy::new());
+        let file_system = CachingFS::new(inner, CacheOptions::new(dir.path().join("cache")))?;
+        let file = file_system.open(path).await?;
+        assert_eq!(file.read_range(7..16).await?, "CachingFS");
+
+        // the ranges not cached yet are read with a single call, and the metadata from opening
+        // the file is reused
+        let contents = file.read_ranges(&[0..5, 7..16, 17..18]).await?;
+        assert_eq!(contents, ["Hello", "CachingFS", "\n"]);
+        assert_eq!(file.read_ranges(&[0..5, 17..18]).await?, ["Hello", "\n"]);
+        assert_eq!(file.metadata().await?.len(), content.len() as u64);
+        drop(file);
+        assert_eq!(file_system.into_inner().calls(), 4);
+
+        Ok(())
+    }
+    #[tokio::test]
+    async fn test_caching_reader() -> InnoFileResult<()> {
+        let content = "Hello, CachingFS!\n";
+
+        let dir = tempdir()?;
+        let path = dir.path().join("caching_reader.txt");
+        let path = path.to_str().unwrap();
+        let mut writer = LocalFS.create_new(path).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.close().await?;
+
+        let cache_dir = dir.path().join("cache");
+        let file_system = CachingFS::new(LocalFS, CacheOptions::new(&cache_dir))?;
+
+        // readers stopping before the end, or seeking elsewhere, don't cache the file
+        let mut buf = [0; 5];
+        let mut reader = file_system.open(path).await?.reader().await?;
+        reader.read_exact(&mut buf).await?;
+        assert_eq!(&buf, b"Hello");
+        drop(reader);
+        let mut reader = file_system.open(path).await?.reader().await?;
+        reader.seek(SeekFrom::Start(7)).await?;
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, "CachingFS!\n");
+        drop(reader);
+        assert_eq!(file_system.stats().entries, 0);
+        assert_eq!(std::fs::read_dir(&cache_dir)?.count(), 0);
+
+        // readers reading through to the end cache the file
+        for _ in 0..2 {
+            let mut buf = String::new();
+            let mut reader = file_system.open(path).await?.reader().await?;
+            reader.read_to_string(&mut buf).await?;
+            assert_eq!(buf, content);
+        }
+        let stats = file_system.stats();
+        assert_eq!((stats.hits, stats.misses, stats.entries), (1, 3, 1));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+use std::{
+    future::Future as _,
+    io::SeekFrom,
+    ops::Range,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::{
+    io::{AsyncBufRead, AsyncRead, AsyncSeek, AsyncWrite, ReadBuf},
+    time::Sleep,
+};
+
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        faulty::{FaultPolicy, Faults},
+        FileStatus,
+    },
+    tokio::io::Closeable,
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+/// Injects the failures of a [`FaultPolicy`] into a [`FileSystem`], its files and their readers
+/// and writers, to test how callers cope with an unreliable store.
+#[derive(Debug)]
+pub struct FaultyFS<F> {
+    inner: F,
+    faults: Arc<Faults>,
+}
+
+impl<F: FileSystem> FaultyFS<F> {
+    pub fn new(inner: F, policy: FaultPolicy) -> Self {
+        Self {
+            inner,
+            faults: Arc::new(Faults::new(policy)),
+        }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    /// Number of calls made so far, including failed ones.
+    pub fn calls(&self) -> usize {
+        self.faults.calls()
+    }
+
+    fn make_file(&self, file: Box<dyn File>) -> Box<dyn File> {
+        Box::new(FaultyFile {
+            inner: file,
+            faults: Arc::clone(&self.faults),
+        })
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem> FileSystem for FaultyFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        inject(&self.faults).await?;
+        self.inner.exists(path).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults).await?;
+        Ok(self.make_file(self.inner.open(path).await?))
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults).await?;
+        Ok(self.make_file(self.inner.create(path).await?))
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults).await?;
+        Ok(self.make_file(self.inner.create_new(path).await?))
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults).await?;
+        Ok(self.make_file(self.inner.create_atomic(path).await?))
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        inject(&self.faults).await?;
+        Ok(self.make_file(self.inner.append(path).await?))
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults).await?;
+        self.inner.create_dir(path).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults).await?;
+        self.inner.create_dir_all(path).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults).await?;
+        self.inner.remove_dir(path).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults).await?;
+        self.inner.remove_dir_all(path).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        inject(&self.faults).await?;
+        self.inner.remove_file(path).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        inject(&self.faults).await?;
+        self.inner.list(path).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        inject(&self.faults).await?;
+        self.inner.status(path).await
+    }
+}
+
+#[derive(Debug)]
+pub struct FaultyFile {
+    inner: Box<dyn File>,
+    faults: Arc<Faults>,
+}
+
+#[async_trait]
+impl File for FaultyFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    async fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        inject(&self.faults).await?;
+        self.inner.metadata().await
+    }
+
+    async fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        inject(&self.faults).await?;
+        Ok(Box::new(FaultyReader {
+            inner: self.inner.reader().await?,
+            injector: Injector::new(Arc::clone(&self.faults)),
+            buf: vec![0; READ_BUFFER_SIZE].into(),
+            start: 0,
+            end: 0,
+        }))
+    }
+
+    async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        inject(&self.faults).await?;
+        Ok(Box::new(FaultyWriter {
+            inner: self.inner.writer().await?,
+            injector: Injector::new(Arc::clone(&self.faults)),
+            written: 0,
+        }))
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        inject(&self.faults).await?;
+        self.inner.read_range(range).await
+    }
+
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        inject(&self.faults).await?;
+        self.inner.read_ranges(ranges).await
+    }
+}
+
+/// Injects faults into the poll-based calls of readers and writers, where a call may be polled
+/// several times before it completes.
+#[derive(Debug)]
+struct Injector {
+    faults: Arc<Faults>,
+    sleep: Option<Pin<Box<Sleep>>>,
+    in_call: bool,
+}
+
+impl Injector {
+    fn new(faults: Arc<Faults>) -> Self {
+        Self {
+            faults,
+            sleep: None,
+            in_call: false,
+        }
+    }
+
+    /// Delays and counts the call being polled, unless already done, failing it if the policy
+    /// says so.
+    fn poll_call(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        if self.in_call {
+            return Poll::Ready(Ok(()));
+        }
+        if let Some(latency) = self.faults.latency() {
+            let sleep = self
+                .sleep
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep(latency)));
+            ready!(sleep.as_mut().poll(cx));
+            self.sleep = None;
+        }
+        self.faults.call()?;
+        self.in_call = true;
+        Poll::Ready(Ok(()))
+    }
+
+    fn done(&mut self) {
+        self.in_call = false;
+    }
+}
+
+/// Reader buffering on its own, so that only reads reaching the inner reader are calls.
+#[derive(Debug)]
+struct FaultyReader {
+    inner: Box<dyn FileRead>,
+    injector: Injector,
+    buf: Box<[u8]>,
+    start: usize,
+    end: usize,
+}
+
+impl FaultyReader {
+    fn poll_read_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        ready!(self.injector.poll_call(cx))?;
+        let len = self.injector.faults.read_len(buf.len());
+        let mut read_buf = ReadBuf::new(&mut buf[..len]);
+        let result = ready!(Pin::new(&mut self.inner).poll_read(cx, &mut read_buf));
+        self.injector.done();
+        result?;
+        Poll::Ready(Ok(read_buf.filled().len()))
+    }
+}
+
+impl AsyncRead for FaultyReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        if this.start == this.end {
+            let len = ready!(this.poll_read_inner(cx, buf.initialize_unfilled()))?;
+            buf.advance(len);
+        } else {
+            let len = buf.remaining().min(this.end - this.start);
+            buf.put_slice(&this.buf[this.start..this.start + len]);
+            this.start += len;
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncBufRead for FaultyReader {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<&[u8]>> {
+        let this = self.get_mut();
+        if this.start == this.end {
+            let mut buf = std::mem::take(&mut this.buf);
+            let result = this.poll_read_inner(cx, &mut buf);
+            this.buf = buf;
+            (this.start, this.end) = (0, ready!(result)?);
+        }
+        Poll::Ready(Ok(&this.buf[this.start..this.end]))
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        let this = self.get_mut();
+        this.start = (this.start + amt).min(this.end);
+    }
+}
+
+impl AsyncSeek for FaultyReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let this = self.get_mut();
+        let position = match position {
+            SeekFrom::Current(offset) => SeekFrom::Current(offset - (this.end - this.start) as i64),
+            position => position,
+        };
+        (this.start, this.end) = (0, 0);
+        Pin::new(&mut this.inner).start_seek(position)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        Pin::new(&mut self.get_mut().inner).poll_complete(cx)
+    }
+}
+
+#[derive(Debug)]
+struct FaultyWriter {
+    inner: Box<dyn FileWrite>,
+    injector: Injector,
+    written: u64,
+}
+
+impl FaultyWriter {
+    /// Runs `poll` on the inner writer as a single call, once the fault injection let it through.
+    fn poll_call<T>(
+        &mut self,
+        cx: &mut Context<'_>,
+        poll: impl FnOnce(&mut Self, &mut Context<'_>) -> Poll<std::io::Result<T>>,
+    ) -> Poll<std::io::Result<T>> {
+        ready!(self.injector.poll_call(cx))?;
+        let result = ready!(poll(self, cx));
+        self.injector.done();
+        Poll::Ready(result)
+    }
+}
+
+impl AsyncWrite for FaultyWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.get_mut().poll_call(cx, |this, cx| {
+            let len = this.injector.faults.write_len(buf.len(), this.written)?;
+            let len = ready!(Pin::new(&mut this.inner).poll_write(cx, &buf[..len]))?;
+            this.written += len as u64;
+            Poll::Ready(Ok(len))
+        })
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.get_mut()
+            .poll_call(cx, |this, cx| Pin::new(&mut this.inner).poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.get_mut()
+            .poll_call(cx, |this, cx| Pin::new(&mut this.inner).poll_shutdown(cx))
+    }
+}
+
+#[async_trait]
+impl Closeable for FaultyWriter {
+    async fn close(self) -> InnoFileResult<()> {
+        inject(&self.injector.faults).await?;
+        self.inner.close().await
+    }
+
+    async fn abort(self) -> InnoFileResult<()> {
+        self.inner.abort().await
+    }
+}
+
+/// Delays and counts a call, failing it if the policy says so.
+async fn inject(faults: &Faults) -> std::io::Result<()> {
+    if let Some(latency) = faults.latency() {
+        tokio::time::sleep(latency).await;
+    }
+    faults.call()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::ErrorKind, time::Duration};
+
+    use tempfile::tempdir;
+    use tokio::io::{AsyncBufReadExt as _, AsyncReadExt as _, AsyncWriteExt as _};
+
+    use crate::tokio::fs::local::LocalFS;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_faulty_fs() -> InnoFileResult<()> {
+        let content = "Hello, FaultyFS!\n";
+
+        let path = tempdir()?.path().join("faulty_fs.txt");
+        let path = path.to_str().unwrap();
+
+        let policy = FaultPolicy::new()
+            .with_failure_on_call(1)
+            .with_truncated_writes(5)
+            .with_latency(Duration::from_millis(1));
+        let file_system = FaultyFS::new(LocalFS, policy);
+        let error = file_system.create_new(path).await.unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::ConnectionReset));
+
+        let mut writer = file_system.create_new(path).await?.writer().await?;
+        let error = writer.write_all(content.as_bytes()).await.unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::ConnectionReset);
+        writer.flush().await?;
+        assert_eq!(LocalFS.open(path).await?.metadata().await?.len(), 5);
+        LocalFS.remove_file(path).await?;
+        let mut writer = LocalFS.create_new(path).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.flush().await?;
+
+        let file_system = FaultyFS::new(LocalFS, FaultPolicy::new().with_short_reads(3));
+        let mut reader = file_system.open(path).await?.reader().await?;
+        assert_eq!(reader.fill_buf().await?, b"Hel");
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, content);
+        assert!(file_system.calls() > content.len() / 3);
+
+        Ok(())
+    }
+}
@@ -1,15 +1,24 @@
 use std::{
     fs::{self, Metadata as FsMetadata},
     path::Path,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::SystemTime,
 };
 
 use async_trait::async_trait;
 use tokio::{
-    fs::File as FsFile,
-    io::{BufReader, BufWriter},
+    fs::{File as FsFile, OpenOptions},
+    io::{AsyncWrite, AsyncWriteExt as _, BufReader, BufWriter},
 };
 
-use crate::error::InnoFileResult;
+use crate::{
+    error::{ErrorContext, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+    tokio::io::Closeable,
+    utils::staging::Staging,
+};
 
 use super::{File, FileRead, FileSystem, FileWrite, Metadata};
 
@@ -25,6 +34,12 @@ impl LocalFS {
             .unwrap_or_else(|| path.to_string())
     }
 
+    fn context(operation: Operation, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_path(path)
+            .with_scheme(SCHEME)
+    }
+
     fn ensure_parent_path(path: impl AsRef<Path>) -> InnoFileResult<()> {
         if let Some(parent) = path.as_ref().parent() {
             if !fs::exists(parent)? {
@@ -42,38 +57,117 @@ impl FileSystem for LocalFS {
     }
 
     async fn exists(&self, path: &str) -> InnoFileResult<bool> {
-        Ok(fs::exists(Self::strip_scheme(path))?)
+        fs::exists(Self::strip_scheme(path)).with_context(|| Self::context(Operation::Stat, path))
     }
 
     async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        let path = Self::strip_scheme(path);
-        Ok(Box::new(LocalFile::new(&path, FsFile::open(&path).await?)))
+        let local_path = Self::strip_scheme(path);
+        let file = FsFile::open(&local_path)
+            .await
+            .with_context(|| Self::context(Operation::Open, path))?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
     }
 
     async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        let path = Self::strip_scheme(path);
-        Self::ensure_parent_path(&path)?;
-        Ok(Box::new(LocalFile::new(
-            &path,
-            FsFile::create(&path).await?,
-        )))
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Create, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let file = FsFile::create(&local_path).await.with_context(context)?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
     }
 
     async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        let path = Self::strip_scheme(path);
-        Self::ensure_parent_path(&path)?;
-        Ok(Box::new(LocalFile::new(
-            &path,
-            FsFile::create_new(&path).await?,
-        )))
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Create, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let file = FsFile::create_new(&local_path)
+            .await
+            .with_context(context)?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Create, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let staging = Staging::new(&local_path);
+        let file = FsFile::create_new(staging.temp_path())
+            .await
+            .with_context(context)?;
+        Ok(Box::new(StagedFile {
+            inner: LocalFile::new(&local_path, file),
+            staging: Arc::new(staging),
+        }))
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let local_path = Self::strip_scheme(path);
+        let context = || Self::context(Operation::Append, path);
+        Self::ensure_parent_path(&local_path).with_context(context)?;
+        let file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(&local_path)
+            .await
+            .with_context(context)?;
+        Ok(Box::new(LocalFile::new(&local_path, file)))
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        fs::create_dir(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Create, path))
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        fs::create_dir_all(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Create, path))
     }
 
     async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
-        Ok(fs::remove_dir(Self::strip_scheme(path))?)
+        fs::remove_dir(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Remove, path))
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        fs::remove_dir_all(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Remove, path))
     }
 
     async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
-        Ok(fs::remove_file(Self::strip_scheme(path))?)
+        fs::remove_file(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Remove, path))
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let context = || Self::context(Operation::List, path);
+        let mut statuses = Vec::new();
+        for entry in fs::read_dir(Self::strip_scheme(path)).with_context(context)? {
+            let entry = entry.with_context(context)?;
+            let metadata = entry.metadata().with_context(context)?;
+            statuses.push(FileStatus {
+                path: format!(
+                    "{}/{}",
+                    path.trim_end_matches('/'),
+                    entry.file_name().to_string_lossy()
+                ),
+                len: metadata.len(),
+                is_dir: metadata.is_dir(),
+                last_modified: metadata.modified().ok(),
+            });
+        }
+        Ok(statuses)
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let metadata = fs::metadata(Self::strip_scheme(path))
+            .with_context(|| Self::context(Operation::Stat, path))?;
+        Ok(FileStatus {
+            path: path.to_string(),
+            len: metadata.len(),
+            is_dir: metadata.is_dir(),
+            last_modified: metadata.modified().ok(),
+        })
     }
 }
 
@@ -111,15 +205,93 @@ impl File for LocalFile {
     }
 }
 
+#[async_trait]
+// Syncs the file, so that write errors are reported on close rather than lost.
+impl Closeable for BufWriter<FsFile> {
+    async fn close(mut self) -> InnoFileResult<()> {
+        self.shutdown().await?;
+        self.into_inner().sync_all().await?;
+        Ok(())
+    }
+}
+
+/// A file written to a staging path and published at its own path when its writer is closed.
+#[derive(Debug)]
+pub struct StagedFile {
+    inner: LocalFile,
+    staging: Arc<Staging>,
+}
+
+#[async_trait]
+impl File for StagedFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    async fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        self.inner.metadata().await
+    }
+
+    async fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        self.inner.reader().await
+    }
+
+    async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        Ok(Box::new(StagedWriter {
+            inner: BufWriter::new(self.inner.inner.try_clone().await?),
+            staging: Arc::clone(&self.staging),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct StagedWriter {
+    inner: BufWriter<FsFile>,
+    staging: Arc<Staging>,
+}
+
+impl AsyncWrite for StagedWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+#[async_trait]
+impl Closeable for StagedWriter {
+    async fn close(self) -> InnoFileResult<()> {
+        self.inner.close().await?;
+        self.staging.publish()?;
+        Ok(())
+    }
+}
+
 #[async_trait]
 impl Metadata for FsMetadata {
     fn len(&self) -> u64 {
         self.len()
     }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        self.modified().ok()
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::io::ErrorKind;
+
     use tempfile::tempdir;
     use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
 
@@ -167,4 +339,71 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_create_atomic() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("create_atomic.txt");
+        let path = path.to_str().unwrap();
+
+        let mut writer = LocalFS.create_atomic(path).await?.writer().await?;
+        writer.write_all(b"discarded").await?;
+        writer.flush().await?;
+        assert!(!LocalFS.exists(path).await?);
+        drop(writer);
+        assert!(!LocalFS.exists(path).await?);
+        assert_eq!(fs::read_dir(dir.path())?.count(), 0);
+
+        let mut writer = LocalFS.create_atomic(path).await?.writer().await?;
+        writer.write_all(b"published").await?;
+        assert!(!LocalFS.exists(path).await?);
+        writer.close().await?;
+        assert_eq!(fs::read_to_string(path)?, "published");
+        assert_eq!(fs::read_dir(dir.path())?.count(), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_dirs() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let parent = dir.path().join("parent");
+        let child = parent.join("child");
+        let (parent, child) = (parent.to_str().unwrap(), child.to_str().unwrap());
+        let file_path = format!("{}/file.txt", child);
+
+        let file_system = LocalFS;
+        assert!(file_system
+            .create_dir(child)
+            .await
+            .unwrap_err()
+            .is_not_found());
+        file_system.create_dir_all(child).await?;
+        file_system.create_dir_all(child).await?;
+        let error = file_system.create_dir(child).await.unwrap_err();
+        assert!(error.is_already_exists());
+        file_system.create(&file_path).await?;
+
+        let error = file_system.remove_dir(parent).await.unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::DirectoryNotEmpty));
+        file_system.remove_dir_all(parent).await?;
+        assert!(!file_system.exists(parent).await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_append() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("append.txt");
+        let path = path.to_str().unwrap();
+
+        for line in ["first\n", "second\n"] {
+            let mut writer = LocalFS.append(path).await?.writer().await?;
+            writer.write_all(line.as_bytes()).await?;
+            writer.close().await?;
+        }
+        assert_eq!(fs::read_to_string(path)?, "first\nsecond\n");
+
+        Ok(())
+    }
 }
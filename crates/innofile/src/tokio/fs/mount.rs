@@ -0,0 +1,211 @@
+use async_trait::async_trait;
+
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        mount::{merge_statuses, uri_root, MountTable},
+        FileStatus, FileSystemBuilder,
+    },
+};
+
+use super::{File, FileSystem, FileSystemRef};
+
+/// Routes paths to the file systems mounted at their longest matching prefix, such as
+/// `/warehouse/` to S3 and `/tmp/` to local files.
+///
+/// Listing a directory merges the listing of its mount with the directories leading to the
+/// mounts nested under it.
+#[derive(Debug, Default)]
+pub struct MountFS {
+    mounts: MountTable<FileSystemRef>,
+}
+
+impl MountFS {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Builds a file system for each mount of `config`, from its prefix to the URI of its root,
+    /// such as `/warehouse/` to `s3://bucket/warehouse/`.
+    pub async fn from_config(
+        config: impl IntoIterator<Item = (impl ToString, impl ToString)>,
+    ) -> InnoFileResult<Self> {
+        let mut file_system = Self::new();
+        for (prefix, uri) in config {
+            let uri = uri.to_string();
+            let mounted = FileSystemBuilder::from_path(&uri)?.build_async().await?;
+            file_system =
+                file_system.with_mount(prefix.to_string(), mounted.into(), uri_root(&uri)?);
+        }
+        Ok(file_system)
+    }
+
+    /// Mounts `file_system` at `prefix`, resolving the paths under it against `root`, a
+    /// directory or object key prefix of the file system.
+    pub fn with_mount(
+        mut self,
+        prefix: impl AsRef<str>,
+        file_system: FileSystemRef,
+        root: impl ToString,
+    ) -> Self {
+        self.mounts.insert(prefix.as_ref(), root, file_system);
+        self
+    }
+}
+
+#[async_trait]
+impl FileSystem for MountFS {
+    fn scheme(&self) -> &str {
+        "mount"
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        if !self.mounts.mount_points(path)?.is_empty() {
+            return Ok(true);
+        }
+        match self.mounts.route(path) {
+            Ok((mount, path)) => mount.file_system.exists(&path).await,
+            Err(_) => Ok(false),
+        }
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.open(&path).await
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create(&path).await
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_new(&path).await
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_atomic(&path).await
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.append(&path).await
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_dir(&path).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.create_dir_all(&path).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.remove_dir(&path).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.remove_dir_all(&path).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        let (mount, path) = self.mounts.route(path)?;
+        mount.file_system.remove_file(&path).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let mount_points = self.mounts.mount_points(path)?;
+        let statuses = match self.mounts.route(path) {
+            Ok((mount, path)) => match mount.file_system.list(&path).await {
+                Ok(statuses) => statuses
+                    .into_iter()
+                    .map(|status| mount.mount_status(status))
+                    .collect(),
+                Err(error) if error.is_not_found() && !mount_points.is_empty() => Vec::new(),
+                Err(error) => return Err(error),
+            },
+            Err(_) if !mount_points.is_empty() => Vec::new(),
+            Err(error) => return Err(error),
+        };
+        Ok(merge_statuses(statuses, mount_points))
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let mount_points = self.mounts.mount_points(path)?;
+        let status = match self.mounts.route(path) {
+            Ok((mount, path)) => match mount.file_system.status(&path).await {
+                Ok(status) => status,
+                Err(error) if error.is_not_found() && !mount_points.is_empty() => {
+                    FileStatus::dir("")
+                }
+                Err(error) => return Err(error),
+            },
+            // directories containing mount points exist even if nothing is mounted on them
+            Err(_) if !mount_points.is_empty() => FileStatus::dir(""),
+            Err(error) => return Err(error),
+        };
+        Ok(FileStatus {
+            path: path.to_string(),
+            ..status
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    use crate::{error::InnoFileError, tokio::fs::local::LocalFS};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_mount_fs() -> InnoFileResult<()> {
+        let content = "Hello, MountFS!\n";
+
+        let dir = tempdir()?;
+        let (warehouse, tmp) = (dir.path().join("warehouse"), dir.path().join("tmp"));
+        let config = [
+            ("/warehouse/", warehouse.to_str().unwrap()),
+            ("/tmp", tmp.to_str().unwrap()),
+        ];
+        let file_system: FileSystemRef = Arc::new(MountFS::from_config(config).await?);
+
+        let file = file_system
+            .create_new("/warehouse/sales/mount_fs.txt")
+            .await?;
+        let mut writer = file.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.flush().await?;
+        let mut buf = String::new();
+        let path = warehouse.join("sales/mount_fs.txt");
+        let file = LocalFS.open(path.to_str().unwrap()).await?;
+        file.reader().await?.read_to_string(&mut buf).await?;
+        assert_eq!(buf, content);
+
+        let paths = |statuses: Vec<FileStatus>| {
+            statuses
+                .into_iter()
+                .map(|status| status.path)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(paths(file_system.list("/").await?), ["/tmp", "/warehouse"]);
+        let statuses = file_system.list("/warehouse/sales/").await?;
+        assert_eq!(paths(statuses), ["/warehouse/sales/mount_fs.txt"]);
+
+        assert!(!file_system.exists("/tmp/mount_fs.txt").await?);
+        let error = file_system.open("/ref/mount_fs.txt").await.unwrap_err();
+        assert!(matches!(error, InnoFileError::MountNotFound(_)));
+
+        Ok(())
+    }
+}
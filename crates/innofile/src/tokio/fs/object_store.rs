@@ -1,16 +1,40 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Formatter},
+    io::ErrorKind,
+    ops::Range,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::SystemTime,
+};
 
+#[cfg(feature = "s3")]
+use anyhow::anyhow;
 use async_trait::async_trait;
+use bytes::Bytes;
 use futures::{StreamExt as _, TryStreamExt as _};
 #[cfg(feature = "s3")]
-use object_store::aws::AmazonS3Builder;
+use object_store::aws::{AmazonS3Builder, AmazonS3ConfigKey};
 use object_store::{
     buffered::{BufReader, BufWriter},
-    path::Path,
-    Error, ObjectMeta, ObjectStore, PutMode, PutPayload, PutResult,
+    Error, ListResult, ObjectMeta, ObjectStore, PutMode, PutPayload, PutResult,
+};
+
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt as _},
+    runtime::Handle,
+};
+
+use crate::{
+    error::{ErrorContext, InnoFileError, InnoFileResult, Operation, ResultExt as _},
+    fs::{upload::UploadOptions, FileStatus},
+    tokio::io::Closeable,
+    utils::{bucket_object_path, object_path},
 };
 
-use crate::error::InnoFileResult;
+#[cfg(feature = "s3")]
+use crate::fs::credential::{AwsCredentialProvider, CredentialProvider};
 
 use super::{File, FileRead, FileSystem, FileWrite, Metadata};
 
@@ -18,6 +42,12 @@ use super::{File, FileRead, FileSystem, FileWrite, Metadata};
 pub struct ObjectFS {
     scheme: String,
     store: Arc<dyn ObjectStore>,
+    upload_options: UploadOptions,
+    /// Bucket of the store, which URIs of other buckets are rejected for.
+    bucket: Option<String>,
+    /// Builder of the store of S3 file systems, to build it again with other credentials.
+    #[cfg(feature = "s3")]
+    s3_builder: Option<AmazonS3Builder>,
 }
 
 impl ObjectFS {
@@ -25,9 +55,24 @@ impl ObjectFS {
         Ok(Self {
             scheme: scheme.to_string(),
             store,
+            upload_options: UploadOptions::default(),
+            bucket: None,
+            #[cfg(feature = "s3")]
+            s3_builder: None,
         })
     }
 
+    pub fn with_upload_options(mut self, upload_options: UploadOptions) -> Self {
+        self.upload_options = upload_options;
+        self
+    }
+
+    /// Rejects URIs of other buckets than `bucket`, which the store is of.
+    pub fn with_bucket(mut self, bucket: impl ToString) -> Self {
+        self.bucket = Some(bucket.to_string());
+        self
+    }
+
     #[cfg(feature = "s3")]
     pub fn new_s3(
         scheme: impl ToString,
@@ -41,29 +86,68 @@ impl ObjectFS {
         for (key, value) in properties {
             builder = builder.with_config(key.to_string().parse()?, value.to_string());
         }
-        let store = Arc::new(builder.build()?);
-        Self::from_store(scheme, store)
+        let store = Arc::new(builder.clone().build()?);
+        Ok(Self {
+            bucket: builder.get_config_value(&AmazonS3ConfigKey::Bucket),
+            s3_builder: Some(builder),
+            ..Self::from_store(scheme, store)?
+        })
     }
 
-    async fn head(&self, path: impl AsRef<str>) -> InnoFileResult<ObjectMeta, Error> {
-        self.store.head(&Path::parse(path)?).await
+    /// Fetches the credentials of an S3 file system from `provider`, instead of resolving them
+    /// from the environment.
+    #[cfg(feature = "s3")]
+    pub fn with_credential_provider(
+        mut self,
+        provider: CredentialProvider,
+    ) -> InnoFileResult<Self> {
+        let builder = self
+            .s3_builder
+            .take()
+            .ok_or_else(|| anyhow!("Credential providers only supported by S3 file systems"))?
+            .with_credentials(Arc::new(AwsCredentialProvider::new(provider)));
+        self.store = Arc::new(builder.clone().build()?);
+        self.s3_builder = Some(builder);
+        Ok(self)
+    }
+
+    async fn head(&self, path: impl AsRef<str>) -> InnoFileResult<ObjectMeta> {
+        Ok(self.store.head(&self.object_path(path.as_ref())?).await?)
     }
 
     async fn put_empty_file(
         &self,
         path: impl AsRef<str>,
         put_mode: PutMode,
-    ) -> InnoFileResult<PutResult, Error> {
-        self.store
-            .put_opts(&Path::parse(path)?, PutPayload::new(), put_mode.into())
-            .await
+    ) -> InnoFileResult<PutResult> {
+        Ok(self
+            .store
+            .put_opts(
+                &self.object_path(path.as_ref())?,
+                PutPayload::new(),
+                put_mode.into(),
+            )
+            .await?)
+    }
+
+    fn object_path(&self, path: &str) -> InnoFileResult<object_store::path::Path> {
+        bucket_object_path(path, self.bucket.as_deref())
+    }
+
+    fn context(&self, operation: Operation, path: &str) -> ErrorContext {
+        ErrorContext::new(operation)
+            .with_path(path)
+            .with_scheme(&self.scheme)
     }
 
     fn make_file(&self, path: impl ToString) -> ObjectFile {
         ObjectFile::new(path, Arc::clone(&self.store))
+            .with_upload_options(self.upload_options.clone())
     }
 }
 
+// Objects can't be appended to, and emulating it by uploading the existing content again costs
+// as much as the whole object, so appending is left unsupported.
 #[async_trait]
 impl FileSystem for ObjectFS {
     fn scheme(&self) -> &str {
@@ -73,41 +157,120 @@ impl FileSystem for ObjectFS {
     async fn exists(&self, path: &str) -> InnoFileResult<bool> {
         match self.head(path).await {
             Ok(_) => Ok(true),
-            Err(Error::NotFound { path: _, source: _ }) => Ok(false),
-            Err(error) => Err(error)?,
+            Err(InnoFileError::ObjectStore(Error::NotFound { .. })) => Ok(false),
+            Err(error) => Err(error).with_context(|| self.context(Operation::Stat, path)),
         }
     }
 
     async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        self.head(path).await?;
-        Ok(Box::new(self.make_file(path)))
+        let meta = self
+            .head(path)
+            .await
+            .with_context(|| self.context(Operation::Open, path))?;
+        Ok(Box::new(self.make_file(path).with_object_meta(meta)))
     }
 
     async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        self.put_empty_file(path, PutMode::Overwrite).await?;
+        self.put_empty_file(path, PutMode::Overwrite)
+            .await
+            .with_context(|| self.context(Operation::Create, path))?;
         Ok(Box::new(self.make_file(path)))
     }
 
     async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
-        self.put_empty_file(path, PutMode::Create).await?;
+        self.put_empty_file(path, PutMode::Create)
+            .await
+            .with_context(|| self.context(Operation::Create, path))?;
         Ok(Box::new(self.make_file(path)))
     }
 
+    // Multipart uploads only become visible once completed, so nothing is put upfront.
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.object_path(path)
+            .with_context(|| self.context(Operation::Create, path))?;
+        Ok(Box::new(ObjectFile {
+            atomic: true,
+            ..self.make_file(path)
+        }))
+    }
+
+    /// Does nothing but validate `path`, since directories are implicit in object stores,
+    /// existing as long as objects are stored under them. The directory doesn't exist afterwards
+    /// until an object is stored under it.
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.object_path(path)
+            .with_context(|| self.context(Operation::Create, path))?;
+        Ok(())
+    }
+
+    /// Does nothing but validate `path`, like [`Self::create_dir`].
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.object_path(path)
+            .with_context(|| self.context(Operation::Create, path))?;
+        Ok(())
+    }
+
     async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        let context = || self.context(Operation::Remove, path);
+        let prefix = self.object_path(path).with_context(context)?;
+        let first = self.store.list(Some(&prefix)).next().await;
+        if first.transpose().with_context(context)?.is_some() {
+            Err(std::io::Error::from(ErrorKind::DirectoryNotEmpty)).with_context(context)?;
+        }
+        Ok(())
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        let context = || self.context(Operation::Remove, path);
+        let prefix = self.object_path(path).with_context(context)?;
         let locations = self
             .store
-            .list(Some(&Path::parse(path)?))
+            .list(Some(&prefix))
             .map_ok(|m| m.location)
             .boxed();
         self.store
             .delete_stream(locations)
             .try_collect::<Vec<_>>()
-            .await?;
+            .await
+            .with_context(context)?;
         Ok(())
     }
 
     async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
-        Ok(self.store.delete(&Path::parse(path)?).await?)
+        let context = || self.context(Operation::Remove, path);
+        let location = self.object_path(path).with_context(context)?;
+        self.store.delete(&location).await.with_context(context)
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let context = || self.context(Operation::List, path);
+        let prefix = self.object_path(path).with_context(context)?;
+        let result = self
+            .store
+            .list_with_delimiter(Some(&prefix))
+            .await
+            .with_context(context)?;
+        Ok(file_statuses(result))
+    }
+
+    // Directories are implicit, existing as long as objects are stored under them.
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let context = || self.context(Operation::Stat, path);
+        match self.head(path).await {
+            Ok(meta) => Ok(FileStatus {
+                path: path.to_string(),
+                ..meta.into()
+            }),
+            Err(InnoFileError::ObjectStore(Error::NotFound { .. })) => {
+                let prefix = self.object_path(path).with_context(context)?;
+                let first = self.store.list(Some(&prefix)).next().await;
+                match first.transpose().with_context(context)? {
+                    Some(_) => Ok(FileStatus::dir(path)),
+                    None => Err(std::io::Error::from(ErrorKind::NotFound)).with_context(context),
+                }
+            }
+            Err(error) => Err(error).with_context(context),
+        }
     }
 }
 
@@ -115,6 +278,10 @@ impl FileSystem for ObjectFS {
 pub struct ObjectFile {
     path: String,
     store: Arc<dyn ObjectStore>,
+    upload_options: UploadOptions,
+    atomic: bool,
+    /// Metadata of the object when the file was opened, which reading reuses.
+    meta: Option<ObjectMeta>,
 }
 
 impl ObjectFile {
@@ -122,11 +289,41 @@ impl ObjectFile {
         Self {
             path: path.to_string(),
             store,
+            upload_options: UploadOptions::default(),
+            atomic: false,
+            meta: None,
         }
     }
 
+    pub fn with_upload_options(mut self, upload_options: UploadOptions) -> Self {
+        self.upload_options = upload_options;
+        self
+    }
+
+    fn with_object_meta(mut self, meta: ObjectMeta) -> Self {
+        self.meta = Some(meta);
+        self
+    }
+
+    /// Metadata of the object, as of opening the file if it was opened.
     pub async fn object_meta(&self) -> InnoFileResult<ObjectMeta> {
-        Ok(self.store.head(&Path::parse(&self.path)?).await?)
+        if let Some(meta) = &self.meta {
+            return Ok(meta.clone());
+        }
+        Ok(self.store.head(&object_path(&self.path)?).await?)
+    }
+
+    /// Opens a writer uploading to the object.
+    pub async fn object_writer(&self) -> InnoFileResult<ObjectWriter> {
+        let location = object_path(&self.path)?;
+        let buf_writer = self
+            .upload_options
+            .buf_writer(Arc::clone(&self.store), location);
+        Ok(ObjectWriter {
+            buf_writer: Some(buf_writer),
+            shut_down: false,
+            abort_on_drop: self.atomic,
+        })
     }
 }
 
@@ -146,8 +343,26 @@ impl File for ObjectFile {
     }
 
     async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
-        let buf_writer = BufWriter::new(Arc::clone(&self.store), Path::parse(&self.path)?);
-        Ok(Box::new(buf_writer))
+        Ok(Box::new(self.object_writer().await?))
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        let range = range.start as usize..range.end as usize;
+        Ok(self
+            .store
+            .get_range(&object_path(&self.path)?, range)
+            .await?)
+    }
+
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        let ranges = ranges
+            .iter()
+            .map(|range| range.start as usize..range.end as usize)
+            .collect::<Vec<_>>();
+        Ok(self
+            .store
+            .get_ranges(&object_path(&self.path)?, &ranges)
+            .await?)
     }
 }
 
@@ -156,10 +371,119 @@ impl Metadata for ObjectMeta {
     fn len(&self) -> u64 {
         self.size as _
     }
+
+    fn last_modified(&self) -> Option<SystemTime> {
+        Some(self.last_modified.into())
+    }
+
+    fn version(&self) -> Option<String> {
+        self.e_tag.clone().or_else(|| self.version.clone())
+    }
+}
+
+/// Writer uploading to an object, completing the upload when closed or shut down.
+///
+/// Writers of atomically created files dropped without closing abort the upload, unless they were
+/// shut down already.
+pub struct ObjectWriter {
+    /// Buffered writer, taken once the upload is completed or aborted.
+    buf_writer: Option<BufWriter>,
+    /// Whether shutting down was started, after which the upload can no longer be aborted.
+    shut_down: bool,
+    abort_on_drop: bool,
+}
+
+impl ObjectWriter {
+    fn buf_writer(&mut self) -> std::io::Result<Pin<&mut BufWriter>> {
+        self.buf_writer
+            .as_mut()
+            .map(Pin::new)
+            .ok_or_else(|| std::io::Error::other("Writer already closed"))
+    }
+}
+
+impl Debug for ObjectWriter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectWriter")
+            .field("buf_writer", &self.buf_writer)
+            .field("shut_down", &self.shut_down)
+            .field("abort_on_drop", &self.abort_on_drop)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for ObjectWriter {
+    fn drop(&mut self) {
+        if !self.abort_on_drop || self.shut_down {
+            return;
+        }
+        if let (Some(mut buf_writer), Ok(handle)) = (self.buf_writer.take(), Handle::try_current())
+        {
+            handle.spawn(async move {
+                if let Err(error) = buf_writer.abort().await {
+                    log::warn!(
+                        "Failed to abort the upload of a writer dropped without closing: {error}"
+                    );
+                }
+            });
+        }
+    }
+}
+
+impl AsyncWrite for ObjectWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.buf_writer()?.poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.buf_writer()?.poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.shut_down = true;
+        let result = ready!(self.buf_writer()?.poll_shutdown(cx));
+        // a completed or failed shutdown cannot be polled again
+        self.buf_writer = None;
+        Poll::Ready(result)
+    }
+}
+
+#[async_trait]
+impl Closeable for ObjectWriter {
+    async fn close(mut self) -> InnoFileResult<()> {
+        if let Some(mut buf_writer) = self.buf_writer.take() {
+            buf_writer.shutdown().await?;
+        }
+        Ok(())
+    }
+
+    /// Aborts the upload, discarding the parts uploaded so far.
+    async fn abort(mut self) -> InnoFileResult<()> {
+        if let Some(mut buf_writer) = self.buf_writer.take() {
+            if self.shut_down {
+                Err(std::io::Error::other("Writer already shut down"))?
+            }
+            buf_writer.abort().await?;
+        }
+        Ok(())
+    }
+}
+
+/// Converts a listing of objects and common prefixes, as directories, to file statuses.
+fn file_statuses(result: ListResult) -> Vec<FileStatus> {
+    let dirs = result.common_prefixes.into_iter().map(FileStatus::dir);
+    dirs.chain(result.objects.into_iter().map(FileStatus::from))
+        .collect()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
     use object_store::local::LocalFileSystem;
     use tempfile::tempdir;
     use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
@@ -195,4 +519,109 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_create_atomic() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let file_name = "create_atomic.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        let mut writer = file_system.create_atomic(file_name).await?.writer().await?;
+        writer.write_all(b"discarded").await?;
+        assert!(!file_system.exists(file_name).await?);
+        drop(writer);
+        assert!(!file_system.exists(file_name).await?);
+
+        let mut writer = file_system.create_atomic(file_name).await?.writer().await?;
+        writer.write_all(b"published").await?;
+        assert!(!file_system.exists(file_name).await?);
+        writer.close().await?;
+        let file = file_system.open(file_name).await?;
+        assert_eq!(file.metadata().await?.len(), 9);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_shutdown() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        // writers shut down are not aborted when dropped
+        let mut writer = file_system
+            .create_atomic("shutdown.txt")
+            .await?
+            .writer()
+            .await?;
+        writer.write_all(b"published").await?;
+        writer.shutdown().await?;
+        assert!(writer.shutdown().await.is_err());
+        drop(writer);
+        let file = file_system.open("shutdown.txt").await?;
+        assert_eq!(file.metadata().await?.len(), 9);
+
+        // uploads below a file fail, since its parent directory cannot be created
+        let mut writer = file_system
+            .create_atomic("shutdown.txt/failed.txt")
+            .await?
+            .writer()
+            .await?;
+        writer.write_all(b"failed").await?;
+        assert!(writer.shutdown().await.is_err());
+        assert!(writer.close().await.is_ok());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_upload_options() -> InnoFileResult<()> {
+        let content = "Hello, multipart ObjectFS!\n".repeat(8);
+
+        let dir = tempdir()?;
+        let file_name = "upload_options.txt";
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let progress = Arc::new(AtomicU64::new(0));
+        let upload_options = UploadOptions::new()
+            .with_part_size(16)
+            .with_max_concurrency(2)
+            .with_upload_progress({
+                let progress = Arc::clone(&progress);
+                move |uploaded| progress.store(uploaded, Ordering::Relaxed)
+            });
+        let file_system = ObjectFS::from_store("file", store)?.with_upload_options(upload_options);
+
+        let mut writer = file_system.create(file_name).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        // the last part is still buffered, and only counted once uploaded
+        assert!(progress.load(Ordering::Relaxed) < content.len() as u64);
+        writer.close().await?;
+        assert_eq!(progress.load(Ordering::Relaxed), content.len() as u64);
+        let mut buf = String::new();
+        let mut reader = file_system.open(file_name).await?.reader().await?;
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, content);
+
+        // writers of files not created atomically are aborted through the trait, and not completed
+        let mut writer = file_system.make_file("aborted.txt").writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.abort().await?;
+        assert!(!file_system.exists("aborted.txt").await?);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_append() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let store = Arc::new(LocalFileSystem::new_with_prefix(dir.path())?);
+        let file_system = ObjectFS::from_store("file", store)?;
+
+        let error = file_system.append("append.txt").await.unwrap_err();
+        assert!(matches!(error.root(), InnoFileError::AppendNotSupported(_)));
+        assert!(!file_system.exists("append.txt").await?);
+
+        Ok(())
+    }
 }
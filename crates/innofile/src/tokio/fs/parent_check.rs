@@ -0,0 +1,135 @@
+use std::io::ErrorKind;
+
+use async_trait::async_trait;
+
+use crate::{
+    error::{ErrorContext, InnoFileError, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+};
+
+use super::{File, FileSystem};
+
+/// Fails creating files whose parent directory doesn't exist, instead of creating the missing
+/// parents as [`LocalFS`](super::local::LocalFS) does.
+#[derive(Debug)]
+pub struct ParentCheckFS<F> {
+    inner: F,
+}
+
+impl<F: FileSystem> ParentCheckFS<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    async fn check_parent(&self, operation: Operation, path: &str) -> InnoFileResult<()> {
+        match path.rsplit_once('/') {
+            Some((parent, _)) if !parent.is_empty() && !self.inner.exists(parent).await? => {
+                Err(InnoFileError::from(std::io::Error::new(
+                    ErrorKind::NotFound,
+                    format!("Parent directory not found: {parent}"),
+                )))
+                .with_context(|| {
+                    ErrorContext::new(operation)
+                        .with_path(path)
+                        .with_scheme(self.scheme())
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem> FileSystem for ParentCheckFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(path).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.open(path).await
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.check_parent(Operation::Create, path).await?;
+        self.inner.create(path).await
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.check_parent(Operation::Create, path).await?;
+        self.inner.create_new(path).await
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.check_parent(Operation::Create, path).await?;
+        self.inner.create_atomic(path).await
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.check_parent(Operation::Append, path).await?;
+        self.inner.append(path).await
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(path).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir_all(path).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(path).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir_all(path).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_file(path).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.inner.list(path).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.inner.status(path).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use crate::tokio::fs::local::LocalFS;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_parent_check_fs() -> InnoFileResult<()> {
+        let dir = tempdir()?;
+        let parent = dir.path().join("parent");
+        let parent = parent.to_str().unwrap();
+        let path = format!("{parent}/parent_check_fs.txt");
+
+        let file_system = ParentCheckFS::new(LocalFS);
+        let error = file_system.create(&path).await.unwrap_err();
+        assert!(error.is_not_found());
+        assert!(!file_system.exists(parent).await?);
+
+        file_system.create_dir(parent).await?;
+        file_system.create(&path).await?;
+        assert!(file_system.exists(&path).await?);
+
+        Ok(())
+    }
+}
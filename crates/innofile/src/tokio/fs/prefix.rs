@@ -0,0 +1,161 @@
+use async_trait::async_trait;
+
+use crate::{
+    error::InnoFileResult,
+    fs::{
+        prefix::{relative, resolve},
+        FileStatus,
+    },
+};
+
+use super::{File, FileSystem};
+
+/// Roots a [`FileSystem`] at a directory or URI prefix, such as `s3://bucket/tenant-42/`.
+///
+/// Paths are resolved relative to the root, or may be given in full under it. Paths escaping
+/// the root with `..` segments are rejected.
+#[derive(Debug)]
+pub struct PrefixFS<F> {
+    inner: F,
+    root: String,
+}
+
+impl<F: FileSystem> PrefixFS<F> {
+    pub fn new(inner: F, root: impl ToString) -> Self {
+        Self {
+            inner,
+            root: root.to_string(),
+        }
+    }
+
+    pub fn root(&self) -> &str {
+        &self.root
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    /// Resolves `path` to the path it stands for in the inner file system.
+    pub fn resolve(&self, path: &str) -> InnoFileResult<String> {
+        resolve(&self.root, path)
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem> FileSystem for PrefixFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(&self.resolve(path)?).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.open(&self.resolve(path)?).await
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.create(&self.resolve(path)?).await
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.create_new(&self.resolve(path)?).await
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.create_atomic(&self.resolve(path)?).await
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.inner.append(&self.resolve(path)?).await
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(&self.resolve(path)?).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir_all(&self.resolve(path)?).await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(&self.resolve(path)?).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir_all(&self.resolve(path)?).await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_file(&self.resolve(path)?).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        let statuses = self.inner.list(&self.resolve(path)?).await?;
+        Ok(statuses
+            .into_iter()
+            .map(|status| FileStatus {
+                path: relative(&self.root, &status.path),
+                ..status
+            })
+            .collect())
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        let status = self.inner.status(&self.resolve(path)?).await?;
+        Ok(FileStatus {
+            path: path.to_string(),
+            ..status
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    use crate::{error::InnoFileError, tokio::fs::local::LocalFS};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prefix_fs() -> InnoFileResult<()> {
+        let content = "Hello, PrefixFS!\n";
+
+        let dir = tempdir()?;
+        let root = dir.path().join("tenant-42");
+        let file_system = PrefixFS::new(LocalFS, root.to_str().unwrap());
+        let mut writer = file_system
+            .create_new("data/../prefix_fs.txt")
+            .await?
+            .writer()
+            .await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.flush().await?;
+
+        let mut buf = String::new();
+        let path = root.join("prefix_fs.txt");
+        let path = path.to_str().unwrap();
+        let mut reader = LocalFS.open(path).await?.reader().await?;
+        reader.read_to_string(&mut buf).await?;
+        assert_eq!(buf, content);
+        assert!(file_system.exists(path).await?);
+        assert!(file_system.exists("/prefix_fs.txt").await?);
+
+        let statuses = file_system.list("/").await?;
+        let paths = statuses.iter().map(|s| s.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, ["prefix_fs.txt"]);
+        assert!(file_system.exists(paths[0]).await?);
+
+        let error = file_system.open("../tenant-43/prefix_fs.txt").await;
+        assert!(matches!(
+            error.unwrap_err(),
+            InnoFileError::PathOutsideRoot { .. }
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,177 @@
+use std::ops::Range;
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+use crate::{
+    error::{ErrorContext, InnoFileError, InnoFileResult, Operation, ResultExt as _},
+    fs::FileStatus,
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+/// Exposes a [`FileSystem`] for reading only, failing any operation that would modify it with
+/// [`InnoFileError::ReadOnlyFileSystem`].
+#[derive(Debug)]
+pub struct ReadOnlyFS<F> {
+    inner: F,
+}
+
+impl<F: FileSystem> ReadOnlyFS<F> {
+    pub fn new(inner: F) -> Self {
+        Self { inner }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    fn reject<T>(&self, operation: Operation, path: &str) -> InnoFileResult<T> {
+        reject(operation, path, self.scheme())
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem> FileSystem for ReadOnlyFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.inner.exists(path).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        Ok(Box::new(ReadOnlyFile {
+            inner: self.inner.open(path).await?,
+            scheme: self.scheme().to_string(),
+        }))
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Create, path)
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Create, path)
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Create, path)
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        self.reject(Operation::Append, path)
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Create, path)
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Create, path)
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Remove, path)
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Remove, path)
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.reject(Operation::Remove, path)
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.inner.list(path).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.inner.status(path).await
+    }
+}
+
+#[derive(Debug)]
+pub struct ReadOnlyFile {
+    inner: Box<dyn File>,
+    scheme: String,
+}
+
+#[async_trait]
+impl File for ReadOnlyFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    async fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        self.inner.metadata().await
+    }
+
+    async fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        self.inner.reader().await
+    }
+
+    async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        reject(Operation::Write, self.path(), &self.scheme)
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        self.inner.read_range(range).await
+    }
+
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        self.inner.read_ranges(ranges).await
+    }
+}
+
+fn reject<T>(operation: Operation, path: &str, scheme: &str) -> InnoFileResult<T> {
+    Err(InnoFileError::ReadOnlyFileSystem).with_context(|| {
+        ErrorContext::new(operation)
+            .with_path(path)
+            .with_scheme(scheme)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    use crate::tokio::fs::local::LocalFS;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_only_fs() -> InnoFileResult<()> {
+        let content = "Hello, ReadOnlyFS!\n";
+
+        let path = tempdir()?.path().join("read_only_fs.txt");
+        let path = path.to_str().unwrap();
+        let mut writer = LocalFS.create_new(path).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.flush().await?;
+
+        let file_system = ReadOnlyFS::new(LocalFS);
+        let file = file_system.open(path).await?;
+        let mut buf = String::new();
+        file.reader().await?.read_to_string(&mut buf).await?;
+        assert_eq!(buf, content);
+
+        let errors = [
+            file.writer().await.map(|_| ()),
+            file_system.create(path).await.map(|_| ()),
+            file_system.append(path).await.map(|_| ()),
+            file_system.remove_file(path).await,
+        ];
+        for error in errors {
+            let error = error.unwrap_err();
+            assert!(matches!(error.root(), InnoFileError::ReadOnlyFileSystem));
+            assert!(error.is_permission_denied());
+        }
+        assert!(file_system.exists(path).await?);
+
+        Ok(())
+    }
+}
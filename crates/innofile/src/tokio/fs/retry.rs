@@ -0,0 +1,337 @@
+use std::{
+    fmt::{Debug, Formatter},
+    future::Future,
+    io::SeekFrom,
+    ops::Range,
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use tokio::io::{AsyncRead, AsyncSeek, AsyncSeekExt as _, BufReader, ReadBuf};
+
+use crate::{
+    error::{InnoFileError, InnoFileResult},
+    fs::{retry::RetryPolicy, FileStatus},
+};
+
+use super::{File, FileRead, FileSystem, FileWrite, Metadata};
+
+/// Retries the operations of a [`FileSystem`] and its files that fail with transient errors,
+/// according to a [`RetryPolicy`].
+///
+/// Reads are resumed at the current offset on a reopened reader. Writes are not retried, since a
+/// partially written stream can't be safely repeated. Neither are operations that fail when
+/// repeated after an attempt which took effect but whose response was lost, such as
+/// `create_new` or `remove_file`.
+#[derive(Debug)]
+pub struct RetryFS<F> {
+    inner: F,
+    policy: RetryPolicy,
+}
+
+impl<F: FileSystem> RetryFS<F> {
+    pub fn new(inner: F, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+
+    fn make_file(&self, file: Box<dyn File>) -> Box<dyn File> {
+        Box::new(RetryFile::new(file, self.policy.clone()))
+    }
+}
+
+#[async_trait]
+impl<F: FileSystem> FileSystem for RetryFS<F> {
+    fn scheme(&self) -> &str {
+        self.inner.scheme()
+    }
+
+    async fn exists(&self, path: &str) -> InnoFileResult<bool> {
+        self.policy.retry_async(|| self.inner.exists(path)).await
+    }
+
+    async fn open(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry_async(|| self.inner.open(path)).await?;
+        Ok(self.make_file(file))
+    }
+
+    async fn create(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry_async(|| self.inner.create(path)).await?;
+        Ok(self.make_file(file))
+    }
+
+    async fn create_new(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.inner.create_new(path).await?;
+        Ok(self.make_file(file))
+    }
+
+    async fn create_atomic(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self
+            .policy
+            .retry_async(|| self.inner.create_atomic(path))
+            .await?;
+        Ok(self.make_file(file))
+    }
+
+    async fn append(&self, path: &str) -> InnoFileResult<Box<dyn File>> {
+        let file = self.policy.retry_async(|| self.inner.append(path)).await?;
+        Ok(self.make_file(file))
+    }
+
+    async fn create_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.create_dir(path).await
+    }
+
+    async fn create_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.policy
+            .retry_async(|| self.inner.create_dir_all(path))
+            .await
+    }
+
+    async fn remove_dir(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_dir(path).await
+    }
+
+    async fn remove_dir_all(&self, path: &str) -> InnoFileResult<()> {
+        self.policy
+            .retry_async(|| self.inner.remove_dir_all(path))
+            .await
+    }
+
+    async fn remove_file(&self, path: &str) -> InnoFileResult<()> {
+        self.inner.remove_file(path).await
+    }
+
+    async fn list(&self, path: &str) -> InnoFileResult<Vec<FileStatus>> {
+        self.policy.retry_async(|| self.inner.list(path)).await
+    }
+
+    async fn status(&self, path: &str) -> InnoFileResult<FileStatus> {
+        self.policy.retry_async(|| self.inner.status(path)).await
+    }
+}
+
+#[derive(Debug)]
+pub struct RetryFile {
+    inner: Arc<dyn File>,
+    policy: RetryPolicy,
+}
+
+impl RetryFile {
+    pub fn new(inner: impl Into<Arc<dyn File>>, policy: RetryPolicy) -> Self {
+        Self {
+            inner: inner.into(),
+            policy,
+        }
+    }
+}
+
+#[async_trait]
+impl File for RetryFile {
+    fn path(&self) -> &str {
+        self.inner.path()
+    }
+
+    async fn metadata(&self) -> InnoFileResult<Box<dyn Metadata>> {
+        self.policy.retry_async(|| self.inner.metadata()).await
+    }
+
+    async fn reader(&self) -> InnoFileResult<Box<dyn FileRead>> {
+        let reader = self.policy.retry_async(|| self.inner.reader()).await?;
+        Ok(Box::new(BufReader::new(RetryReader {
+            file: Arc::clone(&self.inner),
+            inner: reader,
+            pos: 0,
+            policy: self.policy.clone(),
+            attempt: 1,
+            reopening: None,
+        })))
+    }
+
+    async fn writer(&self) -> InnoFileResult<Box<dyn FileWrite>> {
+        self.policy.retry_async(|| self.inner.writer()).await
+    }
+
+    async fn read_range(&self, range: Range<u64>) -> InnoFileResult<Bytes> {
+        self.policy
+            .retry_async(|| self.inner.read_range(range.clone()))
+            .await
+    }
+
+    async fn read_ranges(&self, ranges: &[Range<u64>]) -> InnoFileResult<Vec<Bytes>> {
+        self.policy
+            .retry_async(|| self.inner.read_ranges(ranges))
+            .await
+    }
+}
+
+type Reopening = Pin<Box<dyn Future<Output = InnoFileResult<Box<dyn FileRead>>> + Send>>;
+
+/// Reader that reopens its file and seeks back to the current offset when a read fails with a
+/// transient error.
+///
+/// Buffered by a [`BufReader`] rather than by the inner reader, so that a buffer is only filled by
+/// a read that succeeded.
+struct RetryReader {
+    file: Arc<dyn File>,
+    inner: Box<dyn FileRead>,
+    pos: u64,
+    policy: RetryPolicy,
+    attempt: u32,
+    reopening: Option<Reopening>,
+}
+
+impl RetryReader {
+    /// Schedules reopening the file after `error` failed the current attempt, if the policy
+    /// allows another one.
+    fn retry(&mut self, error: InnoFileError) -> std::io::Result<()> {
+        if !self.policy.should_retry(&error, self.attempt) {
+            self.attempt = 1;
+            return Err(error.into());
+        }
+        let delay = self.policy.backoff(self.attempt);
+        self.attempt += 1;
+
+        let (file, pos) = (Arc::clone(&self.file), self.pos);
+        self.reopening = Some(Box::pin(async move {
+            tokio::time::sleep(delay).await;
+            let mut inner = file.reader().await?;
+            inner.seek(SeekFrom::Start(pos)).await?;
+            Ok(inner)
+        }));
+        Ok(())
+    }
+
+    /// Drives a scheduled reopening of the file until the inner reader is usable again.
+    fn poll_reopen(&mut self, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        while let Some(reopening) = &mut self.reopening {
+            let result = ready!(reopening.as_mut().poll(cx));
+            self.reopening = None;
+            match result {
+                Ok(inner) => self.inner = inner,
+                Err(error) => self.retry(error)?,
+            }
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl Debug for RetryReader {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryReader")
+            .field("file", &self.file)
+            .field("inner", &self.inner)
+            .field("pos", &self.pos)
+            .field("policy", &self.policy)
+            .field("attempt", &self.attempt)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AsyncRead for RetryReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_reopen(cx))?;
+            let filled = buf.filled().len();
+            match ready!(Pin::new(&mut this.inner).poll_read(cx, buf)) {
+                Ok(()) => {
+                    this.pos += (buf.filled().len() - filled) as u64;
+                    this.attempt = 1;
+                    return Poll::Ready(Ok(()));
+                }
+                Err(error) => this.retry(error.into())?,
+            }
+        }
+    }
+}
+
+impl AsyncSeek for RetryReader {
+    fn start_seek(self: Pin<&mut Self>, position: SeekFrom) -> std::io::Result<()> {
+        let this = self.get_mut();
+        if this.reopening.is_some() {
+            return Err(std::io::Error::other(
+                "Reopening after failed read in progress",
+            ));
+        }
+        Pin::new(&mut this.inner).start_seek(position)
+    }
+
+    fn poll_complete(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<u64>> {
+        let this = self.get_mut();
+        ready!(this.poll_reopen(cx))?;
+        this.pos = ready!(Pin::new(&mut this.inner).poll_complete(cx))?;
+        Poll::Ready(Ok(this.pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::ErrorKind, time::Duration};
+
+    use tempfile::tempdir;
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+    use crate::{
+        fs::faulty::FaultPolicy,
+        tokio::fs::{faulty::FaultyFS, local::LocalFS},
+    };
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_retry_fs() -> InnoFileResult<()> {
+        let content = "Hello, RetryFS! Reads resume where they failed.\n";
+
+        let path = tempdir()?.path().join("retry_fs.txt");
+        let path = path.to_str().unwrap();
+        let mut writer = LocalFS.create_new(path).await?.writer().await?;
+        writer.write_all(content.as_bytes()).await?;
+        writer.flush().await?;
+
+        let faults = FaultPolicy::new().with_failure_every(3).with_short_reads(4);
+        let policy = RetryPolicy::new().with_initial_backoff(Duration::from_millis(1));
+        let file_system = RetryFS::new(FaultyFS::new(LocalFS, faults.clone()), policy);
+        for _ in 0..3 {
+            let mut buf = String::new();
+            let mut reader = file_system.open(path).await?.reader().await?;
+            reader.read_to_string(&mut buf).await?;
+            assert_eq!(buf, content);
+        }
+
+        let file_system = RetryFS::new(FaultyFS::new(LocalFS, faults), RetryPolicy::none());
+        let mut buf = String::new();
+        let mut reader = file_system.open(path).await?.reader().await?;
+        let error = reader.read_to_string(&mut buf).await;
+        assert_eq!(error.unwrap_err().kind(), ErrorKind::ConnectionReset);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retry_fs_non_idempotent() -> InnoFileResult<()> {
+        let path = tempdir()?.path().join("retry_fs.txt");
+        let path = path.to_str().unwrap();
+
+        let faults = FaultPolicy::new().with_failure_every(1);
+        let policy = RetryPolicy::new().with_initial_backoff(Duration::from_millis(1));
+        let file_system = RetryFS::new(FaultyFS::new(LocalFS, faults), policy);
+        let error = file_system.create_new(path).await.unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::ConnectionReset));
+        let error = file_system.remove_file(path).await.unwrap_err();
+        assert_eq!(error.io_error_kind(), Some(ErrorKind::ConnectionReset));
+        assert_eq!(file_system.into_inner().calls(), 2);
+
+        Ok(())
+    }
+}
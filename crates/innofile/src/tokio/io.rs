@@ -6,12 +6,24 @@ use crate::error::InnoFileResult;
 #[async_trait]
 pub trait CloseableBoxed {
     async fn close_boxed(self: Box<Self>) -> InnoFileResult<()>;
+
+    async fn abort_boxed(self: Box<Self>) -> InnoFileResult<()>;
 }
 
 /// A trait for objects to close on `Self`.
 #[async_trait]
 pub trait Closeable: CloseableBoxed + Send {
     async fn close(self) -> InnoFileResult<()>;
+
+    /// Discards what was written instead of completing it, where supported. Only drops `self` by
+    /// default.
+    async fn abort(self) -> InnoFileResult<()>
+    where
+        Self: Sized,
+    {
+        drop(self);
+        Ok(())
+    }
 }
 
 // Default implementation of [`CloseableBoxed`] trait for [`Closeable`] objects.
@@ -21,6 +33,10 @@ impl<T: Closeable> CloseableBoxed for T {
         // Dereferencing `Box<Self>` with `*self` to close on `Self`.
         (*self).close().await
     }
+
+    async fn abort_boxed(self: Box<Self>) -> InnoFileResult<()> {
+        (*self).abort().await
+    }
 }
 
 // Implement [`Closeable`] trait for its `Box`ed types.
@@ -29,6 +45,10 @@ impl<T: Closeable + ?Sized> Closeable for Box<T> {
     async fn close(self) -> InnoFileResult<()> {
         self.close_boxed().await
     }
+
+    async fn abort(self) -> InnoFileResult<()> {
+        self.abort_boxed().await
+    }
 }
 
 #[cfg(test)]
@@ -52,6 +72,9 @@ mod tests {
         let boxed_closer = Box::new(Closer) as Box<dyn Closeable>;
         assert!(boxed_closer.close().await.is_ok());
 
+        let boxed_closer = Box::new(Closer) as Box<dyn Closeable>;
+        assert!(boxed_closer.abort().await.is_ok());
+
         Ok(())
     }
 }
@@ -1,12 +1,66 @@
-use std::path::Path;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher as _, Hasher as _},
+    path::Path,
+};
 
 use fluent_uri::UriRef;
 
+#[cfg(feature = "object_store")]
+use crate::error::InnoFileError;
 use crate::error::InnoFileResult;
 
+#[cfg(any(feature = "orc", feature = "parquet"))]
+pub(crate) mod range;
+pub(crate) mod staging;
+
 pub fn path_extension(path: impl AsRef<str>) -> InnoFileResult<Option<String>> {
     Ok(Path::new(UriRef::parse(path.as_ref())?.path().as_str())
         .extension()
         .and_then(|e| e.to_str())
         .map(|e| e.to_string()))
 }
+
+/// Returns a random number, seeded from the randomly keyed std hasher.
+pub(crate) fn random_u64() -> u64 {
+    RandomState::new().build_hasher().finish()
+}
+
+/// Returns a random number in `[0, 1]`.
+pub(crate) fn random_fraction() -> f64 {
+    random_u64() as f64 / u64::MAX as f64
+}
+
+/// Returns the object store location of `path`, which is either a key or a URI such as
+/// `s3://bucket/key`.
+#[cfg(feature = "object_store")]
+pub(crate) fn object_path(
+    path: &str,
+) -> Result<object_store::path::Path, object_store::path::Error> {
+    let key = match path.split_once("://") {
+        Some((_, rest)) => rest.split_once('/').map_or("", |(_, key)| key),
+        None => path,
+    };
+    object_store::path::Path::parse(key)
+}
+
+/// Returns the object store location of `path` like [`object_path`], rejecting URIs of other
+/// buckets than `bucket`, if known.
+#[cfg(feature = "object_store")]
+pub(crate) fn bucket_object_path(
+    path: &str,
+    bucket: Option<&str>,
+) -> InnoFileResult<object_store::path::Path> {
+    if let (Some(bucket), Some((_, rest))) = (bucket, path.split_once("://")) {
+        let authority = rest
+            .split_once('/')
+            .map_or(rest, |(authority, _)| authority);
+        if authority != bucket {
+            return Err(InnoFileError::PathOutsideRoot {
+                path: path.to_string(),
+                root: bucket.to_string(),
+            });
+        }
+    }
+    Ok(object_path(path)?)
+}
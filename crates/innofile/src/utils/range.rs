@@ -0,0 +1,73 @@
+use std::ops::Range;
+
+use bytes::Bytes;
+
+/// Size of the file tail fetched at once to serve reads of the footer.
+pub(crate) const FOOTER_SIZE: u64 = 64 * 1024;
+
+/// Maximum gap between two ranges to merge them into a single fetch.
+pub(crate) const COALESCE_GAP: u64 = 1024 * 1024;
+
+/// Bytes of a file starting at a given offset.
+#[derive(Debug, Clone)]
+pub(crate) struct Chunk {
+    start: u64,
+    data: Bytes,
+}
+
+impl Chunk {
+    pub(crate) fn new(start: u64, data: Bytes) -> Self {
+        Self { start, data }
+    }
+
+    /// Slices the bytes from `start` to `end`, or to the end of the chunk if `end` is `None`.
+    pub(crate) fn slice(&self, start: u64, end: Option<u64>) -> Option<Bytes> {
+        let chunk_end = self.start + self.data.len() as u64;
+        let end = end.unwrap_or(chunk_end);
+        (self.start <= start && start < chunk_end && end <= chunk_end).then(|| {
+            self.data
+                .slice((start - self.start) as usize..(end - self.start) as usize)
+        })
+    }
+}
+
+/// Sorts `ranges` and merges those less than [`COALESCE_GAP`] apart.
+pub(crate) fn coalesce_ranges(ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut ranges = ranges
+        .iter()
+        .filter(|range| !range.is_empty())
+        .cloned()
+        .collect::<Vec<_>>();
+    ranges.sort_unstable_by_key(|range| range.start);
+
+    let mut coalesced: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match coalesced.last_mut() {
+            Some(last) if range.start <= last.end + COALESCE_GAP => {
+                last.end = last.end.max(range.end);
+            }
+            _ => coalesced.push(range),
+        }
+    }
+    coalesced
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesce_ranges() {
+        let ranges = [
+            20..30,
+            0..10,
+            10..15,
+            40..40,
+            COALESCE_GAP + 40..COALESCE_GAP + 50,
+        ];
+        assert_eq!(
+            coalesce_ranges(&ranges),
+            vec![0..30, COALESCE_GAP + 40..COALESCE_GAP + 50]
+        );
+    }
+}
@@ -0,0 +1,76 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::random_u64;
+
+/// A temporary path next to a local file, renamed onto the file once published and removed when
+/// dropped unpublished.
+#[derive(Debug)]
+pub(crate) struct Staging {
+    path: PathBuf,
+    temp_path: PathBuf,
+    published: AtomicBool,
+}
+
+impl Staging {
+    pub(crate) fn new(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref().to_path_buf();
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        let temp_name = format!(".{}.{:016x}.tmp", file_name, random_u64());
+        Self {
+            temp_path: path.with_file_name(temp_name),
+            path,
+            published: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn temp_path(&self) -> &Path {
+        &self.temp_path
+    }
+
+    /// Renames the temporary path onto the file, replacing it if it exists.
+    pub(crate) fn publish(&self) -> std::io::Result<()> {
+        fs::rename(&self.temp_path, &self.path)?;
+        self.published.store(true, Ordering::Release);
+        Ok(())
+    }
+}
+
+impl Drop for Staging {
+    fn drop(&mut self) {
+        if !self.published.load(Ordering::Acquire) {
+            _ = fs::remove_file(&self.temp_path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::tempdir;
+
+    use super::*;
+
+    #[test]
+    fn test_staging() -> std::io::Result<()> {
+        let dir = tempdir()?;
+        let path = dir.path().join("staging.txt");
+
+        let staging = Staging::new(&path);
+        assert_eq!(staging.temp_path().parent(), Some(dir.path()));
+        fs::write(staging.temp_path(), "discarded")?;
+        let temp_path = staging.temp_path().to_path_buf();
+        drop(staging);
+        assert!(!temp_path.exists());
+
+        let staging = Staging::new(&path);
+        fs::write(staging.temp_path(), "published")?;
+        staging.publish()?;
+        drop(staging);
+        assert_eq!(fs::read_to_string(&path)?, "published");
+
+        Ok(())
+    }
+}